@@ -0,0 +1,701 @@
+use std::fmt::Write;
+
+use anyhow::anyhow;
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+use tracing::warn;
+
+use crate::{
+    error::DatabaseError,
+    frame::{Frame, FrameError},
+};
+
+const CRLF: &[u8] = b"\r\n";
+
+/// Upper bound on the capacity reserved for an aggregate before its elements arrive.
+const MAX_PREALLOCATED_ELEMENTS: usize = 1024;
+
+/// Upper bound on the capacity reserved at once for a blob payload that has yet to arrive.
+const MAX_PREALLOCATED_BLOB_BYTES: usize = 64 * 1024;
+
+/// The RESP dialect negotiated with the peer through `HELLO`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum Protocol {
+    /// Every connection starts out speaking RESP2, as stock redis clients expect.
+    #[default]
+    Resp2,
+    Resp3,
+}
+
+impl Protocol {
+    pub(crate) fn version(&self) -> i64 {
+        match self {
+            Protocol::Resp2 => 2,
+            Protocol::Resp3 => 3,
+        }
+    }
+}
+
+impl TryFrom<i64> for Protocol {
+    type Error = anyhow::Error;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        match value {
+            2 => Ok(Protocol::Resp2),
+            3 => Ok(Protocol::Resp3),
+            v => Err(anyhow!("Unsupported protocol version: {v}")),
+        }
+    }
+}
+
+/// Bounds on what a peer may send, protecting the server from unbounded buffer growth,
+/// oversized allocations and deeply nested frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ProtocolLimits {
+    /// Maximum length of a bulk string payload (`proto-max-bulk-len`).
+    pub(crate) max_bulk_len: usize,
+    /// Maximum number of elements in a single aggregate.
+    pub(crate) max_elements: usize,
+    /// Maximum number of aggregates nested in each other.
+    pub(crate) max_depth: usize,
+    /// Maximum length of a single line, such as an inline command or a frame header.
+    pub(crate) max_line_len: usize,
+}
+
+impl Default for ProtocolLimits {
+    fn default() -> Self {
+        // mirrors the redis defaults where there is one
+        Self {
+            max_bulk_len: 512 * 1024 * 1024,
+            max_elements: 1024 * 1024,
+            max_depth: 128,
+            max_line_len: 64 * 1024,
+        }
+    }
+}
+
+/// Encodes and decodes RESP frames.
+///
+/// Decoding is incremental: every complete element is consumed from the buffer as soon as it
+/// arrives and partially received aggregates are kept in the codec, so no byte is scanned
+/// twice when a frame spans several reads. Bulk payloads are split off the read buffer
+/// without copying.
+///
+/// Encoding follows the negotiated [`Protocol`], down-converting RESP3-only frames for RESP2
+/// peers.
+///
+/// Frames violating the codec's [`ProtocolLimits`] are rejected with
+/// [`FrameError::LimitExceeded`] before any memory is reserved for them. Frames within the
+/// limits only have memory reserved in bounded steps as their bytes arrive, so a header
+/// alone cannot make the codec allocate what it declares.
+#[derive(Debug, Default)]
+pub(crate) struct FrameCodec {
+    protocol: Protocol,
+
+    limits: ProtocolLimits,
+
+    /// Aggregates whose elements are still being received, innermost last.
+    stack: Vec<PendingAggregate>,
+
+    /// A blob whose header has been consumed but whose payload has not fully arrived.
+    pending_blob: Option<(BlobKind, usize)>,
+
+    /// Number of bytes at the front of the buffer already searched for a line terminator.
+    searched: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum AggregateKind {
+    Array,
+    Set,
+    Push,
+    Map,
+    Attribute,
+}
+
+#[derive(Debug)]
+struct PendingAggregate {
+    kind: AggregateKind,
+    remaining: usize,
+    items: Vec<Frame>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum BlobKind {
+    Bulk,
+    BulkError,
+    Verbatim,
+}
+
+/// The outcome of decoding a single element.
+enum Step {
+    Frame(Frame),
+    /// The header of an aggregate with the given number of nested frames.
+    Aggregate(AggregateKind, usize),
+    /// The header of a blob whose payload has the given length.
+    Blob(BlobKind, usize),
+}
+
+impl FrameCodec {
+    pub(crate) fn with_limits(limits: ProtocolLimits) -> Self {
+        Self {
+            limits,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn limits(&self) -> ProtocolLimits {
+        self.limits
+    }
+
+    pub(crate) fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+
+    pub(crate) fn set_protocol(&mut self, protocol: Protocol) {
+        self.protocol = protocol;
+    }
+
+    /// Whether part of a frame has been consumed from the buffer but not yet returned.
+    pub(crate) fn has_partial_frame(&self) -> bool {
+        !self.stack.is_empty() || self.pending_blob.is_some()
+    }
+
+    /// Decodes the next frame from `src`, returning `None` when more data is needed.
+    pub(crate) fn decode_frame(&mut self, src: &mut BytesMut) -> Result<Option<Frame>, FrameError> {
+        loop {
+            let step = if let Some((kind, len)) = self.pending_blob {
+                match decode_blob(src, kind, len)? {
+                    Some(frame) => {
+                        self.pending_blob = None;
+                        Step::Frame(frame)
+                    }
+                    None => return Ok(None),
+                }
+            } else if self.stack.is_empty() && src.first().is_some_and(|b| !Frame::is_type_byte(*b))
+            {
+                match self.decode_inline(src)? {
+                    // blank lines are skipped, as redis does
+                    Some(args) if args.is_empty() => continue,
+                    Some(args) => Step::Frame(Frame::Array(args)),
+                    None => return Ok(None),
+                }
+            } else {
+                match self.decode_element(src)? {
+                    Some(step) => step,
+                    None => return Ok(None),
+                }
+            };
+
+            let mut frame = match step {
+                Step::Frame(frame) => frame,
+                Step::Blob(kind, len) => {
+                    self.pending_blob = Some((kind, len));
+                    continue;
+                }
+                Step::Aggregate(kind, 0) => kind.build(vec![]),
+                Step::Aggregate(kind, len) => {
+                    if self.stack.len() >= self.limits.max_depth {
+                        return Err(FrameError::LimitExceeded(format!(
+                            "aggregates nested deeper than {} levels",
+                            self.limits.max_depth
+                        )));
+                    }
+
+                    // the declared length is only trusted up to a point, the rest is
+                    // allocated as elements arrive
+                    self.stack.push(PendingAggregate {
+                        kind,
+                        remaining: len,
+                        items: Vec::with_capacity(len.min(MAX_PREALLOCATED_ELEMENTS)),
+                    });
+                    continue;
+                }
+            };
+
+            // hand the completed frame to its parent, completing parents along the way
+            loop {
+                let Some(parent) = self.stack.last_mut() else {
+                    return Ok(Some(frame));
+                };
+
+                parent.items.push(frame);
+                parent.remaining -= 1;
+
+                if parent.remaining > 0 {
+                    break;
+                }
+
+                // the parent was just checked to exist
+                let parent = self.stack.pop().unwrap();
+                frame = parent.kind.build(parent.items);
+            }
+        }
+    }
+
+    /// Decodes a scalar or the header of a blob or aggregate, consuming its line from `src`.
+    fn decode_element(&mut self, src: &mut BytesMut) -> Result<Option<Step>, FrameError> {
+        // the line starts after the type byte
+        let Some(end) = self.find_line_end(src, 1, b"\r\n")? else {
+            return Ok(None);
+        };
+
+        let line = &src[1..end];
+        let limits = self.limits;
+
+        let bulk_len = |line: &[u8]| match get_length(line)? {
+            len if len > limits.max_bulk_len => Err(FrameError::LimitExceeded(format!(
+                "invalid bulk length {len}, the maximum is {}",
+                limits.max_bulk_len
+            ))),
+            len => Ok(len),
+        };
+
+        // maps and attributes hold two frames per declared entry
+        let element_count = |line: &[u8], per_entry: usize| {
+            let len = get_length(line)?;
+
+            match len.checked_mul(per_entry) {
+                Some(n) if n <= limits.max_elements => Ok(n),
+                _ => Err(FrameError::LimitExceeded(format!(
+                    "invalid multibulk length {len}, the maximum is {}",
+                    limits.max_elements
+                ))),
+            }
+        };
+
+        let step = match src[0] {
+            b'+' => Step::Frame(Frame::Simple(get_text(line)?)),
+            b'-' => Step::Frame(Frame::Error(get_text(line)?)),
+            b':' => Step::Frame(Frame::Integer(get_integer(line)?)),
+            b'_' if line.is_empty() => Step::Frame(Frame::Null),
+            b'#' => Step::Frame(Frame::Boolean(get_boolean(line)?)),
+            b',' => Step::Frame(Frame::Double(get_double(line)?)),
+            b'(' => Step::Frame(Frame::BigNumber(get_big_number(line)?)),
+            // RESP2 encodes null bulk strings and null arrays with a length of -1
+            b'$' if line == b"-1" => Step::Frame(Frame::Null),
+            b'*' if line == b"-1" => Step::Frame(Frame::NullArray),
+            b'$' => Step::Blob(BlobKind::Bulk, bulk_len(line)?),
+            b'!' => Step::Blob(BlobKind::BulkError, bulk_len(line)?),
+            b'=' => Step::Blob(BlobKind::Verbatim, bulk_len(line)?),
+            b'*' => Step::Aggregate(AggregateKind::Array, element_count(line, 1)?),
+            b'~' => Step::Aggregate(AggregateKind::Set, element_count(line, 1)?),
+            b'>' => Step::Aggregate(AggregateKind::Push, element_count(line, 1)?),
+            b'%' => Step::Aggregate(AggregateKind::Map, element_count(line, 2)?),
+            // the attribute pairs are followed by the frame they describe
+            b'|' => Step::Aggregate(AggregateKind::Attribute, element_count(line, 2)? + 1),
+            unsupported => {
+                return Err(FrameError::ProtocolError(format!(
+                    "Invalid frame type. Byte: {unsupported:?}. Bytes: {line:?}"
+                )))
+            }
+        };
+
+        src.advance(end + CRLF.len());
+        Ok(Some(step))
+    }
+
+    /// Decodes an inline command, as typed into telnet or netcat, into bulk strings.
+    ///
+    /// Arguments are separated by whitespace and the line is terminated by either CRLF or LF.
+    /// Double quoted arguments support the usual escape sequences (`\n`, `\"`, `\x41`, ...),
+    /// while single quoted arguments only support `\'`.
+    fn decode_inline(&mut self, src: &mut BytesMut) -> Result<Option<Vec<Frame>>, FrameError> {
+        let Some(end) = self.find_line_end(src, 0, b"\n")? else {
+            return Ok(None);
+        };
+
+        let line = src[..end].strip_suffix(b"\r").unwrap_or(&src[..end]);
+        let args = split_inline_args(line)?;
+
+        src.advance(end + 1);
+        Ok(Some(args.into_iter().map(Frame::Bulk).collect()))
+    }
+
+    /// Finds the position of `terminator` at or after `from`, resuming the search where the
+    /// last unsuccessful call stopped.
+    fn find_line_end(
+        &mut self,
+        src: &[u8],
+        from: usize,
+        terminator: &[u8],
+    ) -> Result<Option<usize>, FrameError> {
+        let start = self.searched.max(from);
+
+        let end = match src
+            .get(start..)
+            .and_then(|tail| tail.windows(terminator.len()).position(|w| w == terminator))
+        {
+            Some(i) => {
+                self.searched = 0;
+                Some(start + i)
+            }
+            None => {
+                // the first byte of a split terminator may already be buffered
+                self.searched = src.len().saturating_sub(terminator.len() - 1);
+                None
+            }
+        };
+
+        if end.unwrap_or(src.len()) > self.limits.max_line_len {
+            return Err(FrameError::LimitExceeded(format!(
+                "line exceeds {} bytes",
+                self.limits.max_line_len
+            )));
+        }
+
+        Ok(end)
+    }
+
+    fn encode_frame(&self, frame: &Frame, dst: &mut BytesMut) {
+        let resp2 = self.protocol == Protocol::Resp2;
+
+        match frame {
+            Frame::Simple(s) => {
+                dst.put_u8(b'+');
+                dst.put_slice(s.as_bytes());
+                dst.put_slice(CRLF);
+            }
+            Frame::Error(e) => {
+                dst.put_u8(b'-');
+                dst.put_slice(e.as_bytes());
+                dst.put_slice(CRLF);
+            }
+            Frame::Integer(i) => encode_header(dst, b':', *i),
+            Frame::Bulk(bs) => encode_blob(dst, b'$', bs),
+            Frame::Null if resp2 => dst.put_slice(b"$-1\r\n"),
+            Frame::NullArray if resp2 => dst.put_slice(b"*-1\r\n"),
+            Frame::Null | Frame::NullArray => dst.put_slice(b"_\r\n"),
+            Frame::Boolean(b) if resp2 => encode_header(dst, b':', *b as i64),
+            Frame::Boolean(b) => dst.put_slice(if *b { b"#t\r\n" } else { b"#f\r\n" }),
+            Frame::Double(d) if resp2 => encode_blob(dst, b'$', format_double(*d).as_bytes()),
+            Frame::Double(d) => {
+                dst.put_u8(b',');
+                dst.put_slice(format_double(*d).as_bytes());
+                dst.put_slice(CRLF);
+            }
+            Frame::BigNumber(n) if resp2 => encode_blob(dst, b'$', n.as_bytes()),
+            Frame::BigNumber(n) => {
+                dst.put_u8(b'(');
+                dst.put_slice(n.as_bytes());
+                dst.put_slice(CRLF);
+            }
+            Frame::BulkError(bs) if resp2 => {
+                // simple errors cannot span multiple lines
+                let e = String::from_utf8_lossy(bs).replace(['\r', '\n'], " ");
+                self.encode_frame(&Frame::Error(e), dst);
+            }
+            Frame::BulkError(bs) => encode_blob(dst, b'!', bs),
+            Frame::Verbatim(_, bs) if resp2 => encode_blob(dst, b'$', bs),
+            Frame::Verbatim(format, bs) => {
+                encode_header(dst, b'=', (format.len() + 1 + bs.len()) as i64);
+                dst.put_slice(format.as_bytes());
+                dst.put_u8(b':');
+                dst.put_slice(bs);
+                dst.put_slice(CRLF);
+            }
+            Frame::Array(v) | Frame::Set(v) | Frame::Push(v) => {
+                // RESP2 only knows flat arrays
+                let prefix = match frame {
+                    _ if resp2 => b'*',
+                    Frame::Set(_) => b'~',
+                    Frame::Push(_) => b'>',
+                    _ => b'*',
+                };
+
+                encode_header(dst, prefix, v.len() as i64);
+                v.iter().for_each(|f| self.encode_frame(f, dst));
+            }
+            Frame::Map(m) => {
+                // RESP2 receives maps as an array of alternating keys and values
+                if resp2 {
+                    encode_header(dst, b'*', 2 * m.len() as i64);
+                } else {
+                    encode_header(dst, b'%', m.len() as i64);
+                }
+                self.encode_pairs(m, dst);
+            }
+            Frame::Attribute(_, f) if resp2 => {
+                // attributes are out-of-band metadata, RESP2 clients only see the reply
+                self.encode_frame(f, dst);
+            }
+            Frame::Attribute(m, f) => {
+                encode_header(dst, b'|', m.len() as i64);
+                self.encode_pairs(m, dst);
+                self.encode_frame(f, dst);
+            }
+        }
+    }
+
+    fn encode_pairs(&self, pairs: &[(Frame, Frame)], dst: &mut BytesMut) {
+        for (k, v) in pairs.iter() {
+            self.encode_frame(k, dst);
+            self.encode_frame(v, dst);
+        }
+    }
+}
+
+impl Decoder for FrameCodec {
+    type Item = Frame;
+    type Error = DatabaseError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        Ok(self.decode_frame(src)?)
+    }
+}
+
+impl Encoder<&Frame> for FrameCodec {
+    type Error = DatabaseError;
+
+    fn encode(&mut self, frame: &Frame, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        self.encode_frame(frame, dst);
+        Ok(())
+    }
+}
+
+impl AggregateKind {
+    fn build(self, mut items: Vec<Frame>) -> Frame {
+        match self {
+            AggregateKind::Array => Frame::Array(items),
+            AggregateKind::Set => Frame::Set(items),
+            AggregateKind::Push => Frame::Push(items),
+            AggregateKind::Map => Frame::Map(into_pairs(items)),
+            AggregateKind::Attribute => {
+                // an attribute always holds at least the frame it annotates
+                let frame = items.pop().unwrap();
+                Frame::Attribute(into_pairs(items), Box::new(frame))
+            }
+        }
+    }
+}
+
+fn into_pairs(items: Vec<Frame>) -> Vec<(Frame, Frame)> {
+    let mut pairs = Vec::with_capacity(items.len() / 2);
+    let mut items = items.into_iter();
+
+    while let (Some(k), Some(v)) = (items.next(), items.next()) {
+        pairs.push((k, v));
+    }
+
+    pairs
+}
+
+/// Splits a blob payload off the buffer once it has fully arrived.
+fn decode_blob(
+    src: &mut BytesMut,
+    kind: BlobKind,
+    len: usize,
+) -> Result<Option<Frame>, FrameError> {
+    let n = len + CRLF.len();
+
+    if src.len() < n {
+        // make room for the next part of the payload, a bounded step at a time
+        src.reserve((n - src.len()).min(MAX_PREALLOCATED_BLOB_BYTES));
+        return Ok(None);
+    }
+
+    if &src[len..n] != CRLF {
+        return Err(FrameError::ParsingError("Invalid terminator.".to_string()));
+    }
+
+    let data = src.split_to(len).freeze();
+    src.advance(CRLF.len());
+
+    match kind {
+        BlobKind::Bulk => Ok(Some(Frame::Bulk(data))),
+        BlobKind::BulkError => Ok(Some(Frame::BulkError(data))),
+        BlobKind::Verbatim => match data.get(..4) {
+            Some(prefix)
+                if prefix[3] == b':' && prefix[..3].iter().all(u8::is_ascii_alphanumeric) =>
+            {
+                // alphanumeric bytes are valid utf8
+                let format = String::from_utf8_lossy(&prefix[..3]).into_owned();
+                Ok(Some(Frame::Verbatim(format, data.slice(4..))))
+            }
+            _ => Err(FrameError::ProtocolError(
+                "Verbatim string is missing its format prefix.".to_string(),
+            )),
+        },
+    }
+}
+
+fn encode_header(dst: &mut BytesMut, prefix: u8, value: i64) {
+    dst.put_u8(prefix);
+    // writing to a `BytesMut` cannot fail
+    let _ = write!(dst, "{value}");
+    dst.put_slice(CRLF);
+}
+
+fn encode_blob(dst: &mut BytesMut, prefix: u8, bs: &[u8]) {
+    encode_header(dst, prefix, bs.len() as i64);
+    dst.put_slice(bs);
+    dst.put_slice(CRLF);
+}
+
+/// RESP3 spells out the special floating point values in lowercase.
+fn format_double(d: f64) -> String {
+    if d.is_nan() {
+        "nan".to_string()
+    } else if d.is_infinite() {
+        if d > 0.0 { "inf" } else { "-inf" }.to_string()
+    } else {
+        d.to_string()
+    }
+}
+
+/// Reads the non-empty line of a simple string or simple error.
+fn get_text(l: &[u8]) -> Result<String, FrameError> {
+    if l.is_empty() || l.contains(&b'\n') || l.contains(&b'\r') {
+        return Err(FrameError::ProtocolError(format!(
+            "Invalid simple frame. Bytes: {l:?}"
+        )));
+    }
+
+    String::from_utf8(l.to_vec()).map_err(|e| {
+        warn!(error = %e, "converting bytes to string failed");
+        FrameError::ParsingError("Byte to string coversion failed.".to_string())
+    })
+}
+
+fn get_length(l: &[u8]) -> Result<usize, FrameError> {
+    use atoi::FromRadix10Checked;
+
+    match usize::from_radix_10_checked(l) {
+        (Some(d), used) if used > 0 && used == l.len() => Ok(d),
+        _ => Err(FrameError::ProtocolError(
+            "Invalid frame format".to_string(),
+        )),
+    }
+}
+
+fn get_integer(l: &[u8]) -> Result<i64, FrameError> {
+    use atoi::FromRadix10SignedChecked;
+
+    match i64::from_radix_10_signed_checked(l) {
+        (Some(d), used) if used == l.len() && l.iter().any(u8::is_ascii_digit) => Ok(d),
+        _ => Err(FrameError::ProtocolError(
+            "Invalid frame format".to_string(),
+        )),
+    }
+}
+
+fn get_boolean(l: &[u8]) -> Result<bool, FrameError> {
+    match l {
+        b"t" => Ok(true),
+        b"f" => Ok(false),
+        l => Err(FrameError::ProtocolError(format!(
+            "Invalid boolean. Bytes: {l:?}"
+        ))),
+    }
+}
+
+fn get_double(l: &[u8]) -> Result<f64, FrameError> {
+    std::str::from_utf8(l)
+        .ok()
+        .and_then(|s| s.parse::<f64>().ok())
+        .ok_or_else(|| FrameError::ProtocolError(format!("Invalid double. Bytes: {l:?}")))
+}
+
+fn get_big_number(l: &[u8]) -> Result<String, FrameError> {
+    let digits = l.strip_prefix(b"-").or(l.strip_prefix(b"+")).unwrap_or(l);
+
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+        return Err(FrameError::ProtocolError(format!(
+            "Invalid big number. Bytes: {l:?}"
+        )));
+    }
+
+    // only ascii digits and a sign remain, so the conversion cannot fail
+    Ok(String::from_utf8_lossy(l).into_owned())
+}
+
+fn split_inline_args(line: &[u8]) -> Result<Vec<Bytes>, FrameError> {
+    let unbalanced = || FrameError::ProtocolError("unbalanced quotes in request".to_string());
+
+    let mut args = vec![];
+    let mut i = 0;
+
+    loop {
+        while i < line.len() && line[i].is_ascii_whitespace() {
+            i += 1;
+        }
+
+        if i == line.len() {
+            return Ok(args);
+        }
+
+        let mut arg = vec![];
+
+        match line[i] {
+            b'"' => {
+                i += 1;
+                loop {
+                    match line.get(i..) {
+                        Some([b'\\', b'x', h, l, ..])
+                            if h.is_ascii_hexdigit() && l.is_ascii_hexdigit() =>
+                        {
+                            let hex = [*h, *l];
+                            // both bytes are ascii hex digits
+                            let s = std::str::from_utf8(&hex).unwrap();
+                            arg.push(u8::from_str_radix(s, 16).unwrap());
+                            i += 4;
+                        }
+                        Some([b'\\', c, ..]) => {
+                            arg.push(match c {
+                                b'n' => b'\n',
+                                b'r' => b'\r',
+                                b't' => b'\t',
+                                b'b' => 0x08,
+                                b'a' => 0x07,
+                                c => *c,
+                            });
+                            i += 2;
+                        }
+                        Some([b'"', ..]) => {
+                            i += 1;
+                            break;
+                        }
+                        Some([c, ..]) => {
+                            arg.push(*c);
+                            i += 1;
+                        }
+                        _ => return Err(unbalanced()),
+                    }
+                }
+            }
+            b'\'' => {
+                i += 1;
+                loop {
+                    match line.get(i..) {
+                        Some([b'\\', b'\'', ..]) => {
+                            arg.push(b'\'');
+                            i += 2;
+                        }
+                        Some([b'\'', ..]) => {
+                            i += 1;
+                            break;
+                        }
+                        Some([c, ..]) => {
+                            arg.push(*c);
+                            i += 1;
+                        }
+                        _ => return Err(unbalanced()),
+                    }
+                }
+            }
+            _ => {
+                while i < line.len() && !line[i].is_ascii_whitespace() {
+                    arg.push(line[i]);
+                    i += 1;
+                }
+            }
+        }
+
+        // a closing quote must be followed by whitespace or the end of the line
+        if i < line.len() && !line[i].is_ascii_whitespace() {
+            return Err(unbalanced());
+        }
+
+        args.push(Bytes::from(arg));
+    }
+}
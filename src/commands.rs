@@ -5,31 +5,248 @@ use bytes::Bytes;
 use std::pin::Pin;
 use tokio_stream::Stream;
 
-use crate::{connection::Connection, frame::Frame, parse::Parse};
+use thiserror::Error;
+
+use crate::{
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::{Parse, ParseError},
+};
 
 #[cfg(feature = "server")]
 use crate::server::{database::database::Database, shutdown_listener::ShutdownListener};
 
+pub(crate) mod append;
+pub(crate) mod bitfield;
+pub(crate) mod bits;
+pub(crate) mod bpop;
+pub(crate) mod copy;
+pub(crate) mod del;
+pub(crate) mod exists;
+pub(crate) mod expire;
 pub(crate) mod get;
+pub(crate) mod getdel;
+pub(crate) mod getex;
+pub(crate) mod getset;
+pub(crate) mod hdel;
+pub(crate) mod hello;
+pub(crate) mod hexpire;
+pub(crate) mod hget;
+pub(crate) mod hincrby;
+pub(crate) mod hscan;
+pub(crate) mod hset;
+pub(crate) mod incr;
+pub(crate) mod info;
+pub(crate) mod key_type;
+pub(crate) mod lindex;
+pub(crate) mod llen;
+pub(crate) mod lmove;
+pub(crate) mod lrange;
+pub(crate) mod lrem;
+pub(crate) mod lset;
+pub(crate) mod ltrim;
+pub(crate) mod mget;
+pub(crate) mod mset;
+pub(crate) mod object;
+pub(crate) mod persist;
 pub(crate) mod ping;
+pub(crate) mod pop;
 pub(crate) mod publish;
+pub(crate) mod push;
+pub(crate) mod range;
+pub(crate) mod rename;
+pub(crate) mod sadd;
 pub(crate) mod set;
+pub(crate) mod setop;
+pub(crate) mod smembers;
+pub(crate) mod srandmember;
+pub(crate) mod strlen;
 pub(crate) mod subscribe;
+pub(crate) mod ttl;
+pub(crate) mod xadd;
+pub(crate) mod xgroup;
+pub(crate) mod xpending;
+pub(crate) mod xrange;
+pub(crate) mod xread;
+pub(crate) mod zadd;
+pub(crate) mod zpop;
+pub(crate) mod zrange;
+pub(crate) mod zrank;
+pub(crate) mod zstore;
 
+use append::Append;
+use bitfield::BitField;
+use bits::{BitCount, BitOp, BitPos, GetBit, SetBit};
+use bpop::{BLMove, BLPop, BRPop};
+use copy::CopyKey;
+use del::{Del, Unlink};
+use exists::Exists;
+use expire::{Expire, ExpireAt, PExpire, PExpireAt};
 use get::Get;
+use getdel::GetDel;
+use getex::GetEx;
+use getset::GetSet;
+use hdel::HDel;
+use hello::Hello;
+use hexpire::{HExpire, HPersist, HTtl};
+use hget::{HGet, HGetAll, HMGet};
+use hincrby::HIncrBy;
+use hscan::HScan;
+use hset::HSet;
+use incr::{Decr, DecrBy, Incr, IncrBy, IncrByFloat};
+use info::Info;
+use key_type::Type;
+use lindex::LIndex;
+use llen::LLen;
+use lmove::LMove;
+use lrange::LRange;
+use lrem::LRem;
+use lset::LSet;
+use ltrim::LTrim;
+use mget::MGet;
+use mset::{MSet, MSetNx};
+use object::Object;
+use persist::Persist;
 use ping::Ping;
+use pop::{LPop, RPop};
 use publish::Publish;
+use push::{LPush, RPush};
+use range::{GetRange, SetRange};
+use rename::{Rename, RenameNx};
+use sadd::{SAdd, SRem};
 use set::Set;
-use subscribe::Subscribe;
+use setop::{SDiff, SDiffStore, SInter, SInterStore, SUnion, SUnionStore};
+use smembers::{SCard, SIsMember, SMembers};
+use srandmember::{SPop, SRandMember};
+use strlen::StrLen;
+use subscribe::{PSubscribe, PUnsubscribe, Subscribe, Unsubscribe};
+use ttl::{ExpireTime, PExpireTime, PTtl, Ttl};
+use xadd::{XAdd, XLen, XTrim};
+use xgroup::{XAck, XGroup};
+use xpending::{XAutoClaim, XClaim, XPending};
+use xrange::{XRange, XRevRange};
+use xread::{XRead, XReadGroup};
+use zadd::{ZAdd, ZIncrBy, ZRem};
+use zpop::{BZPopMax, BZPopMin, ZPopMax, ZPopMin};
+use zrange::{ZRange, ZRangeByScore};
+use zrank::{ZCard, ZRank, ZRevRank, ZScore};
+use zstore::{ZInterStore, ZUnionStore};
 
-type MessageStream = Pin<Box<dyn Stream<Item = Bytes> + Send + Sync>>;
+type MessageStream<T = Bytes> = Pin<Box<dyn Stream<Item = T> + Send + Sync>>;
 
 pub(crate) enum SupportedCommand {
+    Append(Append),
+    BitCount(BitCount),
+    BitField(BitField),
+    BitOp(BitOp),
+    BitPos(BitPos),
+    BLMove(BLMove),
+    BLPop(BLPop),
+    BRPop(BRPop),
+    BZPopMax(BZPopMax),
+    BZPopMin(BZPopMin),
+    Copy(CopyKey),
+    Decr(Decr),
+    DecrBy(DecrBy),
+    Del(Del),
+    Exists(Exists),
+    Expire(Expire),
+    ExpireAt(ExpireAt),
+    ExpireTime(ExpireTime),
     Get(Get),
+    GetBit(GetBit),
+    GetDel(GetDel),
+    GetEx(GetEx),
+    GetRange(GetRange),
+    GetSet(GetSet),
+    HDel(HDel),
+    Hello(Hello),
+    HExpire(HExpire),
+    HGet(HGet),
+    HGetAll(HGetAll),
+    HIncrBy(HIncrBy),
+    HMGet(HMGet),
+    HPersist(HPersist),
+    HScan(HScan),
+    HSet(HSet),
+    HTtl(HTtl),
+    Incr(Incr),
+    IncrBy(IncrBy),
+    IncrByFloat(IncrByFloat),
+    Info(Info),
+    LIndex(LIndex),
+    LLen(LLen),
+    LMove(LMove),
+    LPop(LPop),
+    LPush(LPush),
+    LRange(LRange),
+    LRem(LRem),
+    LSet(LSet),
+    LTrim(LTrim),
+    MGet(MGet),
+    MSet(MSet),
+    MSetNx(MSetNx),
+    Object(Object),
+    Persist(Persist),
+    PExpire(PExpire),
+    PExpireAt(PExpireAt),
+    PExpireTime(PExpireTime),
     Ping(Ping),
+    PSubscribe(PSubscribe),
+    PTtl(PTtl),
     Publish(Publish),
+    PUnsubscribe(PUnsubscribe),
+    Rename(Rename),
+    RenameNx(RenameNx),
+    RPop(RPop),
+    RPush(RPush),
+    SAdd(SAdd),
+    SCard(SCard),
+    SDiff(SDiff),
+    SDiffStore(SDiffStore),
     Set(Set),
+    SetBit(SetBit),
+    SetRange(SetRange),
+    SInter(SInter),
+    SInterStore(SInterStore),
+    SIsMember(SIsMember),
+    SMembers(SMembers),
+    SPop(SPop),
+    SRandMember(SRandMember),
+    SRem(SRem),
+    StrLen(StrLen),
     Subscribe(Subscribe),
+    SUnion(SUnion),
+    SUnionStore(SUnionStore),
+    Ttl(Ttl),
+    Type(Type),
+    Unlink(Unlink),
+    Unsubscribe(Unsubscribe),
+    XAck(XAck),
+    XAdd(XAdd),
+    XAutoClaim(XAutoClaim),
+    XClaim(XClaim),
+    XGroup(XGroup),
+    XLen(XLen),
+    XPending(XPending),
+    XRange(XRange),
+    XRead(XRead),
+    XReadGroup(XReadGroup),
+    XRevRange(XRevRange),
+    XTrim(XTrim),
+    ZAdd(ZAdd),
+    ZCard(ZCard),
+    ZIncrBy(ZIncrBy),
+    ZInterStore(ZInterStore),
+    ZPopMax(ZPopMax),
+    ZPopMin(ZPopMin),
+    ZRange(ZRange),
+    ZRangeByScore(ZRangeByScore),
+    ZRank(ZRank),
+    ZRem(ZRem),
+    ZRevRank(ZRevRank),
+    ZScore(ZScore),
+    ZUnionStore(ZUnionStore),
 }
 
 #[cfg(feature = "server")]
@@ -55,37 +272,536 @@ where
     fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self>;
 }
 
+/// Errors raised while turning a request into a [`SupportedCommand`]. They are reported to the
+/// client as error replies and leave the connection usable.
+#[derive(Error, Debug)]
+pub(crate) enum CommandError {
+    #[error("ERR unknown command '{0}', with args beginning with: {1}")]
+    UnknownCommand(String, String),
+
+    #[error("ERR wrong number of arguments for '{0}' command")]
+    WrongArity(String),
+
+    #[error("ERR {0}")]
+    InvalidArgument(String),
+}
+
+/// Assembles a request made of a command name followed by keys.
+pub(crate) fn keys_frame(name: &str, keys: Vec<String>) -> Result<Frame, FrameError> {
+    let mut frame = Frame::Array(vec![]);
+    frame.push_bulk(Bytes::from(name.as_bytes().to_owned()))?;
+
+    for key in keys {
+        frame.push_bulk(Bytes::from(key.into_bytes()))?;
+    }
+
+    Ok(frame)
+}
+
+impl CommandError {
+    /// Classifies an error raised by [`Command::parse_from_frame`] for the command `name`.
+    fn from_parse_failure(name: &str, e: anyhow::Error) -> Self {
+        match e.downcast::<ParseError>() {
+            Ok(ParseError::EndOfStream) => CommandError::WrongArity(name.to_string()),
+            Ok(ParseError::ProtocolError(msg)) => CommandError::InvalidArgument(msg),
+            Err(e) => CommandError::InvalidArgument(e.to_string()),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for SupportedCommand {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        shutdown: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        match self {
+            SupportedCommand::Append(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::BitCount(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::BitField(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::BitOp(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::BitPos(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::BLMove(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::BLPop(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::BRPop(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::BZPopMax(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::BZPopMin(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::Copy(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::Decr(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::DecrBy(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::Del(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::Exists(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::Expire(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::ExpireAt(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::ExpireTime(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::Get(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::GetBit(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::GetDel(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::GetEx(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::GetRange(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::GetSet(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::HDel(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::Hello(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::HExpire(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::HGet(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::HGetAll(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::HIncrBy(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::HMGet(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::HPersist(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::HScan(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::HSet(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::HTtl(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::Incr(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::IncrBy(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::IncrByFloat(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::Info(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::LIndex(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::LLen(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::LMove(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::LPop(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::LPush(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::LRange(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::LRem(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::LSet(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::LTrim(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::MGet(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::MSet(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::MSetNx(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::Object(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::Persist(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::PExpire(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::PExpireAt(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::PExpireTime(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::Ping(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::PSubscribe(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::PTtl(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::Publish(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::PUnsubscribe(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::Rename(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::RenameNx(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::RPop(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::RPush(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::SAdd(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::SCard(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::SDiff(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::SDiffStore(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::Set(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::SetBit(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::SetRange(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::SInter(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::SInterStore(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::SIsMember(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::SMembers(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::SPop(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::SRandMember(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::SRem(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::StrLen(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::Subscribe(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::SUnion(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::SUnionStore(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::Ttl(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::Type(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::Unlink(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::Unsubscribe(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::XAck(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::XAdd(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::XAutoClaim(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::XClaim(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::XGroup(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::XLen(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::XPending(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::XRange(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::XRead(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::XReadGroup(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::XRevRange(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::XTrim(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::ZAdd(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::ZCard(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::ZIncrBy(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::ZInterStore(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::ZPopMax(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::ZPopMin(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::ZRange(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::ZRangeByScore(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::ZRank(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::ZRem(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::ZRevRank(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::ZScore(cmd) => cmd.execute(db, conn, shutdown).await,
+            SupportedCommand::ZUnionStore(cmd) => cmd.execute(db, conn, shutdown).await,
+        }
+    }
+}
+
 #[cfg(feature = "server")]
-pub(crate) fn from_frame(frame: Frame) -> anyhow::Result<SupportedCommand> {
+pub(crate) fn from_frame(frame: Frame) -> Result<SupportedCommand, CommandError> {
     use tracing::error;
 
     let mut parser = Parse::new(frame).map_err(|e| {
         error!(error = %e, "received frame could not be parsed.");
-        e
+        CommandError::InvalidArgument(format!("Protocol error: {e}"))
     })?;
 
-    let cmd_rep = parser.next_string()?.to_lowercase();
+    let name = parser
+        .next_string()
+        .map_err(|e| CommandError::InvalidArgument(format!("Protocol error: {e}")))?;
 
-    let cmd = match cmd_rep {
+    let cmd_rep = name.to_lowercase();
+
+    let parsed = match cmd_rep.as_str() {
         rep if rep == Ping::representation() => {
-            SupportedCommand::Ping(Ping::parse_from_frame(&mut parser)?)
+            Ping::parse_from_frame(&mut parser).map(SupportedCommand::Ping)
+        }
+        rep if rep == Hello::representation() => {
+            Hello::parse_from_frame(&mut parser).map(SupportedCommand::Hello)
+        }
+        rep if rep == Info::representation() => {
+            Info::parse_from_frame(&mut parser).map(SupportedCommand::Info)
         }
         rep if rep == Get::representation() => {
-            SupportedCommand::Get(Get::parse_from_frame(&mut parser)?)
+            Get::parse_from_frame(&mut parser).map(SupportedCommand::Get)
         }
         rep if rep == Set::representation() => {
-            SupportedCommand::Set(Set::parse_from_frame(&mut parser)?)
+            Set::parse_from_frame(&mut parser).map(SupportedCommand::Set)
         }
         rep if rep == Publish::representation() => {
-            SupportedCommand::Publish(Publish::parse_from_frame(&mut parser)?)
+            Publish::parse_from_frame(&mut parser).map(SupportedCommand::Publish)
         }
         rep if rep == Subscribe::representation() => {
-            SupportedCommand::Subscribe(Subscribe::parse_from_frame(&mut parser)?)
+            Subscribe::parse_from_frame(&mut parser).map(SupportedCommand::Subscribe)
+        }
+        rep if rep == PSubscribe::representation() => {
+            PSubscribe::parse_from_frame(&mut parser).map(SupportedCommand::PSubscribe)
+        }
+        rep if rep == Unsubscribe::representation() => {
+            Unsubscribe::parse_from_frame(&mut parser).map(SupportedCommand::Unsubscribe)
+        }
+        rep if rep == PUnsubscribe::representation() => {
+            PUnsubscribe::parse_from_frame(&mut parser).map(SupportedCommand::PUnsubscribe)
+        }
+        rep if rep == Del::representation() => {
+            Del::parse_from_frame(&mut parser).map(SupportedCommand::Del)
+        }
+        rep if rep == Unlink::representation() => {
+            Unlink::parse_from_frame(&mut parser).map(SupportedCommand::Unlink)
+        }
+        rep if rep == Exists::representation() => {
+            Exists::parse_from_frame(&mut parser).map(SupportedCommand::Exists)
+        }
+        rep if rep == Expire::representation() => {
+            Expire::parse_from_frame(&mut parser).map(SupportedCommand::Expire)
+        }
+        rep if rep == PExpire::representation() => {
+            PExpire::parse_from_frame(&mut parser).map(SupportedCommand::PExpire)
+        }
+        rep if rep == ExpireAt::representation() => {
+            ExpireAt::parse_from_frame(&mut parser).map(SupportedCommand::ExpireAt)
+        }
+        rep if rep == PExpireAt::representation() => {
+            PExpireAt::parse_from_frame(&mut parser).map(SupportedCommand::PExpireAt)
+        }
+        rep if rep == Ttl::representation() => {
+            Ttl::parse_from_frame(&mut parser).map(SupportedCommand::Ttl)
+        }
+        rep if rep == PTtl::representation() => {
+            PTtl::parse_from_frame(&mut parser).map(SupportedCommand::PTtl)
+        }
+        rep if rep == ExpireTime::representation() => {
+            ExpireTime::parse_from_frame(&mut parser).map(SupportedCommand::ExpireTime)
+        }
+        rep if rep == PExpireTime::representation() => {
+            PExpireTime::parse_from_frame(&mut parser).map(SupportedCommand::PExpireTime)
+        }
+        rep if rep == Persist::representation() => {
+            Persist::parse_from_frame(&mut parser).map(SupportedCommand::Persist)
+        }
+        rep if rep == Type::representation() => {
+            Type::parse_from_frame(&mut parser).map(SupportedCommand::Type)
+        }
+        rep if rep == Rename::representation() => {
+            Rename::parse_from_frame(&mut parser).map(SupportedCommand::Rename)
+        }
+        rep if rep == RenameNx::representation() => {
+            RenameNx::parse_from_frame(&mut parser).map(SupportedCommand::RenameNx)
+        }
+        rep if rep == CopyKey::representation() => {
+            CopyKey::parse_from_frame(&mut parser).map(SupportedCommand::Copy)
+        }
+        rep if rep == Incr::representation() => {
+            Incr::parse_from_frame(&mut parser).map(SupportedCommand::Incr)
+        }
+        rep if rep == Decr::representation() => {
+            Decr::parse_from_frame(&mut parser).map(SupportedCommand::Decr)
+        }
+        rep if rep == IncrBy::representation() => {
+            IncrBy::parse_from_frame(&mut parser).map(SupportedCommand::IncrBy)
+        }
+        rep if rep == DecrBy::representation() => {
+            DecrBy::parse_from_frame(&mut parser).map(SupportedCommand::DecrBy)
+        }
+        rep if rep == IncrByFloat::representation() => {
+            IncrByFloat::parse_from_frame(&mut parser).map(SupportedCommand::IncrByFloat)
+        }
+        rep if rep == MGet::representation() => {
+            MGet::parse_from_frame(&mut parser).map(SupportedCommand::MGet)
+        }
+        rep if rep == MSet::representation() => {
+            MSet::parse_from_frame(&mut parser).map(SupportedCommand::MSet)
+        }
+        rep if rep == MSetNx::representation() => {
+            MSetNx::parse_from_frame(&mut parser).map(SupportedCommand::MSetNx)
+        }
+        rep if rep == GetSet::representation() => {
+            GetSet::parse_from_frame(&mut parser).map(SupportedCommand::GetSet)
+        }
+        rep if rep == GetDel::representation() => {
+            GetDel::parse_from_frame(&mut parser).map(SupportedCommand::GetDel)
+        }
+        rep if rep == GetEx::representation() => {
+            GetEx::parse_from_frame(&mut parser).map(SupportedCommand::GetEx)
+        }
+        rep if rep == Append::representation() => {
+            Append::parse_from_frame(&mut parser).map(SupportedCommand::Append)
+        }
+        rep if rep == StrLen::representation() => {
+            StrLen::parse_from_frame(&mut parser).map(SupportedCommand::StrLen)
+        }
+        rep if rep == GetRange::representation() => {
+            GetRange::parse_from_frame(&mut parser).map(SupportedCommand::GetRange)
+        }
+        rep if rep == SetRange::representation() => {
+            SetRange::parse_from_frame(&mut parser).map(SupportedCommand::SetRange)
+        }
+        rep if rep == BitCount::representation() => {
+            BitCount::parse_from_frame(&mut parser).map(SupportedCommand::BitCount)
+        }
+        rep if rep == BitOp::representation() => {
+            BitOp::parse_from_frame(&mut parser).map(SupportedCommand::BitOp)
+        }
+        rep if rep == BitPos::representation() => {
+            BitPos::parse_from_frame(&mut parser).map(SupportedCommand::BitPos)
+        }
+        rep if rep == GetBit::representation() => {
+            GetBit::parse_from_frame(&mut parser).map(SupportedCommand::GetBit)
+        }
+        rep if rep == SetBit::representation() => {
+            SetBit::parse_from_frame(&mut parser).map(SupportedCommand::SetBit)
+        }
+        rep if rep == BitField::representation() => {
+            BitField::parse_from_frame(&mut parser).map(SupportedCommand::BitField)
+        }
+        rep if rep == Object::representation() => {
+            Object::parse_from_frame(&mut parser).map(SupportedCommand::Object)
+        }
+        rep if rep == LPush::representation() => {
+            LPush::parse_from_frame(&mut parser).map(SupportedCommand::LPush)
+        }
+        rep if rep == RPush::representation() => {
+            RPush::parse_from_frame(&mut parser).map(SupportedCommand::RPush)
+        }
+        rep if rep == LPop::representation() => {
+            LPop::parse_from_frame(&mut parser).map(SupportedCommand::LPop)
+        }
+        rep if rep == RPop::representation() => {
+            RPop::parse_from_frame(&mut parser).map(SupportedCommand::RPop)
+        }
+        rep if rep == LLen::representation() => {
+            LLen::parse_from_frame(&mut parser).map(SupportedCommand::LLen)
+        }
+        rep if rep == LRange::representation() => {
+            LRange::parse_from_frame(&mut parser).map(SupportedCommand::LRange)
+        }
+        rep if rep == LTrim::representation() => {
+            LTrim::parse_from_frame(&mut parser).map(SupportedCommand::LTrim)
+        }
+        rep if rep == LIndex::representation() => {
+            LIndex::parse_from_frame(&mut parser).map(SupportedCommand::LIndex)
+        }
+        rep if rep == LSet::representation() => {
+            LSet::parse_from_frame(&mut parser).map(SupportedCommand::LSet)
+        }
+        rep if rep == LRem::representation() => {
+            LRem::parse_from_frame(&mut parser).map(SupportedCommand::LRem)
+        }
+        rep if rep == LMove::representation() => {
+            LMove::parse_from_frame(&mut parser).map(SupportedCommand::LMove)
+        }
+        rep if rep == BLPop::representation() => {
+            BLPop::parse_from_frame(&mut parser).map(SupportedCommand::BLPop)
+        }
+        rep if rep == BRPop::representation() => {
+            BRPop::parse_from_frame(&mut parser).map(SupportedCommand::BRPop)
+        }
+        rep if rep == BLMove::representation() => {
+            BLMove::parse_from_frame(&mut parser).map(SupportedCommand::BLMove)
+        }
+        rep if rep == HSet::representation() => {
+            HSet::parse_from_frame(&mut parser).map(SupportedCommand::HSet)
+        }
+        rep if rep == HGet::representation() => {
+            HGet::parse_from_frame(&mut parser).map(SupportedCommand::HGet)
+        }
+        rep if rep == HMGet::representation() => {
+            HMGet::parse_from_frame(&mut parser).map(SupportedCommand::HMGet)
+        }
+        rep if rep == HGetAll::representation() => {
+            HGetAll::parse_from_frame(&mut parser).map(SupportedCommand::HGetAll)
+        }
+        rep if rep == HDel::representation() => {
+            HDel::parse_from_frame(&mut parser).map(SupportedCommand::HDel)
+        }
+        rep if rep == HIncrBy::representation() => {
+            HIncrBy::parse_from_frame(&mut parser).map(SupportedCommand::HIncrBy)
+        }
+        rep if rep == HScan::representation() => {
+            HScan::parse_from_frame(&mut parser).map(SupportedCommand::HScan)
+        }
+        rep if rep == HExpire::representation() => {
+            HExpire::parse_from_frame(&mut parser).map(SupportedCommand::HExpire)
+        }
+        rep if rep == HTtl::representation() => {
+            HTtl::parse_from_frame(&mut parser).map(SupportedCommand::HTtl)
+        }
+        rep if rep == HPersist::representation() => {
+            HPersist::parse_from_frame(&mut parser).map(SupportedCommand::HPersist)
+        }
+        rep if rep == SAdd::representation() => {
+            SAdd::parse_from_frame(&mut parser).map(SupportedCommand::SAdd)
+        }
+        rep if rep == SRem::representation() => {
+            SRem::parse_from_frame(&mut parser).map(SupportedCommand::SRem)
+        }
+        rep if rep == SMembers::representation() => {
+            SMembers::parse_from_frame(&mut parser).map(SupportedCommand::SMembers)
+        }
+        rep if rep == SIsMember::representation() => {
+            SIsMember::parse_from_frame(&mut parser).map(SupportedCommand::SIsMember)
+        }
+        rep if rep == SCard::representation() => {
+            SCard::parse_from_frame(&mut parser).map(SupportedCommand::SCard)
+        }
+        rep if rep == SRandMember::representation() => {
+            SRandMember::parse_from_frame(&mut parser).map(SupportedCommand::SRandMember)
+        }
+        rep if rep == SPop::representation() => {
+            SPop::parse_from_frame(&mut parser).map(SupportedCommand::SPop)
+        }
+        rep if rep == SInter::representation() => {
+            SInter::parse_from_frame(&mut parser).map(SupportedCommand::SInter)
+        }
+        rep if rep == SUnion::representation() => {
+            SUnion::parse_from_frame(&mut parser).map(SupportedCommand::SUnion)
+        }
+        rep if rep == SDiff::representation() => {
+            SDiff::parse_from_frame(&mut parser).map(SupportedCommand::SDiff)
+        }
+        rep if rep == SInterStore::representation() => {
+            SInterStore::parse_from_frame(&mut parser).map(SupportedCommand::SInterStore)
+        }
+        rep if rep == SUnionStore::representation() => {
+            SUnionStore::parse_from_frame(&mut parser).map(SupportedCommand::SUnionStore)
+        }
+        rep if rep == SDiffStore::representation() => {
+            SDiffStore::parse_from_frame(&mut parser).map(SupportedCommand::SDiffStore)
+        }
+        rep if rep == ZAdd::representation() => {
+            ZAdd::parse_from_frame(&mut parser).map(SupportedCommand::ZAdd)
+        }
+        rep if rep == ZIncrBy::representation() => {
+            ZIncrBy::parse_from_frame(&mut parser).map(SupportedCommand::ZIncrBy)
+        }
+        rep if rep == ZRem::representation() => {
+            ZRem::parse_from_frame(&mut parser).map(SupportedCommand::ZRem)
+        }
+        rep if rep == ZScore::representation() => {
+            ZScore::parse_from_frame(&mut parser).map(SupportedCommand::ZScore)
+        }
+        rep if rep == ZCard::representation() => {
+            ZCard::parse_from_frame(&mut parser).map(SupportedCommand::ZCard)
+        }
+        rep if rep == ZRank::representation() => {
+            ZRank::parse_from_frame(&mut parser).map(SupportedCommand::ZRank)
+        }
+        rep if rep == ZRevRank::representation() => {
+            ZRevRank::parse_from_frame(&mut parser).map(SupportedCommand::ZRevRank)
+        }
+        rep if rep == ZRange::representation() => {
+            ZRange::parse_from_frame(&mut parser).map(SupportedCommand::ZRange)
+        }
+        rep if rep == ZRangeByScore::representation() => {
+            ZRangeByScore::parse_from_frame(&mut parser).map(SupportedCommand::ZRangeByScore)
+        }
+        rep if rep == ZPopMin::representation() => {
+            ZPopMin::parse_from_frame(&mut parser).map(SupportedCommand::ZPopMin)
+        }
+        rep if rep == ZPopMax::representation() => {
+            ZPopMax::parse_from_frame(&mut parser).map(SupportedCommand::ZPopMax)
+        }
+        rep if rep == BZPopMin::representation() => {
+            BZPopMin::parse_from_frame(&mut parser).map(SupportedCommand::BZPopMin)
+        }
+        rep if rep == BZPopMax::representation() => {
+            BZPopMax::parse_from_frame(&mut parser).map(SupportedCommand::BZPopMax)
+        }
+        rep if rep == ZUnionStore::representation() => {
+            ZUnionStore::parse_from_frame(&mut parser).map(SupportedCommand::ZUnionStore)
+        }
+        rep if rep == ZInterStore::representation() => {
+            ZInterStore::parse_from_frame(&mut parser).map(SupportedCommand::ZInterStore)
+        }
+        rep if rep == XAdd::representation() => {
+            XAdd::parse_from_frame(&mut parser).map(SupportedCommand::XAdd)
+        }
+        rep if rep == XTrim::representation() => {
+            XTrim::parse_from_frame(&mut parser).map(SupportedCommand::XTrim)
+        }
+        rep if rep == XLen::representation() => {
+            XLen::parse_from_frame(&mut parser).map(SupportedCommand::XLen)
+        }
+        rep if rep == XRange::representation() => {
+            XRange::parse_from_frame(&mut parser).map(SupportedCommand::XRange)
+        }
+        rep if rep == XRevRange::representation() => {
+            XRevRange::parse_from_frame(&mut parser).map(SupportedCommand::XRevRange)
+        }
+        rep if rep == XRead::representation() => {
+            XRead::parse_from_frame(&mut parser).map(SupportedCommand::XRead)
+        }
+        rep if rep == XGroup::representation() => {
+            XGroup::parse_from_frame(&mut parser).map(SupportedCommand::XGroup)
+        }
+        rep if rep == XReadGroup::representation() => {
+            XReadGroup::parse_from_frame(&mut parser).map(SupportedCommand::XReadGroup)
+        }
+        rep if rep == XAck::representation() => {
+            XAck::parse_from_frame(&mut parser).map(SupportedCommand::XAck)
+        }
+        rep if rep == XPending::representation() => {
+            XPending::parse_from_frame(&mut parser).map(SupportedCommand::XPending)
+        }
+        rep if rep == XClaim::representation() => {
+            XClaim::parse_from_frame(&mut parser).map(SupportedCommand::XClaim)
+        }
+        rep if rep == XAutoClaim::representation() => {
+            XAutoClaim::parse_from_frame(&mut parser).map(SupportedCommand::XAutoClaim)
         }
         // unrecognized command
-        _ => todo!(),
+        _ => {
+            let args = std::iter::from_fn(|| parser.next_string().ok())
+                .map(|arg| format!("'{arg}' "))
+                .collect::<String>();
+
+            return Err(CommandError::UnknownCommand(name, args));
+        }
     };
 
-    parser.finish()?;
+    let cmd = parsed.map_err(|e| CommandError::from_parse_failure(&cmd_rep, e))?;
+
+    parser
+        .finish()
+        .map_err(|_| CommandError::WrongArity(cmd_rep))?;
+
     Ok(cmd)
 }
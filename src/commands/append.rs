@@ -0,0 +1,85 @@
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::{range::STRING_TOO_LONG, Execute},
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+#[derive(Debug)]
+pub(crate) struct Append {
+    key: String,
+    value: Bytes,
+}
+
+impl Append {
+    pub(crate) fn new(key: impl ToString, value: Bytes) -> Self {
+        Self {
+            key: key.to_string(),
+            value,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for Append {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let max_len = db.max_string_len();
+        let res = db.edit(&self.key, |buf| {
+            if buf.len() + self.value.len() > max_len {
+                return Err(STRING_TOO_LONG);
+            }
+
+            buf.extend_from_slice(&self.value);
+            Ok(buf.len())
+        });
+
+        let res = match res {
+            Ok(Ok(len)) => Frame::Integer(len as i64),
+            Ok(Err(e)) => Frame::Error(e.to_string()),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for Append {
+    fn representation<'a>() -> &'a str {
+        "append"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+            value: parser.next_bytes()?,
+        })
+    }
+}
+
+impl TryInto<Frame> for Append {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_bulk(self.value)?;
+        Ok(frame)
+    }
+}
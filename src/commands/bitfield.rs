@@ -0,0 +1,322 @@
+use anyhow::anyhow;
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{bits::parse_bit_offset, keys_frame, set::syntax_error, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::{Parse, ParseError},
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::{bits::check_bit_offsets, Execute},
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+/// An integer field of a BITFIELD value: `i1` to `i64`, or `u1` to `u63`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FieldType {
+    signed: bool,
+    bits: u8,
+}
+
+/// How SET and INCRBY handle results that do not fit in their field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Overflow {
+    /// Wrap around, the default.
+    Wrap,
+    /// Saturate at the minimum or maximum of the field.
+    Sat,
+    /// Leave the field unchanged and reply with a null.
+    Fail,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FieldOp {
+    Get(FieldType, u64),
+    Set(FieldType, u64, i64),
+    IncrBy(FieldType, u64, i64),
+    /// Changes the overflow handling of the operations that follow.
+    Overflow(Overflow),
+}
+
+/// Reads and writes integer fields of arbitrary width and offset in a value.
+#[derive(Debug)]
+pub(crate) struct BitField {
+    key: String,
+    ops: Vec<FieldOp>,
+}
+
+impl FieldType {
+    fn parse(arg: &str) -> anyhow::Result<Self> {
+        let signed = arg.starts_with(['i', 'I']);
+
+        arg.get(1..)
+            .filter(|_| signed || arg.starts_with(['u', 'U']))
+            .and_then(|bits| bits.parse::<u8>().ok())
+            .filter(|bits| (1..=if signed { 64 } else { 63 }).contains(bits))
+            .map(|bits| Self { signed, bits })
+            .ok_or_else(|| {
+                anyhow!(
+                    "Invalid bitfield type. Use something like i16 u8. Note that u64 is not \
+                     supported but i64 is."
+                )
+            })
+    }
+
+    fn as_string(&self) -> String {
+        format!("{}{}", if self.signed { 'i' } else { 'u' }, self.bits)
+    }
+
+    #[cfg(feature = "server")]
+    fn min(&self) -> i128 {
+        if self.signed {
+            -(1 << (self.bits - 1))
+        } else {
+            0
+        }
+    }
+
+    #[cfg(feature = "server")]
+    fn max(&self) -> i128 {
+        if self.signed {
+            (1 << (self.bits - 1)) - 1
+        } else {
+            (1 << self.bits) - 1
+        }
+    }
+
+    /// Reads the field at bit `offset`. Bits past the end of the value are zeros.
+    #[cfg(feature = "server")]
+    fn get(&self, buf: &[u8], offset: u64) -> i64 {
+        let raw = (offset..offset + self.bits as u64).fold(0u64, |acc, pos| {
+            let byte = buf.get((pos / 8) as usize).copied().unwrap_or(0);
+            (acc << 1) | ((byte >> (7 - pos % 8)) & 1) as u64
+        });
+
+        if self.signed && self.bits < 64 && raw >> (self.bits - 1) == 1 {
+            (raw as i128 - (1 << self.bits)) as i64
+        } else {
+            raw as i64
+        }
+    }
+
+    /// Writes the field at bit `offset`. The value must be long enough to hold it.
+    #[cfg(feature = "server")]
+    fn set(&self, buf: &mut [u8], offset: u64, value: i64) {
+        for i in 0..self.bits as u64 {
+            let pos = offset + i;
+            let mask = 0x80 >> (pos % 8);
+            let byte = &mut buf[(pos / 8) as usize];
+
+            if (value as u64 >> (self.bits as u64 - 1 - i)) & 1 == 1 {
+                *byte |= mask;
+            } else {
+                *byte &= !mask;
+            }
+        }
+    }
+
+    /// Fits `value` in the field according to `overflow`, `None` meaning it failed.
+    #[cfg(feature = "server")]
+    fn fit(&self, value: i128, overflow: Overflow) -> Option<i64> {
+        let (min, max) = (self.min(), self.max());
+
+        if (min..=max).contains(&value) {
+            return Some(value as i64);
+        }
+
+        match overflow {
+            Overflow::Wrap => {
+                let wrapped = value.rem_euclid(1 << self.bits);
+                Some(if wrapped > max {
+                    wrapped - (1 << self.bits)
+                } else {
+                    wrapped
+                } as i64)
+            }
+            Overflow::Sat => Some(value.clamp(min, max) as i64),
+            Overflow::Fail => None,
+        }
+    }
+}
+
+impl Overflow {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Overflow::Wrap => "wrap",
+            Overflow::Sat => "sat",
+            Overflow::Fail => "fail",
+        }
+    }
+}
+
+impl BitField {
+    pub(crate) fn new(key: impl ToString, ops: Vec<FieldOp>) -> Self {
+        Self {
+            key: key.to_string(),
+            ops,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for BitField {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let offsets = self.ops.iter().filter_map(|op| match op {
+            FieldOp::Get(_, offset)
+            | FieldOp::Set(_, offset, _)
+            | FieldOp::IncrBy(_, offset, _) => Some(*offset),
+            FieldOp::Overflow(_) => None,
+        });
+
+        if let Err(e) = check_bit_offsets(offsets, db) {
+            conn.write_frame(&e).await?;
+            return Ok(());
+        }
+
+        // the value is grown to hold every field written to, even if the write fails
+        let len = self
+            .ops
+            .iter()
+            .filter_map(|op| match op {
+                FieldOp::Set(ty, offset, _) | FieldOp::IncrBy(ty, offset, _) => {
+                    Some((offset + ty.bits as u64).div_ceil(8) as usize)
+                }
+                FieldOp::Get(..) | FieldOp::Overflow(_) => None,
+            })
+            .max()
+            .unwrap_or(0);
+
+        let replies = db.edit(&self.key, |buf| {
+            if buf.len() < len {
+                buf.resize(len, 0);
+            }
+
+            let mut overflow = Overflow::Wrap;
+            let mut replies = vec![];
+
+            for op in self.ops {
+                let reply = match op {
+                    FieldOp::Get(ty, offset) => Some(ty.get(buf, offset)),
+                    FieldOp::Set(ty, offset, value) => {
+                        let previous = ty.get(buf, offset);
+                        ty.fit(value as i128, overflow).map(|value| {
+                            ty.set(buf, offset, value);
+                            previous
+                        })
+                    }
+                    FieldOp::IncrBy(ty, offset, increment) => {
+                        let value = ty.get(buf, offset) as i128 + increment as i128;
+                        ty.fit(value, overflow).inspect(|value| {
+                            ty.set(buf, offset, *value);
+                        })
+                    }
+                    FieldOp::Overflow(o) => {
+                        overflow = o;
+                        continue;
+                    }
+                };
+
+                replies.push(reply.map_or(Frame::Null, Frame::Integer));
+            }
+
+            replies
+        });
+
+        let res = match replies {
+            Ok(replies) => Frame::Array(replies),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for BitField {
+    fn representation<'a>() -> &'a str {
+        "bitfield"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let key = parser.next_string()?;
+        let mut ops = vec![];
+
+        loop {
+            let subcommand = match parser.next_string() {
+                Ok(s) => s.to_lowercase(),
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            };
+
+            if subcommand == "overflow" {
+                let overflow = match parser.next_string()?.to_lowercase().as_str() {
+                    "wrap" => Overflow::Wrap,
+                    "sat" => Overflow::Sat,
+                    "fail" => Overflow::Fail,
+                    _ => return Err(anyhow!("Invalid OVERFLOW type specified")),
+                };
+
+                ops.push(FieldOp::Overflow(overflow));
+                continue;
+            }
+
+            if !matches!(subcommand.as_str(), "get" | "set" | "incrby") {
+                return Err(syntax_error());
+            }
+
+            let ty = FieldType::parse(&parser.next_string()?)?;
+            let offset = parse_bit_offset(&parser.next_string()?, Some(ty.bits))?;
+
+            ops.push(match subcommand.as_str() {
+                "get" => FieldOp::Get(ty, offset),
+                "set" => FieldOp::Set(ty, offset, parser.next_int()?),
+                _ => FieldOp::IncrBy(ty, offset, parser.next_int()?),
+            });
+        }
+
+        Ok(Self { key, ops })
+    }
+}
+
+impl TryInto<Frame> for BitField {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+
+        for op in self.ops {
+            let (subcommand, ty, offset, value) = match op {
+                FieldOp::Get(ty, offset) => ("get", ty, offset, None),
+                FieldOp::Set(ty, offset, value) => ("set", ty, offset, Some(value)),
+                FieldOp::IncrBy(ty, offset, increment) => ("incrby", ty, offset, Some(increment)),
+                FieldOp::Overflow(overflow) => {
+                    frame.push_bulk(Bytes::from("overflow".as_bytes()))?;
+                    frame.push_bulk(Bytes::from(overflow.as_str().as_bytes()))?;
+                    continue;
+                }
+            };
+
+            frame.push_bulk(Bytes::from(subcommand.as_bytes()))?;
+            frame.push_bulk(Bytes::from(ty.as_string()))?;
+            frame.push_int(offset as i64)?;
+
+            if let Some(value) = value {
+                frame.push_int(value)?;
+            }
+        }
+
+        Ok(frame)
+    }
+}
@@ -0,0 +1,609 @@
+use anyhow::anyhow;
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use {async_trait::async_trait, std::ops::RangeInclusive};
+
+use crate::{
+    commands::{keys_frame, set::syntax_error, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::{Parse, ParseError},
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::{range::index_range, Execute},
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+const BIT_OFFSET_OUT_OF_RANGE: &str = "bit offset is not an integer or out of range";
+
+/// Parses a bit offset. If `width` is given, an offset prefixed with `#` counts fields of
+/// `width` bits rather than bits.
+pub(super) fn parse_bit_offset(arg: &str, width: Option<u8>) -> anyhow::Result<u64> {
+    let (arg, width) = match (arg.strip_prefix('#'), width) {
+        (Some(arg), Some(width)) => (arg, width as i64),
+        _ => (arg, 1),
+    };
+
+    arg.parse::<i64>()
+        .ok()
+        .and_then(|offset| offset.checked_mul(width))
+        .and_then(|offset| u64::try_from(offset).ok())
+        .ok_or_else(|| anyhow!(BIT_OFFSET_OUT_OF_RANGE))
+}
+
+/// Checks that every bit offset lies within the largest string a value may grow to,
+/// returning the error to reply otherwise.
+#[cfg(feature = "server")]
+pub(super) fn check_bit_offsets(
+    mut offsets: impl Iterator<Item = u64>,
+    db: &Database,
+) -> Result<(), Frame> {
+    let max_len = db.max_string_len() as u64;
+
+    match offsets.all(|offset| offset / 8 < max_len) {
+        true => Ok(()),
+        false => Err(Frame::Error(format!("ERR {BIT_OFFSET_OUT_OF_RANGE}"))),
+    }
+}
+
+fn parse_bit(parser: &mut Parse, error: &str) -> anyhow::Result<bool> {
+    match parser.next_int() {
+        Ok(0) => Ok(false),
+        Ok(1) => Ok(true),
+        Err(ParseError::EndOfStream) => Err(ParseError::EndOfStream.into()),
+        _ => Err(anyhow!("{error}")),
+    }
+}
+
+/// Unit of the indexes of a [`BitRange`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BitUnit {
+    Byte,
+    Bit,
+}
+
+/// The range of a value searched by BITCOUNT and BITPOS. Negative indexes count from the end
+/// of the value.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BitRange {
+    start: i64,
+    /// The last byte or bit of the range, the end of the value if not given.
+    end: Option<i64>,
+    unit: BitUnit,
+}
+
+impl BitRange {
+    pub(crate) fn new(start: i64, end: Option<i64>, unit: BitUnit) -> Self {
+        Self { start, end, unit }
+    }
+
+    /// Reads an optional range. The unit may only follow an end, which is required if
+    /// `end_required` is set.
+    fn parse(parser: &mut Parse, end_required: bool) -> anyhow::Result<Option<Self>> {
+        let start = match parser.next_int() {
+            Ok(start) => start,
+            Err(ParseError::EndOfStream) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let end = match parser.next_int() {
+            Ok(end) => end,
+            Err(ParseError::EndOfStream) if end_required => return Err(syntax_error()),
+            Err(ParseError::EndOfStream) => return Ok(Some(Self::new(start, None, BitUnit::Byte))),
+            Err(e) => return Err(e.into()),
+        };
+
+        let unit = match parser.next_string() {
+            Ok(s) => match s.to_lowercase().as_str() {
+                "byte" => BitUnit::Byte,
+                "bit" => BitUnit::Bit,
+                _ => return Err(syntax_error()),
+            },
+            Err(ParseError::EndOfStream) => BitUnit::Byte,
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Some(Self::new(start, Some(end), unit)))
+    }
+
+    fn push_to(&self, frame: &mut Frame) -> Result<(), FrameError> {
+        frame.push_int(self.start)?;
+
+        if let Some(end) = self.end {
+            frame.push_int(end)?;
+
+            if self.unit == BitUnit::Bit {
+                frame.push_bulk(Bytes::from("bit".as_bytes()))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Resolves `range` to the bits of a value of `len` bytes, the whole value if there is no
+/// range.
+#[cfg(feature = "server")]
+fn bit_range(range: Option<BitRange>, len: usize) -> Option<RangeInclusive<usize>> {
+    let range = range.unwrap_or(BitRange::new(0, None, BitUnit::Byte));
+    let end = range.end.unwrap_or(-1);
+
+    match range.unit {
+        BitUnit::Byte => {
+            index_range(range.start, end, len).map(|bytes| bytes.start() * 8..=bytes.end() * 8 + 7)
+        }
+        BitUnit::Bit => index_range(range.start, end, len * 8),
+    }
+}
+
+/// Keeps the bits of `byte`, found at `index` in the value, that fall inside `bits`.
+#[cfg(feature = "server")]
+fn mask(byte: u8, index: usize, bits: &RangeInclusive<usize>) -> u8 {
+    let mut byte = byte;
+
+    if index == bits.start() / 8 {
+        byte &= 0xff >> (bits.start() % 8);
+    }
+
+    if index == bits.end() / 8 {
+        byte &= 0xff << (7 - bits.end() % 8);
+    }
+
+    byte
+}
+
+#[cfg(feature = "server")]
+fn count_bits(buf: &[u8], bits: RangeInclusive<usize>) -> u64 {
+    (bits.start() / 8..=bits.end() / 8)
+        .map(|i| mask(buf[i], i, &bits).count_ones() as u64)
+        .sum()
+}
+
+/// Finds the first bit set to `bit` in the range.
+#[cfg(feature = "server")]
+fn find_bit(buf: &[u8], bit: bool, bits: &RangeInclusive<usize>) -> Option<usize> {
+    (bits.start() / 8..=bits.end() / 8).find_map(|i| {
+        let byte = mask(if bit { buf[i] } else { !buf[i] }, i, bits);
+        (byte != 0).then(|| i * 8 + byte.leading_zeros() as usize)
+    })
+}
+
+#[derive(Debug)]
+pub(crate) struct GetBit {
+    key: String,
+    offset: u64,
+}
+
+/// Sets or clears the bit at `offset`, growing the value with zero bytes as needed.
+#[derive(Debug)]
+pub(crate) struct SetBit {
+    key: String,
+    offset: u64,
+    value: bool,
+}
+
+/// Counts the set bits of a value, or of a range of it.
+#[derive(Debug)]
+pub(crate) struct BitCount {
+    key: String,
+    range: Option<BitRange>,
+}
+
+/// Finds the first bit set, or cleared, in a value, or in a range of it.
+#[derive(Debug)]
+pub(crate) struct BitPos {
+    key: String,
+    bit: bool,
+    range: Option<BitRange>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BitOperation {
+    And,
+    Or,
+    Xor,
+    Not,
+}
+
+/// Combines the values of `keys` bitwise and stores the result at `dest`. Shorter values are
+/// padded with zero bytes.
+#[derive(Debug)]
+pub(crate) struct BitOp {
+    operation: BitOperation,
+    dest: String,
+    keys: Vec<String>,
+}
+
+impl BitOperation {
+    fn as_str(&self) -> &'static str {
+        match self {
+            BitOperation::And => "and",
+            BitOperation::Or => "or",
+            BitOperation::Xor => "xor",
+            BitOperation::Not => "not",
+        }
+    }
+
+    #[cfg(feature = "server")]
+    fn apply(&self, values: Vec<Option<&[u8]>>) -> Bytes {
+        let len = values
+            .iter()
+            .map(|value| value.map_or(0, <[u8]>::len))
+            .max()
+            .unwrap_or(0);
+
+        (0..len)
+            .map(|i| {
+                let mut bytes = values
+                    .iter()
+                    .map(|value| value.and_then(|v| v.get(i)).copied().unwrap_or(0));
+
+                match self {
+                    BitOperation::And => bytes.fold(0xff, |acc, b| acc & b),
+                    BitOperation::Or => bytes.fold(0, |acc, b| acc | b),
+                    BitOperation::Xor => bytes.fold(0, |acc, b| acc ^ b),
+                    BitOperation::Not => !bytes.next().unwrap_or(0),
+                }
+            })
+            .collect()
+    }
+}
+
+impl GetBit {
+    pub(crate) fn new(key: impl ToString, offset: u64) -> Self {
+        Self {
+            key: key.to_string(),
+            offset,
+        }
+    }
+}
+
+impl SetBit {
+    pub(crate) fn new(key: impl ToString, offset: u64, value: bool) -> Self {
+        Self {
+            key: key.to_string(),
+            offset,
+            value,
+        }
+    }
+}
+
+impl BitCount {
+    pub(crate) fn new(key: impl ToString, range: Option<BitRange>) -> Self {
+        Self {
+            key: key.to_string(),
+            range,
+        }
+    }
+}
+
+impl BitPos {
+    pub(crate) fn new(key: impl ToString, bit: bool, range: Option<BitRange>) -> Self {
+        Self {
+            key: key.to_string(),
+            bit,
+            range,
+        }
+    }
+}
+
+impl BitOp {
+    pub(crate) fn new(operation: BitOperation, dest: impl ToString, keys: Vec<String>) -> Self {
+        Self {
+            operation,
+            dest: dest.to_string(),
+            keys,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for GetBit {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        if let Err(e) = check_bit_offsets(std::iter::once(self.offset), db) {
+            conn.write_frame(&e).await?;
+            return Ok(());
+        }
+
+        let index = (self.offset / 8) as usize;
+        let bit = db.read(&self.key, |buf| {
+            buf.get(index)
+                .map_or(0, |byte| (byte >> (7 - self.offset % 8)) & 1)
+        });
+
+        let res = match bit {
+            Ok(bit) => Frame::Integer(bit.unwrap_or(0) as i64),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for SetBit {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        if let Err(e) = check_bit_offsets(std::iter::once(self.offset), db) {
+            conn.write_frame(&e).await?;
+            return Ok(());
+        }
+
+        let index = (self.offset / 8) as usize;
+        let mask = 0x80 >> (self.offset % 8);
+
+        let previous = db.edit(&self.key, |buf| {
+            if buf.len() <= index {
+                buf.resize(index + 1, 0);
+            }
+
+            let previous = buf[index] & mask != 0;
+
+            if self.value {
+                buf[index] |= mask;
+            } else {
+                buf[index] &= !mask;
+            }
+
+            previous
+        });
+
+        let res = match previous {
+            Ok(previous) => Frame::Integer(previous as i64),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for BitCount {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let count = db.read(&self.key, |buf| {
+            bit_range(self.range, buf.len()).map_or(0, |bits| count_bits(buf, bits))
+        });
+
+        let res = match count {
+            Ok(count) => Frame::Integer(count.unwrap_or(0) as i64),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for BitPos {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let end_given = self.range.is_some_and(|range| range.end.is_some());
+
+        let pos = db.read(&self.key, |buf| {
+            let bits = bit_range(self.range, buf.len())?;
+
+            match find_bit(buf, self.bit, &bits) {
+                Some(pos) => Some(pos),
+                // the value is considered padded with zeros to the right, unless the end of
+                // the range is given
+                None if !self.bit && !end_given => Some(bits.end() + 1),
+                None => None,
+            }
+        });
+
+        let res = match pos {
+            Ok(Some(Some(pos))) => Frame::Integer(pos as i64),
+            Ok(Some(None)) => Frame::Integer(-1),
+            // a missing key is an empty value
+            Ok(None) if self.bit => Frame::Integer(-1),
+            Ok(None) => Frame::Integer(0),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for BitOp {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let len = db.combine(&self.dest, &self.keys, |values| {
+            self.operation.apply(values)
+        });
+
+        let res = match len {
+            Ok(len) => Frame::Integer(len as i64),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for GetBit {
+    fn representation<'a>() -> &'a str {
+        "getbit"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+            offset: parse_bit_offset(&parser.next_string()?, None)?,
+        })
+    }
+}
+
+impl Command for SetBit {
+    fn representation<'a>() -> &'a str {
+        "setbit"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+            offset: parse_bit_offset(&parser.next_string()?, None)?,
+            value: parse_bit(parser, "bit is not an integer or out of range")?,
+        })
+    }
+}
+
+impl Command for BitCount {
+    fn representation<'a>() -> &'a str {
+        "bitcount"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+            range: BitRange::parse(parser, true)?,
+        })
+    }
+}
+
+impl Command for BitPos {
+    fn representation<'a>() -> &'a str {
+        "bitpos"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+            bit: parse_bit(parser, "The bit argument must be 1 or 0.")?,
+            range: BitRange::parse(parser, false)?,
+        })
+    }
+}
+
+impl Command for BitOp {
+    fn representation<'a>() -> &'a str {
+        "bitop"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let operation = match parser.next_string()?.to_lowercase().as_str() {
+            "and" => BitOperation::And,
+            "or" => BitOperation::Or,
+            "xor" => BitOperation::Xor,
+            "not" => BitOperation::Not,
+            _ => return Err(syntax_error()),
+        };
+
+        let dest = parser.next_string()?;
+        let mut keys = vec![parser.next_string()?];
+        keys.extend(parser.remaining_strings()?);
+
+        if operation == BitOperation::Not && keys.len() != 1 {
+            return Err(anyhow!(
+                "BITOP NOT must be called with a single source key."
+            ));
+        }
+
+        Ok(Self {
+            operation,
+            dest,
+            keys,
+        })
+    }
+}
+
+impl TryInto<Frame> for GetBit {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_int(self.offset as i64)?;
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for SetBit {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_int(self.offset as i64)?;
+        frame.push_int(self.value as i64)?;
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for BitCount {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+
+        if let Some(range) = self.range {
+            range.push_to(&mut frame)?;
+        }
+
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for BitPos {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_int(self.bit as i64)?;
+
+        if let Some(range) = self.range {
+            range.push_to(&mut frame)?;
+        }
+
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for BitOp {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![])?;
+        frame.push_bulk(Bytes::from(self.operation.as_str().as_bytes()))?;
+        frame.push_bulk(Bytes::from(self.dest.into_bytes()))?;
+
+        for key in self.keys {
+            frame.push_bulk(Bytes::from(key.into_bytes()))?;
+        }
+
+        Ok(frame)
+    }
+}
@@ -0,0 +1,330 @@
+use anyhow::anyhow;
+use bytes::Bytes;
+use std::time::Duration;
+
+#[cfg(feature = "server")]
+use {async_trait::async_trait, tokio::select};
+
+use crate::{
+    commands::{keys_frame, lmove::Direction, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::{Parse, ParseError},
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{
+        database::{
+            blocking::{BlockedPop, Served},
+            database::{Database, ListEnd},
+        },
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// Pops a value from the head of the first non-empty list, waiting for a push if they are
+/// all empty.
+#[derive(Debug)]
+pub(crate) struct BLPop {
+    keys: Vec<String>,
+    /// How long to wait, forever if `None`.
+    timeout: Option<Duration>,
+}
+
+/// Pops a value from the tail of the first non-empty list, waiting for a push if they are
+/// all empty.
+#[derive(Debug)]
+pub(crate) struct BRPop {
+    keys: Vec<String>,
+    timeout: Option<Duration>,
+}
+
+/// `LMOVE`, waiting for a push if the source list is empty.
+#[derive(Debug)]
+pub(crate) struct BLMove {
+    src: String,
+    dst: String,
+    from: Direction,
+    to: Direction,
+    timeout: Option<Duration>,
+}
+
+impl BLPop {
+    pub(crate) fn new(keys: Vec<String>, timeout: Option<Duration>) -> Self {
+        Self { keys, timeout }
+    }
+}
+
+impl BRPop {
+    pub(crate) fn new(keys: Vec<String>, timeout: Option<Duration>) -> Self {
+        Self { keys, timeout }
+    }
+}
+
+impl BLMove {
+    pub(crate) fn new(
+        src: impl ToString,
+        dst: impl ToString,
+        from: Direction,
+        to: Direction,
+        timeout: Option<Duration>,
+    ) -> Self {
+        Self {
+            src: src.to_string(),
+            dst: dst.to_string(),
+            from,
+            to,
+            timeout,
+        }
+    }
+}
+
+/// How a blocking pop ended.
+#[cfg(feature = "server")]
+pub(super) enum Waited<T> {
+    Served(Served<T>),
+    TimedOut,
+    /// The server shut down or the client disconnected.
+    Interrupted,
+}
+
+/// Waits at most `timeout` for a blocked client to be served.
+#[cfg(feature = "server")]
+pub(super) async fn wait<T>(
+    mut blocked: BlockedPop<T>,
+    timeout: Option<Duration>,
+    conn: &mut Connection,
+    shutdown: &mut ShutdownListener,
+) -> Waited<T> {
+    let deadline = async {
+        match timeout {
+            Some(timeout) => tokio::time::sleep(timeout).await,
+            None => std::future::pending().await,
+        }
+    };
+
+    let waited = select! {
+        served = blocked.served() => Waited::Served(served),
+        _ = deadline => Waited::TimedOut,
+        _ = shutdown.subscribe() => Waited::Interrupted,
+        _ = conn.closed() => Waited::Interrupted,
+    };
+
+    match waited {
+        Waited::Served(_) => waited,
+        // the client may have been served while the wait came to an end
+        _ => blocked.cancel().map_or(waited, Waited::Served),
+    }
+}
+
+/// Pops from the first of the lists at `keys` that exists, waiting at most `timeout` for a
+/// push if none of them does.
+#[cfg(feature = "server")]
+async fn pop_or_wait(
+    keys: Vec<String>,
+    from: ListEnd,
+    to: Option<(String, ListEnd)>,
+    timeout: Option<Duration>,
+    db: &Database,
+    conn: &mut Connection,
+    shutdown: &mut ShutdownListener,
+) -> Waited<Bytes> {
+    match db.pop_or_block(keys, from, to) {
+        Ok(Ok(popped)) => Waited::Served(Ok(popped)),
+        Ok(Err(blocked)) => wait(blocked, timeout, conn, shutdown).await,
+        Err(e) => Waited::Served(Err(e)),
+    }
+}
+
+#[cfg(feature = "server")]
+async fn blocking_pop(
+    keys: Vec<String>,
+    end: ListEnd,
+    timeout: Option<Duration>,
+    db: &Database,
+    conn: &mut Connection,
+    shutdown: &mut ShutdownListener,
+) -> anyhow::Result<()> {
+    let res = match pop_or_wait(keys, end, None, timeout, db, conn, shutdown).await {
+        Waited::Served(Ok((key, value))) => Frame::Array(vec![
+            Frame::Bulk(Bytes::from(key.into_bytes())),
+            Frame::Bulk(value),
+        ]),
+        Waited::Served(Err(e)) => Frame::Error(e.to_string()),
+        Waited::TimedOut => Frame::NullArray,
+        Waited::Interrupted => return Ok(()),
+    };
+
+    conn.write_frame(&res).await?;
+    Ok(())
+}
+
+/// Reads the timeout closing a blocking command, in seconds. Zero waits forever.
+fn parse_timeout(timeout: &str) -> anyhow::Result<Option<Duration>> {
+    let secs = timeout
+        .parse::<f64>()
+        .ok()
+        .filter(|secs| secs.is_finite())
+        .ok_or_else(|| anyhow!("timeout is not a float or out of range"))?;
+
+    if secs < 0.0 {
+        return Err(anyhow!("timeout is negative"));
+    }
+
+    if secs == 0.0 {
+        return Ok(None);
+    }
+
+    Duration::try_from_secs_f64(secs)
+        .map(Some)
+        .map_err(|_| anyhow!("timeout is out of range"))
+}
+
+/// Reads keys followed by a timeout.
+pub(super) fn parse_keys(parser: &mut Parse) -> anyhow::Result<(Vec<String>, Option<Duration>)> {
+    let mut keys = vec![parser.next_string()?];
+    keys.extend(parser.remaining_strings()?);
+
+    let timeout = match keys.len() {
+        1 => return Err(ParseError::EndOfStream.into()),
+        _ => keys.pop().unwrap(),
+    };
+
+    Ok((keys, parse_timeout(&timeout)?))
+}
+
+pub(super) fn timeout_bytes(timeout: Option<Duration>) -> Bytes {
+    let secs = timeout.map_or(0.0, |t| t.as_secs_f64());
+    Bytes::from(secs.to_string().into_bytes())
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for BLPop {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        shutdown: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        blocking_pop(self.keys, ListEnd::Left, self.timeout, db, conn, shutdown).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for BRPop {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        shutdown: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        blocking_pop(self.keys, ListEnd::Right, self.timeout, db, conn, shutdown).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for BLMove {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        shutdown: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let to = Some((self.dst, self.to.end()));
+        let waited = pop_or_wait(
+            vec![self.src],
+            self.from.end(),
+            to,
+            self.timeout,
+            db,
+            conn,
+            shutdown,
+        )
+        .await;
+
+        let res = match waited {
+            Waited::Served(Ok((_, value))) => Frame::Bulk(value),
+            Waited::Served(Err(e)) => Frame::Error(e.to_string()),
+            Waited::TimedOut => Frame::Null,
+            Waited::Interrupted => return Ok(()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for BLPop {
+    fn representation<'a>() -> &'a str {
+        "blpop"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let (keys, timeout) = parse_keys(parser)?;
+        Ok(Self { keys, timeout })
+    }
+}
+
+impl Command for BRPop {
+    fn representation<'a>() -> &'a str {
+        "brpop"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let (keys, timeout) = parse_keys(parser)?;
+        Ok(Self { keys, timeout })
+    }
+}
+
+impl Command for BLMove {
+    fn representation<'a>() -> &'a str {
+        "blmove"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            src: parser.next_string()?,
+            dst: parser.next_string()?,
+            from: Direction::parse(parser)?,
+            to: Direction::parse(parser)?,
+            timeout: parse_timeout(&parser.next_string()?)?,
+        })
+    }
+}
+
+impl TryInto<Frame> for BLPop {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), self.keys)?;
+        frame.push_bulk(timeout_bytes(self.timeout))?;
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for BRPop {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), self.keys)?;
+        frame.push_bulk(timeout_bytes(self.timeout))?;
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for BLMove {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.src, self.dst])?;
+        frame.push_bulk(Bytes::from(self.from.as_str().as_bytes()))?;
+        frame.push_bulk(Bytes::from(self.to.as_str().as_bytes()))?;
+        frame.push_bulk(timeout_bytes(self.timeout))?;
+        Ok(frame)
+    }
+}
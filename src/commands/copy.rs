@@ -0,0 +1,90 @@
+use anyhow::anyhow;
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::{Parse, ParseError},
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+/// The `COPY` command. There is a single database, so the `DB` option is not supported.
+#[derive(Debug)]
+pub(crate) struct CopyKey {
+    src: String,
+    dst: String,
+    replace: bool,
+}
+
+impl CopyKey {
+    pub(crate) fn new(src: impl ToString, dst: impl ToString, replace: bool) -> Self {
+        Self {
+            src: src.to_string(),
+            dst: dst.to_string(),
+            replace,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for CopyKey {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let res = if self.src == self.dst {
+            Frame::Error("ERR source and destination objects are the same".to_string())
+        } else {
+            Frame::Integer(db.copy(&self.src, &self.dst, self.replace) as i64)
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for CopyKey {
+    fn representation<'a>() -> &'a str {
+        "copy"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let src = parser.next_string()?;
+        let dst = parser.next_string()?;
+
+        let replace = match parser.next_string() {
+            Ok(s) if s.to_lowercase() == "replace" => true,
+            Ok(_) => return Err(anyhow!("syntax error")),
+            Err(ParseError::EndOfStream) => false,
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self { src, dst, replace })
+    }
+}
+
+impl TryInto<Frame> for CopyKey {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.src, self.dst])?;
+
+        if self.replace {
+            frame.push_bulk(Bytes::from("replace".as_bytes()))?;
+        }
+
+        Ok(frame)
+    }
+}
@@ -0,0 +1,106 @@
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+#[derive(Debug)]
+pub(crate) struct Del {
+    keys: Vec<String>,
+}
+
+/// Same as [`Del`]. Values are freed along with their entry, so there is nothing left to
+/// reclaim in the background.
+#[derive(Debug)]
+pub(crate) struct Unlink {
+    keys: Vec<String>,
+}
+
+impl Del {
+    pub(crate) fn new(keys: Vec<String>) -> Self {
+        Self { keys }
+    }
+}
+
+impl Unlink {
+    pub(crate) fn new(keys: Vec<String>) -> Self {
+        Self { keys }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for Del {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let res = Frame::Integer(db.del(&self.keys) as i64);
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for Unlink {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        shutdown: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        Del { keys: self.keys }.execute(db, conn, shutdown).await
+    }
+}
+
+impl Command for Del {
+    fn representation<'a>() -> &'a str {
+        "del"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let mut keys = vec![parser.next_string()?];
+        keys.extend(parser.remaining_strings()?);
+
+        Ok(Self { keys })
+    }
+}
+
+impl Command for Unlink {
+    fn representation<'a>() -> &'a str {
+        "unlink"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Del::parse_from_frame(parser).map(|del| Self { keys: del.keys })
+    }
+}
+
+impl TryInto<Frame> for Del {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), self.keys)
+    }
+}
+
+impl TryInto<Frame> for Unlink {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), self.keys)
+    }
+}
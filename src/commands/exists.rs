@@ -0,0 +1,62 @@
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+#[derive(Debug)]
+pub(crate) struct Exists {
+    keys: Vec<String>,
+}
+
+impl Exists {
+    pub(crate) fn new(keys: Vec<String>) -> Self {
+        Self { keys }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for Exists {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let res = Frame::Integer(db.exists(&self.keys) as i64);
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for Exists {
+    fn representation<'a>() -> &'a str {
+        "exists"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let mut keys = vec![parser.next_string()?];
+        keys.extend(parser.remaining_strings()?);
+
+        Ok(Self { keys })
+    }
+}
+
+impl TryInto<Frame> for Exists {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), self.keys)
+    }
+}
@@ -0,0 +1,352 @@
+use anyhow::anyhow;
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use {
+    async_trait::async_trait,
+    std::time::{SystemTime, UNIX_EPOCH},
+    tokio::time::{Duration, Instant},
+};
+
+use crate::{
+    commands::Command,
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::{Parse, ParseError},
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+/// Conditions on the current expiration of a key, under which the EXPIRE family changes it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ExpireCondition {
+    /// The key has no expiration.
+    Nx,
+    /// The key has an expiration.
+    Xx,
+    /// The new expiration is later than the current one.
+    Gt,
+    /// The new expiration is earlier than the current one.
+    Lt,
+}
+
+impl ExpireCondition {
+    /// Reads a condition, given in any case.
+    pub(super) fn parse(option: &str) -> Option<Self> {
+        match option.to_lowercase().as_str() {
+            "nx" => Some(ExpireCondition::Nx),
+            "xx" => Some(ExpireCondition::Xx),
+            "gt" => Some(ExpireCondition::Gt),
+            "lt" => Some(ExpireCondition::Lt),
+            _ => None,
+        }
+    }
+
+    pub(super) fn as_str(&self) -> &'static str {
+        match self {
+            ExpireCondition::Nx => "nx",
+            ExpireCondition::Xx => "xx",
+            ExpireCondition::Gt => "gt",
+            ExpireCondition::Lt => "lt",
+        }
+    }
+
+    /// Keys without an expiration are considered to never expire when comparing expirations.
+    #[cfg(feature = "server")]
+    pub(super) fn allows(&self, current: Option<Instant>, new: Instant) -> bool {
+        match self {
+            ExpireCondition::Nx => current.is_none(),
+            ExpireCondition::Xx => current.is_some(),
+            ExpireCondition::Gt => current.is_some_and(|current| new > current),
+            ExpireCondition::Lt => current.is_none_or(|current| new < current),
+        }
+    }
+}
+
+/// Arguments shared by EXPIRE, PEXPIRE, EXPIREAT and PEXPIREAT. `time` is interpreted by
+/// each command.
+#[derive(Debug)]
+pub(crate) struct ExpireArgs {
+    key: String,
+    time: i64,
+    conditions: Vec<ExpireCondition>,
+}
+
+impl ExpireArgs {
+    pub(crate) fn new(key: impl ToString, time: i64, conditions: Vec<ExpireCondition>) -> Self {
+        Self {
+            key: key.to_string(),
+            time,
+            conditions,
+        }
+    }
+
+    fn parse(parser: &mut Parse) -> anyhow::Result<Self> {
+        let key = parser.next_string()?;
+        let time = parser.next_int()?;
+
+        let mut conditions = vec![];
+
+        loop {
+            let condition = match parser.next_string() {
+                Ok(s) => match ExpireCondition::parse(&s) {
+                    Some(condition) => condition,
+                    None => return Err(anyhow!("Unsupported option {s}")),
+                },
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            };
+
+            conditions.push(condition);
+        }
+
+        let has = |condition| conditions.contains(&condition);
+
+        if has(ExpireCondition::Nx) && conditions.iter().any(|c| *c != ExpireCondition::Nx) {
+            return Err(anyhow!(
+                "NX and XX, GT or LT options at the same time are not compatible"
+            ));
+        }
+
+        if has(ExpireCondition::Gt) && has(ExpireCondition::Lt) {
+            return Err(anyhow!(
+                "GT and LT options at the same time are not compatible"
+            ));
+        }
+
+        Ok(Self {
+            key,
+            time,
+            conditions,
+        })
+    }
+
+    /// Applies an expiration `delay_ms` milliseconds from now, `None` standing for a delay
+    /// that cannot be represented.
+    #[cfg(feature = "server")]
+    async fn apply(
+        self,
+        name: &str,
+        delay_ms: Option<i64>,
+        db: &Database,
+        conn: &mut Connection,
+    ) -> anyhow::Result<()> {
+        // expirations in the past are brought forward to now, removing the key
+        let time = delay_ms
+            .and_then(|ms| Instant::now().checked_add(Duration::from_millis(ms.max(0) as u64)));
+
+        let res = match time {
+            Some(time) => {
+                let conditions = self.conditions;
+                let changed = db.expire(&self.key, time, |current| {
+                    conditions.iter().all(|c| c.allows(current, time))
+                });
+
+                Frame::Integer(changed as i64)
+            }
+            None => Frame::Error(format!("ERR invalid expire time in '{name}' command")),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+
+    fn into_frame(self, name: &str) -> Result<Frame, FrameError> {
+        let mut frame = Frame::Array(vec![]);
+
+        frame.push_bulk(Bytes::from(name.as_bytes().to_owned()))?;
+        frame.push_bulk(Bytes::from(self.key.into_bytes()))?;
+        frame.push_int(self.time)?;
+
+        for condition in self.conditions {
+            frame.push_bulk(Bytes::from(condition.as_str().as_bytes()))?;
+        }
+
+        Ok(frame)
+    }
+}
+
+/// Milliseconds elapsed since the unix epoch.
+#[cfg(feature = "server")]
+pub(super) fn unix_time_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as i64)
+}
+
+/// Sets a key's time to live in seconds.
+#[derive(Debug)]
+pub(crate) struct Expire(ExpireArgs);
+
+/// Sets a key's time to live in milliseconds.
+#[derive(Debug)]
+pub(crate) struct PExpire(ExpireArgs);
+
+/// Sets the unix time, in seconds, at which a key expires.
+#[derive(Debug)]
+pub(crate) struct ExpireAt(ExpireArgs);
+
+/// Sets the unix time, in milliseconds, at which a key expires.
+#[derive(Debug)]
+pub(crate) struct PExpireAt(ExpireArgs);
+
+impl Expire {
+    pub(crate) fn new(args: ExpireArgs) -> Self {
+        Self(args)
+    }
+}
+
+impl PExpire {
+    pub(crate) fn new(args: ExpireArgs) -> Self {
+        Self(args)
+    }
+}
+
+impl ExpireAt {
+    pub(crate) fn new(args: ExpireArgs) -> Self {
+        Self(args)
+    }
+}
+
+impl PExpireAt {
+    pub(crate) fn new(args: ExpireArgs) -> Self {
+        Self(args)
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for Expire {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let delay = self.0.time.checked_mul(1000);
+        self.0.apply(Self::representation(), delay, db, conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for PExpire {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let delay = Some(self.0.time);
+        self.0.apply(Self::representation(), delay, db, conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for ExpireAt {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let delay = self
+            .0
+            .time
+            .checked_mul(1000)
+            .map(|ms| ms.saturating_sub(unix_time_ms()));
+
+        self.0.apply(Self::representation(), delay, db, conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for PExpireAt {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let delay = Some(self.0.time.saturating_sub(unix_time_ms()));
+        self.0.apply(Self::representation(), delay, db, conn).await
+    }
+}
+
+impl Command for Expire {
+    fn representation<'a>() -> &'a str {
+        "expire"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        ExpireArgs::parse(parser).map(Self)
+    }
+}
+
+impl Command for PExpire {
+    fn representation<'a>() -> &'a str {
+        "pexpire"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        ExpireArgs::parse(parser).map(Self)
+    }
+}
+
+impl Command for ExpireAt {
+    fn representation<'a>() -> &'a str {
+        "expireat"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        ExpireArgs::parse(parser).map(Self)
+    }
+}
+
+impl Command for PExpireAt {
+    fn representation<'a>() -> &'a str {
+        "pexpireat"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        ExpireArgs::parse(parser).map(Self)
+    }
+}
+
+impl TryInto<Frame> for Expire {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        self.0.into_frame(Self::representation())
+    }
+}
+
+impl TryInto<Frame> for PExpire {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        self.0.into_frame(Self::representation())
+    }
+}
+
+impl TryInto<Frame> for ExpireAt {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        self.0.into_frame(Self::representation())
+    }
+}
+
+impl TryInto<Frame> for PExpireAt {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        self.0.into_frame(Self::representation())
+    }
+}
@@ -34,10 +34,10 @@ impl Execute for Get {
         conn: &mut Connection,
         shutdown: &mut ShutdownListener,
     ) -> anyhow::Result<()> {
-        let res = if let Some(v) = db.get(&self.key) {
-            Frame::Bulk(v)
-        } else {
-            Frame::Null
+        let res = match db.get(&self.key) {
+            Ok(Some(v)) => Frame::Bulk(v),
+            Ok(None) => Frame::Null,
+            Err(e) => Frame::Error(e.to_string()),
         };
 
         debug!(?res);
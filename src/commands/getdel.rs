@@ -0,0 +1,67 @@
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+#[derive(Debug)]
+pub(crate) struct GetDel {
+    key: String,
+}
+
+impl GetDel {
+    pub(crate) fn new(key: impl ToString) -> Self {
+        Self {
+            key: key.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for GetDel {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let res = match db.get_del(&self.key) {
+            Ok(previous) => previous.map_or(Frame::Null, Frame::Bulk),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for GetDel {
+    fn representation<'a>() -> &'a str {
+        "getdel"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+        })
+    }
+}
+
+impl TryInto<Frame> for GetDel {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), vec![self.key])
+    }
+}
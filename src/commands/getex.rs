@@ -0,0 +1,132 @@
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{
+        keys_frame,
+        set::{syntax_error, SetExpiry},
+        Command,
+    },
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::{Parse, ParseError},
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{
+        database::database::{Database, SetExpiration},
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// Gets a key and optionally changes its expiration.
+#[derive(Debug)]
+pub(crate) struct GetEx {
+    key: String,
+    /// The new expiration, never [`SetExpiry::KeepTtl`].
+    expiry: Option<SetExpiry>,
+    persist: bool,
+}
+
+impl GetEx {
+    pub(crate) fn new(key: impl ToString) -> Self {
+        Self {
+            key: key.to_string(),
+            expiry: None,
+            persist: false,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for GetEx {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let expiration = match self.expiry {
+            Some(expiry) => expiry.resolve(),
+            None if self.persist => Some(SetExpiration::Persist),
+            None => Some(SetExpiration::Keep),
+        };
+
+        let Some(expiration) = expiration else {
+            let res = Frame::Error("ERR invalid expire time in 'getex' command".to_string());
+            conn.write_frame(&res).await?;
+            return Ok(());
+        };
+
+        let res = match db.get_and_expire(&self.key, expiration) {
+            Ok(value) => value.map_or(Frame::Null, Frame::Bulk),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for GetEx {
+    fn representation<'a>() -> &'a str {
+        "getex"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let mut getex = Self::new(parser.next_string()?);
+
+        loop {
+            let option = match parser.next_string() {
+                Ok(s) => s.to_lowercase(),
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            };
+
+            if getex.expiry.is_some() || getex.persist {
+                return Err(syntax_error());
+            }
+
+            match option.as_str() {
+                "persist" => getex.persist = true,
+                "ex" | "px" | "exat" | "pxat" => {
+                    getex.expiry = Some(SetExpiry::parse(&option, Self::representation(), parser)?);
+                }
+                _ => return Err(syntax_error()),
+            }
+        }
+
+        Ok(getex)
+    }
+}
+
+impl TryInto<Frame> for GetEx {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+
+        let (option, time) = match self.expiry {
+            Some(SetExpiry::Ex(t)) => ("ex", t),
+            Some(SetExpiry::Px(t)) => ("px", t),
+            Some(SetExpiry::ExAt(t)) => ("exat", t),
+            Some(SetExpiry::PxAt(t)) => ("pxat", t),
+            Some(SetExpiry::KeepTtl) | None => {
+                if self.persist {
+                    frame.push_bulk(Bytes::from("persist".as_bytes()))?;
+                }
+
+                return Ok(frame);
+            }
+        };
+
+        frame.push_bulk(Bytes::from(option.as_bytes()))?;
+        frame.push_int(time as i64)?;
+        Ok(frame)
+    }
+}
@@ -0,0 +1,78 @@
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{
+        database::database::{Database, SetExpiration},
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// Sets a key and replies with its previous value. Same as `SET key value GET`.
+#[derive(Debug)]
+pub(crate) struct GetSet {
+    key: String,
+    value: Bytes,
+}
+
+impl GetSet {
+    pub(crate) fn new(key: impl ToString, value: Bytes) -> Self {
+        Self {
+            key: key.to_string(),
+            value,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for GetSet {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let res = match db.set(self.key, self.value, SetExpiration::Persist, true, |_| true) {
+            Ok((previous, _)) => previous.map_or(Frame::Null, Frame::Bulk),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for GetSet {
+    fn representation<'a>() -> &'a str {
+        "getset"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+            value: parser.next_bytes()?,
+        })
+    }
+}
+
+impl TryInto<Frame> for GetSet {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_bulk(self.value)?;
+        Ok(frame)
+    }
+}
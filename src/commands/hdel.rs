@@ -0,0 +1,90 @@
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{
+        database::{database::Database, hash::Hash},
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// Removes fields of a hash and replies with how many existed.
+#[derive(Debug)]
+pub(crate) struct HDel {
+    key: String,
+    fields: Vec<Bytes>,
+}
+
+impl HDel {
+    pub(crate) fn new(key: impl ToString, fields: Vec<Bytes>) -> Self {
+        Self {
+            key: key.to_string(),
+            fields,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for HDel {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let removed = db.edit_collection(&self.key, false, |hash: &mut Hash| {
+            self.fields
+                .iter()
+                .filter(|field| hash.remove(field))
+                .count()
+        });
+
+        let res = match removed {
+            Ok(removed) => Frame::Integer(removed.unwrap_or(0) as i64),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for HDel {
+    fn representation<'a>() -> &'a str {
+        "hdel"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let key = parser.next_string()?;
+        let mut fields = vec![parser.next_bytes()?];
+        fields.extend(parser.remaining_bytes()?);
+
+        Ok(Self { key, fields })
+    }
+}
+
+impl TryInto<Frame> for HDel {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+
+        for field in self.fields {
+            frame.push_bulk(field)?;
+        }
+
+        Ok(frame)
+    }
+}
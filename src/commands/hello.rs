@@ -0,0 +1,164 @@
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    codec::Protocol,
+    commands::Command,
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::{Parse, ParseError},
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+/// The only user known to the server. No password can be configured for it yet, so
+/// authenticating as it is refused rather than any password being accepted.
+const DEFAULT_USER: &str = "default";
+
+#[derive(Debug, Default)]
+pub(crate) struct Hello {
+    protocol: Option<i64>,
+    auth: Option<(String, String)>,
+    client_name: Option<String>,
+}
+
+impl Hello {
+    pub(crate) fn new(
+        protocol: Option<i64>,
+        auth: Option<(String, String)>,
+        client_name: Option<String>,
+    ) -> Self {
+        Self {
+            protocol,
+            auth,
+            client_name,
+        }
+    }
+
+    fn assemble_response(protocol: Protocol) -> Frame {
+        let bulk = |s: &str| Frame::Bulk(Bytes::from(s.to_owned()));
+
+        Frame::Map(vec![
+            (bulk("server"), bulk("insomnia_db")),
+            (bulk("version"), bulk(env!("CARGO_PKG_VERSION"))),
+            (bulk("proto"), Frame::Integer(protocol.version())),
+            (bulk("mode"), bulk("standalone")),
+            (bulk("role"), bulk("master")),
+            (bulk("modules"), Frame::Array(vec![])),
+        ])
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for Hello {
+    async fn execute(
+        self,
+        _: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let protocol = match self.protocol.map(Protocol::try_from) {
+            Some(Ok(protocol)) => protocol,
+            Some(Err(_)) => {
+                let res = Frame::Error("NOPROTO unsupported protocol version".to_string());
+                conn.write_frame(&res).await?;
+                return Ok(());
+            }
+            None => conn.protocol(),
+        };
+
+        if let Some((user, _)) = &self.auth {
+            let res = match user == DEFAULT_USER {
+                true => Frame::Error(
+                    "ERR AUTH <password> called without any password configured for the default \
+                     user. Are you sure your configuration is correct?"
+                        .to_string(),
+                ),
+                false => Frame::Error(
+                    "WRONGPASS invalid username-password pair or user is disabled.".to_string(),
+                ),
+            };
+            conn.write_frame(&res).await?;
+            return Ok(());
+        }
+
+        if let Some(name) = self.client_name {
+            conn.set_name(name);
+        }
+
+        conn.set_protocol(protocol);
+        conn.write_frame(&Self::assemble_response(protocol)).await?;
+        Ok(())
+    }
+}
+
+impl Command for Hello {
+    fn representation<'a>() -> &'a str {
+        "hello"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let protocol = match parser.next_int() {
+            Ok(v) => v,
+            Err(ParseError::EndOfStream) => return Ok(Hello::default()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut hello = Hello {
+            protocol: Some(protocol),
+            ..Default::default()
+        };
+
+        loop {
+            match parser.next_string() {
+                Ok(s) if s.to_lowercase() == "auth" => {
+                    let user = parser.next_string()?;
+                    let pass = parser.next_string()?;
+                    hello.auth = Some((user, pass));
+                }
+                Ok(s) if s.to_lowercase() == "setname" => {
+                    hello.client_name = Some(parser.next_string()?);
+                }
+                Ok(s) => return Err(anyhow::anyhow!("Syntax error in HELLO option '{s}'")),
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(hello)
+    }
+}
+
+impl TryInto<Frame> for Hello {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = Frame::Array(vec![]);
+
+        frame.push_bulk(Bytes::from(Self::representation().as_bytes().to_owned()))?;
+
+        if let Some(protocol) = self.protocol {
+            frame.push_int(protocol)?;
+
+            if let Some((user, pass)) = self.auth {
+                frame.push_bulk(Bytes::from("auth".as_bytes()))?;
+                frame.push_bulk(Bytes::from(user.into_bytes()))?;
+                frame.push_bulk(Bytes::from(pass.into_bytes()))?;
+            }
+
+            if let Some(name) = self.client_name {
+                frame.push_bulk(Bytes::from("setname".as_bytes()))?;
+                frame.push_bulk(Bytes::from(name.into_bytes()))?;
+            }
+        }
+
+        Ok(frame)
+    }
+}
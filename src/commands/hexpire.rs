@@ -0,0 +1,334 @@
+use anyhow::anyhow;
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use {
+    async_trait::async_trait,
+    tokio::time::{Duration, Instant},
+};
+
+use crate::{
+    commands::{expire::ExpireCondition, keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{
+        database::{database::Database, entry::WrongType, hash::Hash},
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// Sets the time to live of fields of a hash, in seconds. Replies with a code for every
+/// field: -2 if it does not exist, 0 if the condition was not met, 1 if the expiration was
+/// set and 2 if the field was removed, its expiration being in the past.
+#[derive(Debug)]
+pub(crate) struct HExpire {
+    key: String,
+    seconds: i64,
+    condition: Option<ExpireCondition>,
+    fields: Vec<Bytes>,
+}
+
+/// Replies with the time to live of fields of a hash in seconds, -1 for fields that do not
+/// expire and -2 for fields that do not exist.
+#[derive(Debug)]
+pub(crate) struct HTtl {
+    key: String,
+    fields: Vec<Bytes>,
+}
+
+/// Removes the expiration of fields of a hash. Replies with 1 for every field whose
+/// expiration was removed, -1 for fields that do not expire and -2 for fields that do not
+/// exist.
+#[derive(Debug)]
+pub(crate) struct HPersist {
+    key: String,
+    fields: Vec<Bytes>,
+}
+
+impl HExpire {
+    pub(crate) fn new(
+        key: impl ToString,
+        seconds: i64,
+        condition: Option<ExpireCondition>,
+        fields: Vec<Bytes>,
+    ) -> Self {
+        Self {
+            key: key.to_string(),
+            seconds,
+            condition,
+            fields,
+        }
+    }
+}
+
+impl HTtl {
+    pub(crate) fn new(key: impl ToString, fields: Vec<Bytes>) -> Self {
+        Self {
+            key: key.to_string(),
+            fields,
+        }
+    }
+}
+
+impl HPersist {
+    pub(crate) fn new(key: impl ToString, fields: Vec<Bytes>) -> Self {
+        Self {
+            key: key.to_string(),
+            fields,
+        }
+    }
+}
+
+/// Reads the `FIELDS numfields field...` clause closing the field expiration commands.
+fn parse_fields(parser: &mut Parse) -> anyhow::Result<Vec<Bytes>> {
+    let missing = || anyhow!("Mandatory argument FIELDS is missing or not at the right position");
+
+    if !parser.next_string()?.eq_ignore_ascii_case("fields") {
+        return Err(missing());
+    }
+
+    let count = parser.next_int().map_err(|_| missing())?;
+
+    if count <= 0 {
+        return Err(anyhow!("Parameter `numFields` should be greater than 0"));
+    }
+
+    let fields = parser.remaining_bytes()?;
+
+    if fields.len() as i64 != count {
+        return Err(anyhow!(
+            "The `numfields` parameter must match the number of arguments"
+        ));
+    }
+
+    Ok(fields)
+}
+
+fn fields_frame(name: &str, key: String, fields: Vec<Bytes>) -> Result<Frame, FrameError> {
+    let mut frame = keys_frame(name, vec![key])?;
+    push_fields(&mut frame, fields)?;
+    Ok(frame)
+}
+
+/// Appends the `FIELDS numfields field...` tail closing every hash field expiry command.
+fn push_fields(frame: &mut Frame, fields: Vec<Bytes>) -> Result<(), FrameError> {
+    frame.push_bulk(Bytes::from_static(b"FIELDS"))?;
+    frame.push_int(fields.len() as i64)?;
+
+    for field in fields {
+        frame.push_bulk(field)?;
+    }
+
+    Ok(())
+}
+
+/// Replies with a code per field, every field being missing if the key does not exist.
+#[cfg(feature = "server")]
+async fn reply_codes(
+    codes: Result<Option<Vec<i64>>, WrongType>,
+    fields: usize,
+    conn: &mut Connection,
+) -> anyhow::Result<()> {
+    let res = match codes {
+        Ok(codes) => Frame::Array(
+            codes
+                .unwrap_or_else(|| vec![-2; fields])
+                .into_iter()
+                .map(Frame::Integer)
+                .collect(),
+        ),
+        Err(e) => Frame::Error(e.to_string()),
+    };
+
+    conn.write_frame(&res).await?;
+    Ok(())
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for HExpire {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let now = Instant::now();
+        let Some(time) = now.checked_add(Duration::from_secs(self.seconds as u64)) else {
+            let res = Frame::Error("ERR invalid expire time in 'hexpire' command".to_string());
+            conn.write_frame(&res).await?;
+            return Ok(());
+        };
+
+        let condition = self.condition;
+        let fields = &self.fields;
+        let codes = db.edit_collection(&self.key, false, |hash: &mut Hash| {
+            fields
+                .iter()
+                .map(|field| match hash.expiration(field) {
+                    None => -2,
+                    Some(current) if !condition.is_none_or(|c| c.allows(current, time)) => 0,
+                    Some(_) if time <= now => {
+                        hash.remove(field);
+                        2
+                    }
+                    Some(_) => {
+                        hash.set_expiration(field, Some(time));
+                        1
+                    }
+                })
+                .collect()
+        });
+
+        reply_codes(codes, self.fields.len(), conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for HTtl {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let now = Instant::now();
+        let fields = &self.fields;
+        let codes = db.read_collection(&self.key, |hash: &Hash| {
+            fields
+                .iter()
+                .map(|field| match hash.expiration(field) {
+                    None => -2,
+                    Some(None) => -1,
+                    Some(Some(time)) => {
+                        let ms = time.saturating_duration_since(now).as_millis() as i64;
+                        (ms + 500) / 1000
+                    }
+                })
+                .collect()
+        });
+
+        reply_codes(codes, self.fields.len(), conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for HPersist {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let fields = &self.fields;
+        let codes = db.edit_collection(&self.key, false, |hash: &mut Hash| {
+            fields
+                .iter()
+                .map(|field| match hash.expiration(field) {
+                    None => -2,
+                    Some(None) => -1,
+                    Some(Some(_)) => {
+                        hash.set_expiration(field, None);
+                        1
+                    }
+                })
+                .collect()
+        });
+
+        reply_codes(codes, self.fields.len(), conn).await
+    }
+}
+
+impl Command for HExpire {
+    fn representation<'a>() -> &'a str {
+        "hexpire"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let key = parser.next_string()?;
+        let seconds = parser.next_int()?;
+
+        if seconds < 0 {
+            return Err(anyhow!("invalid expire time, must be >= 0"));
+        }
+
+        let condition = ExpireCondition::parse(&parser.peek_string()?);
+
+        if condition.is_some() {
+            parser.next_string()?;
+        }
+
+        Ok(Self {
+            key,
+            seconds,
+            condition,
+            fields: parse_fields(parser)?,
+        })
+    }
+}
+
+impl Command for HTtl {
+    fn representation<'a>() -> &'a str {
+        "httl"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+            fields: parse_fields(parser)?,
+        })
+    }
+}
+
+impl Command for HPersist {
+    fn representation<'a>() -> &'a str {
+        "hpersist"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+            fields: parse_fields(parser)?,
+        })
+    }
+}
+
+impl TryInto<Frame> for HExpire {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_int(self.seconds)?;
+
+        if let Some(condition) = self.condition {
+            frame.push_bulk(Bytes::from(condition.as_str().as_bytes()))?;
+        }
+
+        push_fields(&mut frame, self.fields)?;
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for HTtl {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        fields_frame(Self::representation(), self.key, self.fields)
+    }
+}
+
+impl TryInto<Frame> for HPersist {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        fields_frame(Self::representation(), self.key, self.fields)
+    }
+}
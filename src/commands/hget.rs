@@ -0,0 +1,217 @@
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{
+        database::{database::Database, hash::Hash},
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// Gets the value of a field of a hash.
+#[derive(Debug)]
+pub(crate) struct HGet {
+    key: String,
+    field: Bytes,
+}
+
+/// Gets the values of fields of a hash, `nil` standing for missing fields.
+#[derive(Debug)]
+pub(crate) struct HMGet {
+    key: String,
+    fields: Vec<Bytes>,
+}
+
+/// Gets every field of a hash along with its value, as a map where the protocol has them.
+#[derive(Debug)]
+pub(crate) struct HGetAll {
+    key: String,
+}
+
+impl HGet {
+    pub(crate) fn new(key: impl ToString, field: Bytes) -> Self {
+        Self {
+            key: key.to_string(),
+            field,
+        }
+    }
+}
+
+impl HMGet {
+    pub(crate) fn new(key: impl ToString, fields: Vec<Bytes>) -> Self {
+        Self {
+            key: key.to_string(),
+            fields,
+        }
+    }
+}
+
+impl HGetAll {
+    pub(crate) fn new(key: impl ToString) -> Self {
+        Self {
+            key: key.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for HGet {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let value = db.read_collection(&self.key, |hash: &Hash| hash.get(&self.field).cloned());
+
+        let res = match value {
+            Ok(value) => value.flatten().map_or(Frame::Null, Frame::Bulk),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for HMGet {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let values = db.read_collection(&self.key, |hash: &Hash| {
+            self.fields
+                .iter()
+                .map(|field| hash.get(field).cloned())
+                .collect::<Vec<_>>()
+        });
+
+        let res = match values {
+            Ok(values) => {
+                let values = values.unwrap_or_else(|| vec![None; self.fields.len()]);
+                Frame::Array(
+                    values
+                        .into_iter()
+                        .map(|v| v.map_or(Frame::Null, Frame::Bulk))
+                        .collect(),
+                )
+            }
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for HGetAll {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let pairs = db.read_collection(&self.key, |hash: &Hash| {
+            hash.iter()
+                .map(|(field, value)| (Frame::Bulk(field.clone()), Frame::Bulk(value.clone())))
+                .collect()
+        });
+
+        let res = match pairs {
+            Ok(pairs) => Frame::Map(pairs.unwrap_or_default()),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for HGet {
+    fn representation<'a>() -> &'a str {
+        "hget"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+            field: parser.next_bytes()?,
+        })
+    }
+}
+
+impl Command for HMGet {
+    fn representation<'a>() -> &'a str {
+        "hmget"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let key = parser.next_string()?;
+        let mut fields = vec![parser.next_bytes()?];
+        fields.extend(parser.remaining_bytes()?);
+
+        Ok(Self { key, fields })
+    }
+}
+
+impl Command for HGetAll {
+    fn representation<'a>() -> &'a str {
+        "hgetall"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+        })
+    }
+}
+
+impl TryInto<Frame> for HGet {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_bulk(self.field)?;
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for HMGet {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+
+        for field in self.fields {
+            frame.push_bulk(field)?;
+        }
+
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for HGetAll {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), vec![self.key])
+    }
+}
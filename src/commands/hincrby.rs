@@ -0,0 +1,106 @@
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::{incr::parse_integer, Execute},
+    server::{
+        database::{database::Database, hash::Hash},
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// Adds to the integer stored at a field of a hash, a missing field counting as `0`, and
+/// replies with the result.
+#[derive(Debug)]
+pub(crate) struct HIncrBy {
+    key: String,
+    field: Bytes,
+    increment: i64,
+}
+
+impl HIncrBy {
+    pub(crate) fn new(key: impl ToString, field: Bytes, increment: i64) -> Self {
+        Self {
+            key: key.to_string(),
+            field,
+            increment,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for HIncrBy {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let res = db.edit_collection(&self.key, true, |hash: &mut Hash| -> Result<_, &str> {
+            let current = match hash.get(&self.field) {
+                Some(buf) => parse_integer(buf).ok_or("ERR hash value is not an integer")?,
+                None => 0,
+            };
+
+            let value = current
+                .checked_add(self.increment)
+                .ok_or("ERR increment or decrement would overflow")?;
+
+            // the field keeps its expiration
+            match hash.get_mut(&self.field) {
+                Some(buf) => *buf = Bytes::from(value.to_string()),
+                None => {
+                    hash.insert(self.field, Bytes::from(value.to_string()));
+                }
+            }
+
+            Ok(value)
+        });
+
+        let res = match res {
+            Ok(Some(Ok(value))) => Frame::Integer(value),
+            Ok(Some(Err(e))) => Frame::Error(e.to_string()),
+            Ok(None) => unreachable!("missing hashes are created"),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for HIncrBy {
+    fn representation<'a>() -> &'a str {
+        "hincrby"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+            field: parser.next_bytes()?,
+            increment: parser.next_int()?,
+        })
+    }
+}
+
+impl TryInto<Frame> for HIncrBy {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_bulk(self.field)?;
+        frame.push_int(self.increment)?;
+        Ok(frame)
+    }
+}
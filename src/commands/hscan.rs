@@ -0,0 +1,165 @@
+use anyhow::anyhow;
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{keys_frame, set::syntax_error, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::{Parse, ParseError},
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{
+        database::{database::Database, hash::Hash},
+        glob,
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// Fields returned per call when no `COUNT` is given.
+const DEFAULT_COUNT: usize = 10;
+
+/// Iterates over the fields of a hash, a few per call. Fields present for a whole iteration
+/// are returned exactly once.
+#[derive(Debug)]
+pub(crate) struct HScan {
+    key: String,
+    cursor: u64,
+    /// Only fields matching this glob-style pattern are returned.
+    pattern: Option<Bytes>,
+    count: Option<usize>,
+    /// Return the fields without their values.
+    no_values: bool,
+}
+
+impl HScan {
+    pub(crate) fn new(key: impl ToString, cursor: u64) -> Self {
+        Self {
+            key: key.to_string(),
+            cursor,
+            pattern: None,
+            count: None,
+            no_values: false,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for HScan {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let count = self.count.unwrap_or(DEFAULT_COUNT);
+
+        let scanned = db.read_collection(&self.key, |hash: &Hash| {
+            let (cursor, fields) = hash.scan(self.cursor, count);
+            let mut items = vec![];
+
+            // the pattern filters the fields scanned rather than choosing them
+            let fields = fields.into_iter().filter(|(field, _)| {
+                self.pattern
+                    .as_ref()
+                    .is_none_or(|pattern| glob::matches(pattern, field))
+            });
+
+            for (field, value) in fields {
+                items.push(Frame::Bulk(field.clone()));
+
+                if !self.no_values {
+                    items.push(Frame::Bulk(value.clone()));
+                }
+            }
+
+            (cursor, items)
+        });
+
+        let res = match scanned {
+            Ok(scanned) => {
+                let (cursor, items) = scanned.unwrap_or_default();
+
+                Frame::Array(vec![
+                    Frame::Bulk(Bytes::from(cursor.to_string())),
+                    Frame::Array(items),
+                ])
+            }
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for HScan {
+    fn representation<'a>() -> &'a str {
+        "hscan"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let key = parser.next_string()?;
+        let cursor = parser
+            .next_string()?
+            .parse()
+            .map_err(|_| anyhow!("invalid cursor"))?;
+
+        let mut scan = Self::new(key, cursor);
+
+        loop {
+            let option = match parser.next_string() {
+                Ok(s) => s.to_lowercase(),
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            };
+
+            match option.as_str() {
+                "match" => scan.pattern = Some(parser.next_bytes()?),
+                "count" => {
+                    let count = usize::try_from(parser.next_int()?)
+                        .ok()
+                        .filter(|count| *count > 0)
+                        .ok_or_else(syntax_error)?;
+
+                    scan.count = Some(count);
+                }
+                "novalues" => scan.no_values = true,
+                _ => return Err(syntax_error()),
+            }
+        }
+
+        Ok(scan)
+    }
+}
+
+impl TryInto<Frame> for HScan {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_bulk(Bytes::from(self.cursor.to_string()))?;
+
+        if let Some(pattern) = self.pattern {
+            frame.push_bulk(Bytes::from("match".as_bytes()))?;
+            frame.push_bulk(pattern)?;
+        }
+
+        if let Some(count) = self.count {
+            frame.push_bulk(Bytes::from("count".as_bytes()))?;
+            frame.push_int(count as i64)?;
+        }
+
+        if self.no_values {
+            frame.push_bulk(Bytes::from("novalues".as_bytes()))?;
+        }
+
+        Ok(frame)
+    }
+}
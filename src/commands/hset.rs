@@ -0,0 +1,100 @@
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::{Parse, ParseError},
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{
+        database::{database::Database, hash::Hash},
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// Sets fields of a hash, creating it if needed, and replies with how many fields are new.
+#[derive(Debug)]
+pub(crate) struct HSet {
+    key: String,
+    pairs: Vec<(Bytes, Bytes)>,
+}
+
+impl HSet {
+    pub(crate) fn new(key: impl ToString, pairs: Vec<(Bytes, Bytes)>) -> Self {
+        Self {
+            key: key.to_string(),
+            pairs,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for HSet {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let added = db.edit_collection(&self.key, true, |hash: &mut Hash| {
+            self.pairs
+                .into_iter()
+                .filter(|(field, value)| hash.insert(field.clone(), value.clone()))
+                .count()
+        });
+
+        let res = match added {
+            Ok(added) => Frame::Integer(added.unwrap_or(0) as i64),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for HSet {
+    fn representation<'a>() -> &'a str {
+        "hset"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let key = parser.next_string()?;
+        let mut pairs = vec![(parser.next_bytes()?, parser.next_bytes()?)];
+
+        loop {
+            let field = match parser.next_bytes() {
+                Ok(field) => field,
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            };
+
+            pairs.push((field, parser.next_bytes()?));
+        }
+
+        Ok(Self { key, pairs })
+    }
+}
+
+impl TryInto<Frame> for HSet {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+
+        for (field, value) in self.pairs {
+            frame.push_bulk(field)?;
+            frame.push_bulk(value)?;
+        }
+
+        Ok(frame)
+    }
+}
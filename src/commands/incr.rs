@@ -0,0 +1,352 @@
+use anyhow::anyhow;
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+#[derive(Debug)]
+pub(crate) struct Incr {
+    key: String,
+}
+
+#[derive(Debug)]
+pub(crate) struct Decr {
+    key: String,
+}
+
+#[derive(Debug)]
+pub(crate) struct IncrBy {
+    key: String,
+    increment: i64,
+}
+
+#[derive(Debug)]
+pub(crate) struct DecrBy {
+    key: String,
+    decrement: i64,
+}
+
+#[derive(Debug)]
+pub(crate) struct IncrByFloat {
+    key: String,
+    increment: f64,
+}
+
+impl Incr {
+    pub(crate) fn new(key: impl ToString) -> Self {
+        Self {
+            key: key.to_string(),
+        }
+    }
+}
+
+impl Decr {
+    pub(crate) fn new(key: impl ToString) -> Self {
+        Self {
+            key: key.to_string(),
+        }
+    }
+}
+
+impl IncrBy {
+    pub(crate) fn new(key: impl ToString, increment: i64) -> Self {
+        Self {
+            key: key.to_string(),
+            increment,
+        }
+    }
+}
+
+impl DecrBy {
+    pub(crate) fn new(key: impl ToString, decrement: i64) -> Self {
+        Self {
+            key: key.to_string(),
+            decrement,
+        }
+    }
+}
+
+impl IncrByFloat {
+    pub(crate) fn new(key: impl ToString, increment: f64) -> Self {
+        Self {
+            key: key.to_string(),
+            increment,
+        }
+    }
+}
+
+/// Adds `delta` to the integer stored at `key`, a missing key counting as `0`, and replies
+/// with the result.
+#[cfg(feature = "server")]
+async fn incr_by(
+    key: &str,
+    delta: Option<i64>,
+    db: &Database,
+    conn: &mut Connection,
+) -> anyhow::Result<()> {
+    let res = db.update(key, |current| -> Result<_, &str> {
+        let current = match current {
+            Some(buf) => parse_integer(buf).ok_or("ERR value is not an integer or out of range")?,
+            None => 0,
+        };
+
+        let value = delta
+            .and_then(|delta| current.checked_add(delta))
+            .ok_or("ERR increment or decrement would overflow")?;
+
+        Ok((Bytes::from(value.to_string()), value))
+    });
+
+    let res = match res {
+        Ok(Ok(value)) => Frame::Integer(value),
+        Ok(Err(e)) => Frame::Error(e.to_string()),
+        Err(e) => Frame::Error(e.to_string()),
+    };
+
+    conn.write_frame(&res).await?;
+    Ok(())
+}
+
+/// Integers are stored in their canonical decimal representation: no plus sign, no leading
+/// zeros and no negative zero. Anything else is not an integer, as in redis.
+#[cfg(feature = "server")]
+pub(super) fn parse_integer(buf: &[u8]) -> Option<i64> {
+    let digits = buf.strip_prefix(b"-").unwrap_or(buf);
+
+    let canonical = match digits {
+        [] => false,
+        // zero is never negative
+        [b'0'] => digits.len() == buf.len(),
+        [b'0', ..] => false,
+        _ => digits.iter().all(u8::is_ascii_digit),
+    };
+
+    if !canonical {
+        return None;
+    }
+
+    std::str::from_utf8(buf).ok()?.parse().ok()
+}
+
+/// Parses a float as given to, or stored by, `INCRBYFLOAT`. Infinite and NaN values are
+/// rejected.
+fn parse_float(buf: &[u8]) -> Option<f64> {
+    let s = std::str::from_utf8(buf).ok()?;
+
+    if s.is_empty() || s.starts_with(char::is_whitespace) || s.ends_with(char::is_whitespace) {
+        return None;
+    }
+
+    s.parse::<f64>().ok().filter(|f| f.is_finite())
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for Incr {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        incr_by(&self.key, Some(1), db, conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for Decr {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        incr_by(&self.key, Some(-1), db, conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for IncrBy {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        incr_by(&self.key, Some(self.increment), db, conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for DecrBy {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        incr_by(&self.key, self.decrement.checked_neg(), db, conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for IncrByFloat {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let res = db.update(&self.key, |current| {
+            let current = match current {
+                Some(buf) => parse_float(buf).ok_or("ERR value is not a valid float")?,
+                None => 0.0,
+            };
+
+            let value = current + self.increment;
+
+            if !value.is_finite() {
+                return Err("ERR increment would produce NaN or Infinity");
+            }
+
+            let buf = Bytes::from(value.to_string());
+            Ok((buf.clone(), buf))
+        });
+
+        let res = match res {
+            Ok(Ok(buf)) => Frame::Bulk(buf),
+            Ok(Err(e)) => Frame::Error(e.to_string()),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for Incr {
+    fn representation<'a>() -> &'a str {
+        "incr"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+        })
+    }
+}
+
+impl Command for Decr {
+    fn representation<'a>() -> &'a str {
+        "decr"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+        })
+    }
+}
+
+impl Command for IncrBy {
+    fn representation<'a>() -> &'a str {
+        "incrby"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+            increment: parser.next_int()?,
+        })
+    }
+}
+
+impl Command for DecrBy {
+    fn representation<'a>() -> &'a str {
+        "decrby"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+            decrement: parser.next_int()?,
+        })
+    }
+}
+
+impl Command for IncrByFloat {
+    fn representation<'a>() -> &'a str {
+        "incrbyfloat"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let key = parser.next_string()?;
+        let increment = parse_float(&parser.next_bytes()?)
+            .ok_or_else(|| anyhow!("value is not a valid float"))?;
+
+        Ok(Self { key, increment })
+    }
+}
+
+impl TryInto<Frame> for Incr {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), vec![self.key])
+    }
+}
+
+impl TryInto<Frame> for Decr {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), vec![self.key])
+    }
+}
+
+impl TryInto<Frame> for IncrBy {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_int(self.increment)?;
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for DecrBy {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_int(self.decrement)?;
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for IncrByFloat {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_bulk(Bytes::from(self.increment.to_string()))?;
+        Ok(frame)
+    }
+}
@@ -0,0 +1,135 @@
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::Command,
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::{Parse, ParseError},
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+/// Sections reported when none are asked for.
+const DEFAULT_SECTIONS: [&str; 3] = ["server", "clients", "stats"];
+
+#[derive(Debug, Default)]
+pub(crate) struct Info {
+    sections: Vec<String>,
+}
+
+impl Info {
+    pub(crate) fn new(sections: Vec<String>) -> Self {
+        Self { sections }
+    }
+
+    fn wants(&self, section: &str) -> bool {
+        self.sections.is_empty()
+            || self
+                .sections
+                .iter()
+                .any(|s| matches!(s.as_str(), "all" | "default" | "everything") || s == section)
+    }
+
+    #[cfg(feature = "server")]
+    fn assemble_response(&self, db: &Database, conn: &Connection) -> String {
+        let mut res = String::new();
+
+        for section in DEFAULT_SECTIONS.into_iter().filter(|s| self.wants(s)) {
+            if !res.is_empty() {
+                res.push_str("\r\n");
+            }
+
+            let fields = match section {
+                "server" => vec![
+                    ("insomnia_version", env!("CARGO_PKG_VERSION").to_string()),
+                    ("process_id", std::process::id().to_string()),
+                    ("resp", conn.protocol().version().to_string()),
+                ],
+                "clients" => vec![
+                    ("connected_clients", db.connected_clients().to_string()),
+                    ("maxclients", db.max_clients().to_string()),
+                ],
+                "stats" => {
+                    let stats = db.expiry_stats();
+                    vec![
+                        ("expired_keys", stats.expired_keys.to_string()),
+                        (
+                            "expired_stale_perc",
+                            format!("{:.2}", stats.stale_ratio * 100.0),
+                        ),
+                        ("volatile_keys", stats.volatile_keys.to_string()),
+                    ]
+                }
+                _ => continue,
+            };
+
+            let mut title = section.to_string();
+            title[..1].make_ascii_uppercase();
+            res.push_str(&format!("# {title}\r\n"));
+
+            for (key, value) in fields {
+                res.push_str(&format!("{key}:{value}\r\n"));
+            }
+        }
+
+        res
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for Info {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let res = self.assemble_response(db, conn);
+        conn.write_frame(&Frame::Verbatim("txt".to_string(), Bytes::from(res)))
+            .await?;
+        Ok(())
+    }
+}
+
+impl Command for Info {
+    fn representation<'a>() -> &'a str {
+        "info"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let mut sections = vec![];
+
+        loop {
+            match parser.next_string() {
+                Ok(s) => sections.push(s.to_lowercase()),
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(Self { sections })
+    }
+}
+
+impl TryInto<Frame> for Info {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = Frame::Array(vec![]);
+        frame.push_bulk(Bytes::from(Self::representation().as_bytes().to_owned()))?;
+
+        for section in self.sections {
+            frame.push_bulk(Bytes::from(section.into_bytes()))?;
+        }
+
+        Ok(frame)
+    }
+}
@@ -0,0 +1,64 @@
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+/// The `TYPE` command.
+#[derive(Debug)]
+pub(crate) struct Type {
+    key: String,
+}
+
+impl Type {
+    pub(crate) fn new(key: impl ToString) -> Self {
+        Self {
+            key: key.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for Type {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let name = db.key_type(&self.key).unwrap_or("none");
+        conn.write_frame(&Frame::Simple(name.to_string())).await?;
+        Ok(())
+    }
+}
+
+impl Command for Type {
+    fn representation<'a>() -> &'a str {
+        "type"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+        })
+    }
+}
+
+impl TryInto<Frame> for Type {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), vec![self.key])
+    }
+}
@@ -0,0 +1,83 @@
+#[cfg(feature = "server")]
+use {async_trait::async_trait, bytes::Bytes, std::collections::VecDeque};
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+/// Resolves `index` into a list of `len` values, negative indexes counting from the tail.
+#[cfg(feature = "server")]
+pub(super) fn list_index(index: i64, len: usize) -> Option<usize> {
+    let index = if index < 0 { len as i64 + index } else { index };
+    (0..len as i64).contains(&index).then_some(index as usize)
+}
+
+#[derive(Debug)]
+pub(crate) struct LIndex {
+    key: String,
+    index: i64,
+}
+
+impl LIndex {
+    pub(crate) fn new(key: impl ToString, index: i64) -> Self {
+        Self {
+            key: key.to_string(),
+            index,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for LIndex {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let value = db.read_collection(&self.key, |list: &VecDeque<Bytes>| {
+            list_index(self.index, list.len()).map(|i| list[i].clone())
+        });
+
+        let res = match value {
+            Ok(value) => value.flatten().map_or(Frame::Null, Frame::Bulk),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for LIndex {
+    fn representation<'a>() -> &'a str {
+        "lindex"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+            index: parser.next_int()?,
+        })
+    }
+}
+
+impl TryInto<Frame> for LIndex {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_int(self.index)?;
+        Ok(frame)
+    }
+}
@@ -0,0 +1,67 @@
+#[cfg(feature = "server")]
+use {async_trait::async_trait, bytes::Bytes, std::collections::VecDeque};
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+#[derive(Debug)]
+pub(crate) struct LLen {
+    key: String,
+}
+
+impl LLen {
+    pub(crate) fn new(key: impl ToString) -> Self {
+        Self {
+            key: key.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for LLen {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let res = match db.read_collection(&self.key, VecDeque::<Bytes>::len) {
+            Ok(len) => Frame::Integer(len.unwrap_or(0) as i64),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for LLen {
+    fn representation<'a>() -> &'a str {
+        "llen"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+        })
+    }
+}
+
+impl TryInto<Frame> for LLen {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), vec![self.key])
+    }
+}
@@ -0,0 +1,123 @@
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{keys_frame, set::syntax_error, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{
+        database::database::{Database, ListEnd},
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// An end of a list, as given to the LMOVE family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Direction {
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub(super) fn parse(parser: &mut Parse) -> anyhow::Result<Self> {
+        match parser.next_string()?.to_lowercase().as_str() {
+            "left" => Ok(Direction::Left),
+            "right" => Ok(Direction::Right),
+            _ => Err(syntax_error()),
+        }
+    }
+
+    pub(super) fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Left => "left",
+            Direction::Right => "right",
+        }
+    }
+
+    #[cfg(feature = "server")]
+    pub(super) fn end(&self) -> ListEnd {
+        match self {
+            Direction::Left => ListEnd::Left,
+            Direction::Right => ListEnd::Right,
+        }
+    }
+}
+
+/// Pops a value from one end of a list and pushes it to an end of another, or of the same,
+/// list.
+#[derive(Debug)]
+pub(crate) struct LMove {
+    src: String,
+    dst: String,
+    from: Direction,
+    to: Direction,
+}
+
+impl LMove {
+    pub(crate) fn new(
+        src: impl ToString,
+        dst: impl ToString,
+        from: Direction,
+        to: Direction,
+    ) -> Self {
+        Self {
+            src: src.to_string(),
+            dst: dst.to_string(),
+            from,
+            to,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for LMove {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let res = match db.list_move(&self.src, &self.dst, self.from.end(), self.to.end()) {
+            Ok(value) => value.map_or(Frame::Null, Frame::Bulk),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for LMove {
+    fn representation<'a>() -> &'a str {
+        "lmove"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            src: parser.next_string()?,
+            dst: parser.next_string()?,
+            from: Direction::parse(parser)?,
+            to: Direction::parse(parser)?,
+        })
+    }
+}
+
+impl TryInto<Frame> for LMove {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.src, self.dst])?;
+        frame.push_bulk(Bytes::from(self.from.as_str().as_bytes()))?;
+        frame.push_bulk(Bytes::from(self.to.as_str().as_bytes()))?;
+        Ok(frame)
+    }
+}
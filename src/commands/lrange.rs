@@ -0,0 +1,105 @@
+#[cfg(feature = "server")]
+use {
+    async_trait::async_trait,
+    bytes::Bytes,
+    std::{collections::VecDeque, ops::RangeInclusive},
+};
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+/// Resolves the inclusive range `start..=stop` over a list of `len` values, where negative
+/// indexes count from the tail. Returns `None` if the range is empty.
+#[cfg(feature = "server")]
+pub(super) fn list_range(start: i64, stop: i64, len: usize) -> Option<RangeInclusive<usize>> {
+    let len = len as i64;
+    let start = if start < 0 {
+        (len + start).max(0)
+    } else {
+        start
+    };
+    let stop = if stop < 0 {
+        len + stop
+    } else {
+        stop.min(len - 1)
+    };
+
+    (start <= stop && start < len).then_some(start as usize..=stop as usize)
+}
+
+#[derive(Debug)]
+pub(crate) struct LRange {
+    key: String,
+    start: i64,
+    stop: i64,
+}
+
+impl LRange {
+    pub(crate) fn new(key: impl ToString, start: i64, stop: i64) -> Self {
+        Self {
+            key: key.to_string(),
+            start,
+            stop,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for LRange {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let values = db.read_collection(&self.key, |list: &VecDeque<Bytes>| {
+            list_range(self.start, self.stop, list.len())
+                .map(|range| list.range(range).cloned().map(Frame::Bulk).collect())
+                .unwrap_or_default()
+        });
+
+        let res = match values {
+            Ok(values) => Frame::Array(values.unwrap_or_default()),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for LRange {
+    fn representation<'a>() -> &'a str {
+        "lrange"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+            start: parser.next_int()?,
+            stop: parser.next_int()?,
+        })
+    }
+}
+
+impl TryInto<Frame> for LRange {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_int(self.start)?;
+        frame.push_int(self.stop)?;
+        Ok(frame)
+    }
+}
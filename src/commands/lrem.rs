@@ -0,0 +1,105 @@
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use {async_trait::async_trait, std::collections::VecDeque};
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+/// Removes occurrences of a value from a list: the first `count` from the head if `count` is
+/// positive, the last `-count` from the tail if it is negative, and all of them if it is `0`.
+#[derive(Debug)]
+pub(crate) struct LRem {
+    key: String,
+    count: i64,
+    value: Bytes,
+}
+
+impl LRem {
+    pub(crate) fn new(key: impl ToString, count: i64, value: Bytes) -> Self {
+        Self {
+            key: key.to_string(),
+            count,
+            value,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for LRem {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let removed = db.edit_collection(&self.key, false, |list: &mut VecDeque<Bytes>| {
+            let limit = match self.count {
+                0 => usize::MAX,
+                count => count.unsigned_abs() as usize,
+            };
+
+            let mut matches: Vec<_> = (0..list.len()).filter(|i| list[*i] == self.value).collect();
+
+            if self.count < 0 {
+                matches.reverse();
+            }
+
+            matches.truncate(limit);
+            matches.sort_unstable();
+
+            let mut index = 0;
+            list.retain(|_| {
+                let removed = matches.binary_search(&index).is_ok();
+                index += 1;
+                !removed
+            });
+
+            matches.len()
+        });
+
+        let res = match removed {
+            Ok(removed) => Frame::Integer(removed.unwrap_or(0) as i64),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for LRem {
+    fn representation<'a>() -> &'a str {
+        "lrem"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+            count: parser.next_int()?,
+            value: parser.next_bytes()?,
+        })
+    }
+}
+
+impl TryInto<Frame> for LRem {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_int(self.count)?;
+        frame.push_bulk(self.value)?;
+        Ok(frame)
+    }
+}
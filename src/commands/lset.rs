@@ -0,0 +1,86 @@
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use {async_trait::async_trait, std::collections::VecDeque};
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::{lindex::list_index, Execute},
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+#[derive(Debug)]
+pub(crate) struct LSet {
+    key: String,
+    index: i64,
+    value: Bytes,
+}
+
+impl LSet {
+    pub(crate) fn new(key: impl ToString, index: i64, value: Bytes) -> Self {
+        Self {
+            key: key.to_string(),
+            index,
+            value,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for LSet {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let written = db.edit_collection(&self.key, false, |list: &mut VecDeque<Bytes>| {
+            let index = list_index(self.index, list.len())?;
+            list[index] = self.value;
+            Some(())
+        });
+
+        let res = match written {
+            Ok(Some(Some(()))) => Frame::Simple("OK".to_string()),
+            Ok(Some(None)) => Frame::Error("ERR index out of range".to_string()),
+            Ok(None) => Frame::Error("ERR no such key".to_string()),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for LSet {
+    fn representation<'a>() -> &'a str {
+        "lset"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+            index: parser.next_int()?,
+            value: parser.next_bytes()?,
+        })
+    }
+}
+
+impl TryInto<Frame> for LSet {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_int(self.index)?;
+        frame.push_bulk(self.value)?;
+        Ok(frame)
+    }
+}
@@ -0,0 +1,90 @@
+#[cfg(feature = "server")]
+use {async_trait::async_trait, bytes::Bytes, std::collections::VecDeque};
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::{lrange::list_range, Execute},
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+/// Keeps only the values of a list within a range, removing the list if the range is empty.
+#[derive(Debug)]
+pub(crate) struct LTrim {
+    key: String,
+    start: i64,
+    stop: i64,
+}
+
+impl LTrim {
+    pub(crate) fn new(key: impl ToString, start: i64, stop: i64) -> Self {
+        Self {
+            key: key.to_string(),
+            start,
+            stop,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for LTrim {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let trimmed =
+            db.edit_collection(
+                &self.key,
+                false,
+                |list: &mut VecDeque<Bytes>| match list_range(self.start, self.stop, list.len()) {
+                    Some(range) => {
+                        list.truncate(range.end() + 1);
+                        list.drain(..range.start());
+                    }
+                    None => list.clear(),
+                },
+            );
+
+        let res = match trimmed {
+            Ok(_) => Frame::Simple("OK".to_string()),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for LTrim {
+    fn representation<'a>() -> &'a str {
+        "ltrim"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+            start: parser.next_int()?,
+            stop: parser.next_int()?,
+        })
+    }
+}
+
+impl TryInto<Frame> for LTrim {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_int(self.start)?;
+        frame.push_int(self.stop)?;
+        Ok(frame)
+    }
+}
@@ -0,0 +1,67 @@
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+#[derive(Debug)]
+pub(crate) struct MGet {
+    keys: Vec<String>,
+}
+
+impl MGet {
+    pub(crate) fn new(keys: Vec<String>) -> Self {
+        Self { keys }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for MGet {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let values = db
+            .mget(&self.keys)
+            .into_iter()
+            .map(|v| v.map_or(Frame::Null, Frame::Bulk))
+            .collect();
+
+        conn.write_frame(&Frame::Array(values)).await?;
+        Ok(())
+    }
+}
+
+impl Command for MGet {
+    fn representation<'a>() -> &'a str {
+        "mget"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let mut keys = vec![parser.next_string()?];
+        keys.extend(parser.remaining_strings()?);
+
+        Ok(Self { keys })
+    }
+}
+
+impl TryInto<Frame> for MGet {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), self.keys)
+    }
+}
@@ -0,0 +1,140 @@
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::Command,
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::{Parse, ParseError},
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+#[derive(Debug)]
+pub(crate) struct MSet {
+    pairs: Vec<(String, Bytes)>,
+}
+
+/// Sets all of the given keys, or none of them if any exists already.
+#[derive(Debug)]
+pub(crate) struct MSetNx {
+    pairs: Vec<(String, Bytes)>,
+}
+
+impl MSet {
+    pub(crate) fn new(pairs: Vec<(String, Bytes)>) -> Self {
+        Self { pairs }
+    }
+}
+
+impl MSetNx {
+    pub(crate) fn new(pairs: Vec<(String, Bytes)>) -> Self {
+        Self { pairs }
+    }
+}
+
+/// Reads at least one key-value pair. An odd number of arguments is reported as a wrong
+/// number of arguments.
+fn parse_pairs(parser: &mut Parse) -> anyhow::Result<Vec<(String, Bytes)>> {
+    let mut pairs = vec![(parser.next_string()?, parser.next_bytes()?)];
+
+    loop {
+        let key = match parser.next_string() {
+            Ok(key) => key,
+            Err(ParseError::EndOfStream) => break,
+            Err(e) => return Err(e.into()),
+        };
+
+        pairs.push((key, parser.next_bytes()?));
+    }
+
+    Ok(pairs)
+}
+
+fn pairs_frame(name: &str, pairs: Vec<(String, Bytes)>) -> Result<Frame, FrameError> {
+    let mut frame = Frame::Array(vec![]);
+    frame.push_bulk(Bytes::from(name.as_bytes().to_owned()))?;
+
+    for (key, value) in pairs {
+        frame.push_bulk(Bytes::from(key.into_bytes()))?;
+        frame.push_bulk(value)?;
+    }
+
+    Ok(frame)
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for MSet {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        db.mset(self.pairs, false);
+        conn.write_frame(&Frame::Simple("OK".to_string())).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for MSetNx {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let res = Frame::Integer(db.mset(self.pairs, true) as i64);
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for MSet {
+    fn representation<'a>() -> &'a str {
+        "mset"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            pairs: parse_pairs(parser)?,
+        })
+    }
+}
+
+impl Command for MSetNx {
+    fn representation<'a>() -> &'a str {
+        "msetnx"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            pairs: parse_pairs(parser)?,
+        })
+    }
+}
+
+impl TryInto<Frame> for MSet {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        pairs_frame(Self::representation(), self.pairs)
+    }
+}
+
+impl TryInto<Frame> for MSetNx {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        pairs_frame(Self::representation(), self.pairs)
+    }
+}
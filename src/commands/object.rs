@@ -0,0 +1,81 @@
+use anyhow::anyhow;
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+/// Inspects the value stored at a key. Only the `ENCODING` subcommand is supported.
+#[derive(Debug)]
+pub(crate) struct Object {
+    key: String,
+}
+
+impl Object {
+    pub(crate) fn new(key: impl ToString) -> Self {
+        Self {
+            key: key.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for Object {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let res = db.encoding(&self.key).map_or(Frame::Null, |encoding| {
+            Frame::Bulk(Bytes::from(encoding.as_bytes()))
+        });
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for Object {
+    fn representation<'a>() -> &'a str {
+        "object"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let subcommand = parser.next_string()?;
+
+        if subcommand.to_lowercase() != "encoding" {
+            return Err(anyhow!(
+                "unknown subcommand '{subcommand}'. Try OBJECT HELP."
+            ));
+        }
+
+        Ok(Self {
+            key: parser.next_string()?,
+        })
+    }
+}
+
+impl TryInto<Frame> for Object {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![])?;
+        frame.push_bulk(Bytes::from("encoding".as_bytes()))?;
+        frame.push_bulk(Bytes::from(self.key.into_bytes()))?;
+        Ok(frame)
+    }
+}
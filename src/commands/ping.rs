@@ -3,6 +3,7 @@ use bytes::Bytes;
 use tracing::error;
 
 use crate::{
+    codec::Protocol,
     connection::Connection,
     frame::{Frame, FrameError},
     parse::{Parse, ParseError},
@@ -20,6 +21,21 @@ impl Ping {
     pub(crate) fn new(buffer: Option<Bytes>) -> Self {
         Self { buffer }
     }
+
+    /// The reply to a ping sent while subscribed. RESP2 clients only expect pushes in that
+    /// state, so they are pushed a pong along with the message, empty if none was given.
+    pub(super) fn subscribed_reply(self, protocol: Protocol) -> Frame {
+        match protocol {
+            Protocol::Resp2 => Frame::Push(vec![
+                Frame::Bulk(Bytes::from_static(b"pong")),
+                Frame::Bulk(self.buffer.unwrap_or_default()),
+            ]),
+            Protocol::Resp3 => match self.buffer {
+                Some(buffer) => Frame::Bulk(buffer),
+                None => Frame::Simple("PONG".to_string()),
+            },
+        }
+    }
 }
 
 #[cfg(feature = "server")]
@@ -0,0 +1,173 @@
+use anyhow::anyhow;
+
+#[cfg(feature = "server")]
+use {async_trait::async_trait, bytes::Bytes, std::collections::VecDeque};
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::{Parse, ParseError},
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{
+        database::database::{Database, ListEnd},
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// Pops values from the head of a list. Without a count, a single value is replied rather
+/// than an array.
+#[derive(Debug)]
+pub(crate) struct LPop {
+    key: String,
+    count: Option<usize>,
+}
+
+/// Pops values from the tail of a list.
+#[derive(Debug)]
+pub(crate) struct RPop {
+    key: String,
+    count: Option<usize>,
+}
+
+impl LPop {
+    pub(crate) fn new(key: impl ToString, count: Option<usize>) -> Self {
+        Self {
+            key: key.to_string(),
+            count,
+        }
+    }
+}
+
+impl RPop {
+    pub(crate) fn new(key: impl ToString, count: Option<usize>) -> Self {
+        Self {
+            key: key.to_string(),
+            count,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+async fn pop(
+    key: &str,
+    count: Option<usize>,
+    end: ListEnd,
+    db: &Database,
+    conn: &mut Connection,
+) -> anyhow::Result<()> {
+    let popped = db.edit_collection(key, false, |list: &mut VecDeque<Bytes>| {
+        let count = count.unwrap_or(1).min(list.len());
+        (0..count).filter_map(|_| end.pop(list)).collect::<Vec<_>>()
+    });
+
+    let res = match popped {
+        Ok(Some(mut values)) if count.is_none() => values.pop().map_or(Frame::Null, Frame::Bulk),
+        Ok(Some(values)) => Frame::Array(values.into_iter().map(Frame::Bulk).collect()),
+        Ok(None) if count.is_some() => Frame::NullArray,
+        Ok(None) => Frame::Null,
+        Err(e) => Frame::Error(e.to_string()),
+    };
+
+    conn.write_frame(&res).await?;
+    Ok(())
+}
+
+/// Reads a key followed by an optional count.
+pub(super) fn parse_count(parser: &mut Parse) -> anyhow::Result<(String, Option<usize>)> {
+    let key = parser.next_string()?;
+
+    let count = match parser.next_int() {
+        Ok(count) => Some(
+            usize::try_from(count)
+                .map_err(|_| anyhow!("value is out of range, must be positive"))?,
+        ),
+        Err(ParseError::EndOfStream) => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok((key, count))
+}
+
+/// Builds the frame of a command taking a key and an optional count.
+pub(super) fn count_frame(
+    name: &str,
+    key: String,
+    count: Option<usize>,
+) -> Result<Frame, FrameError> {
+    let mut frame = keys_frame(name, vec![key])?;
+
+    if let Some(count) = count {
+        frame.push_int(count as i64)?;
+    }
+
+    Ok(frame)
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for LPop {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        pop(&self.key, self.count, ListEnd::Left, db, conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for RPop {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        pop(&self.key, self.count, ListEnd::Right, db, conn).await
+    }
+}
+
+impl Command for LPop {
+    fn representation<'a>() -> &'a str {
+        "lpop"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let (key, count) = parse_count(parser)?;
+        Ok(Self { key, count })
+    }
+}
+
+impl Command for RPop {
+    fn representation<'a>() -> &'a str {
+        "rpop"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let (key, count) = parse_count(parser)?;
+        Ok(Self { key, count })
+    }
+}
+
+impl TryInto<Frame> for LPop {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        count_frame(Self::representation(), self.key, self.count)
+    }
+}
+
+impl TryInto<Frame> for RPop {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        count_frame(Self::representation(), self.key, self.count)
+    }
+}
@@ -41,7 +41,7 @@ impl Execute for Publish {
         _: &mut ShutdownListener,
     ) -> anyhow::Result<()> {
         let subs = db.publish(&self.channel, self.message);
-        conn.write_frame(&Frame::Integer(subs as u64)).await?;
+        conn.write_frame(&Frame::Integer(subs as i64)).await?;
         Ok(())
     }
 }
@@ -0,0 +1,161 @@
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use {async_trait::async_trait, std::collections::VecDeque};
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{
+        database::database::{Database, ListEnd},
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// Pushes values to the head of a list, one after the other.
+#[derive(Debug)]
+pub(crate) struct LPush {
+    key: String,
+    values: Vec<Bytes>,
+}
+
+/// Pushes values to the tail of a list.
+#[derive(Debug)]
+pub(crate) struct RPush {
+    key: String,
+    values: Vec<Bytes>,
+}
+
+impl LPush {
+    pub(crate) fn new(key: impl ToString, values: Vec<Bytes>) -> Self {
+        Self {
+            key: key.to_string(),
+            values,
+        }
+    }
+}
+
+impl RPush {
+    pub(crate) fn new(key: impl ToString, values: Vec<Bytes>) -> Self {
+        Self {
+            key: key.to_string(),
+            values,
+        }
+    }
+}
+
+/// Pushes `values` to the `end` of the list at `key`, creating it if needed, and replies with
+/// the length of the list.
+#[cfg(feature = "server")]
+async fn push(
+    key: &str,
+    values: Vec<Bytes>,
+    end: ListEnd,
+    db: &Database,
+    conn: &mut Connection,
+) -> anyhow::Result<()> {
+    let len = db.edit_collection(key, true, |list: &mut VecDeque<Bytes>| {
+        for value in values {
+            end.push(list, value);
+        }
+
+        list.len()
+    });
+
+    let res = match len {
+        Ok(len) => Frame::Integer(len.unwrap_or(0) as i64),
+        Err(e) => Frame::Error(e.to_string()),
+    };
+
+    conn.write_frame(&res).await?;
+    Ok(())
+}
+
+fn parse_values(parser: &mut Parse) -> anyhow::Result<(String, Vec<Bytes>)> {
+    let key = parser.next_string()?;
+    let mut values = vec![parser.next_bytes()?];
+    values.extend(parser.remaining_bytes()?);
+
+    Ok((key, values))
+}
+
+fn values_frame(name: &str, key: String, values: Vec<Bytes>) -> Result<Frame, FrameError> {
+    let mut frame = keys_frame(name, vec![key])?;
+
+    for value in values {
+        frame.push_bulk(value)?;
+    }
+
+    Ok(frame)
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for LPush {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        push(&self.key, self.values, ListEnd::Left, db, conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for RPush {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        push(&self.key, self.values, ListEnd::Right, db, conn).await
+    }
+}
+
+impl Command for LPush {
+    fn representation<'a>() -> &'a str {
+        "lpush"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let (key, values) = parse_values(parser)?;
+        Ok(Self { key, values })
+    }
+}
+
+impl Command for RPush {
+    fn representation<'a>() -> &'a str {
+        "rpush"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let (key, values) = parse_values(parser)?;
+        Ok(Self { key, values })
+    }
+}
+
+impl TryInto<Frame> for LPush {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        values_frame(Self::representation(), self.key, self.values)
+    }
+}
+
+impl TryInto<Frame> for RPush {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        values_frame(Self::representation(), self.key, self.values)
+    }
+}
@@ -0,0 +1,195 @@
+use anyhow::anyhow;
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use {async_trait::async_trait, std::ops::RangeInclusive};
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+/// Resolves the inclusive range `start..=end` over `len` elements, where negative indexes
+/// count from the end. Indexes past either end are clamped. Returns `None` if the range is
+/// empty.
+#[cfg(feature = "server")]
+pub(super) fn index_range(start: i64, end: i64, len: usize) -> Option<RangeInclusive<usize>> {
+    let len = len as i64;
+
+    if len == 0 || (start < 0 && end < 0 && start > end) {
+        return None;
+    }
+
+    let resolve = |i: i64| if i < 0 { (len + i).max(0) } else { i };
+    let (start, end) = (resolve(start), resolve(end).min(len - 1));
+
+    (start <= end).then_some(start as usize..=end as usize)
+}
+
+#[cfg(feature = "server")]
+pub(super) const STRING_TOO_LONG: &str =
+    "ERR string exceeds maximum allowed size (proto-max-bulk-len)";
+
+#[derive(Debug)]
+pub(crate) struct GetRange {
+    key: String,
+    start: i64,
+    end: i64,
+}
+
+/// Overwrites part of a value, starting at `offset`. The value is padded with zero bytes if
+/// it is shorter than `offset`.
+#[derive(Debug)]
+pub(crate) struct SetRange {
+    key: String,
+    offset: usize,
+    value: Bytes,
+}
+
+impl GetRange {
+    pub(crate) fn new(key: impl ToString, start: i64, end: i64) -> Self {
+        Self {
+            key: key.to_string(),
+            start,
+            end,
+        }
+    }
+}
+
+impl SetRange {
+    pub(crate) fn new(key: impl ToString, offset: usize, value: Bytes) -> Self {
+        Self {
+            key: key.to_string(),
+            offset,
+            value,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for GetRange {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let res = db.read(&self.key, |buf| {
+            index_range(self.start, self.end, buf.len())
+                .map_or_else(Bytes::new, |range| Bytes::copy_from_slice(&buf[range]))
+        });
+
+        let res = match res {
+            Ok(buf) => Frame::Bulk(buf.unwrap_or_default()),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for SetRange {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let max_len = db.max_string_len();
+        let res = db.edit(&self.key, |buf| {
+            // an empty value leaves the string untouched, however large the offset
+            if self.value.is_empty() {
+                return Ok(buf.len());
+            }
+
+            if self.offset.saturating_add(self.value.len()) > max_len {
+                return Err(STRING_TOO_LONG);
+            }
+
+            let end = self.offset + self.value.len();
+
+            if buf.len() < end {
+                buf.resize(end, 0);
+            }
+
+            buf[self.offset..end].copy_from_slice(&self.value);
+            Ok(buf.len())
+        });
+
+        let res = match res {
+            Ok(Ok(len)) => Frame::Integer(len as i64),
+            Ok(Err(e)) => Frame::Error(e.to_string()),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for GetRange {
+    fn representation<'a>() -> &'a str {
+        "getrange"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+            start: parser.next_int()?,
+            end: parser.next_int()?,
+        })
+    }
+}
+
+impl Command for SetRange {
+    fn representation<'a>() -> &'a str {
+        "setrange"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let key = parser.next_string()?;
+
+        let offset =
+            usize::try_from(parser.next_int()?).map_err(|_| anyhow!("offset is out of range"))?;
+
+        Ok(Self {
+            key,
+            offset,
+            value: parser.next_bytes()?,
+        })
+    }
+}
+
+impl TryInto<Frame> for GetRange {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_int(self.start)?;
+        frame.push_int(self.end)?;
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for SetRange {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_int(self.offset as i64)?;
+        frame.push_bulk(self.value)?;
+        Ok(frame)
+    }
+}
@@ -0,0 +1,126 @@
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+#[derive(Debug)]
+pub(crate) struct Rename {
+    src: String,
+    dst: String,
+}
+
+/// Renames a key unless the new name is taken.
+#[derive(Debug)]
+pub(crate) struct RenameNx {
+    src: String,
+    dst: String,
+}
+
+impl Rename {
+    pub(crate) fn new(src: impl ToString, dst: impl ToString) -> Self {
+        Self {
+            src: src.to_string(),
+            dst: dst.to_string(),
+        }
+    }
+}
+
+impl RenameNx {
+    pub(crate) fn new(src: impl ToString, dst: impl ToString) -> Self {
+        Self {
+            src: src.to_string(),
+            dst: dst.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for Rename {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let res = match db.rename(&self.src, &self.dst, true) {
+            Some(_) => Frame::Simple("OK".to_string()),
+            None => Frame::Error("ERR no such key".to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for RenameNx {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let res = match db.rename(&self.src, &self.dst, false) {
+            Some(renamed) => Frame::Integer(renamed as i64),
+            None => Frame::Error("ERR no such key".to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for Rename {
+    fn representation<'a>() -> &'a str {
+        "rename"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            src: parser.next_string()?,
+            dst: parser.next_string()?,
+        })
+    }
+}
+
+impl Command for RenameNx {
+    fn representation<'a>() -> &'a str {
+        "renamenx"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            src: parser.next_string()?,
+            dst: parser.next_string()?,
+        })
+    }
+}
+
+impl TryInto<Frame> for Rename {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), vec![self.src, self.dst])
+    }
+}
+
+impl TryInto<Frame> for RenameNx {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), vec![self.src, self.dst])
+    }
+}
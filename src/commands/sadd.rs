@@ -0,0 +1,160 @@
+use bytes::Bytes;
+use std::collections::HashSet;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+/// Adds members to a set, creating it if needed, and replies with how many were not members
+/// yet.
+#[derive(Debug)]
+pub(crate) struct SAdd {
+    key: String,
+    members: Vec<Bytes>,
+}
+
+/// Removes members from a set and replies with how many were members.
+#[derive(Debug)]
+pub(crate) struct SRem {
+    key: String,
+    members: Vec<Bytes>,
+}
+
+impl SAdd {
+    pub(crate) fn new(key: impl ToString, members: Vec<Bytes>) -> Self {
+        Self {
+            key: key.to_string(),
+            members,
+        }
+    }
+}
+
+impl SRem {
+    pub(crate) fn new(key: impl ToString, members: Vec<Bytes>) -> Self {
+        Self {
+            key: key.to_string(),
+            members,
+        }
+    }
+}
+
+/// Reads a key followed by at least one member.
+fn parse_members(parser: &mut Parse) -> anyhow::Result<(String, Vec<Bytes>)> {
+    let key = parser.next_string()?;
+    let mut members = vec![parser.next_bytes()?];
+    members.extend(parser.remaining_bytes()?);
+
+    Ok((key, members))
+}
+
+fn members_frame(name: &str, key: String, members: Vec<Bytes>) -> Result<Frame, FrameError> {
+    let mut frame = keys_frame(name, vec![key])?;
+
+    for member in members {
+        frame.push_bulk(member)?;
+    }
+
+    Ok(frame)
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for SAdd {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let added = db.edit_collection(&self.key, true, |set: &mut HashSet<Bytes>| {
+            self.members
+                .into_iter()
+                .filter(|member| set.insert(member.clone()))
+                .count()
+        });
+
+        let res = match added {
+            Ok(added) => Frame::Integer(added.unwrap_or(0) as i64),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for SRem {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let removed = db.edit_collection(&self.key, false, |set: &mut HashSet<Bytes>| {
+            self.members
+                .iter()
+                .filter(|member| set.remove(*member))
+                .count()
+        });
+
+        let res = match removed {
+            Ok(removed) => Frame::Integer(removed.unwrap_or(0) as i64),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for SAdd {
+    fn representation<'a>() -> &'a str {
+        "sadd"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let (key, members) = parse_members(parser)?;
+        Ok(Self { key, members })
+    }
+}
+
+impl Command for SRem {
+    fn representation<'a>() -> &'a str {
+        "srem"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let (key, members) = parse_members(parser)?;
+        Ok(Self { key, members })
+    }
+}
+
+impl TryInto<Frame> for SAdd {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        members_frame(Self::representation(), self.key, self.members)
+    }
+}
+
+impl TryInto<Frame> for SRem {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        members_frame(Self::representation(), self.key, self.members)
+    }
+}
@@ -1,9 +1,10 @@
+use anyhow::anyhow;
 use bytes::Bytes;
 use std::time::Duration;
 use tracing::{debug, instrument};
 
 #[cfg(feature = "server")]
-use async_trait::async_trait;
+use {async_trait::async_trait, tokio::time::Instant};
 
 use crate::{
     commands::{Command, Execute},
@@ -13,13 +14,46 @@ use crate::{
 };
 
 #[cfg(feature = "server")]
-use crate::server::{database::database::Database, shutdown_listener::ShutdownListener};
+use crate::{
+    commands::expire::unix_time_ms,
+    server::{
+        database::database::{Database, SetExpiration},
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// How `SET` treats the expiration of the key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SetExpiry {
+    /// Expire after the given number of seconds.
+    Ex(u64),
+    /// Expire after the given number of milliseconds.
+    Px(u64),
+    /// Expire at the given unix time, in seconds.
+    ExAt(u64),
+    /// Expire at the given unix time, in milliseconds.
+    PxAt(u64),
+    /// Keep the expiration of the replaced value.
+    KeepTtl,
+}
+
+/// Condition on the existence of the key for `SET` to write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SetCondition {
+    /// Only set the key if it does not exist.
+    Nx,
+    /// Only set the key if it exists.
+    Xx,
+}
 
 #[derive(Debug)]
 pub(crate) struct Set {
     key: String,
     value: Bytes,
-    expiration: Option<Duration>,
+    expiry: Option<SetExpiry>,
+    condition: Option<SetCondition>,
+    /// Reply with the replaced value rather than `OK`.
+    get: bool,
 }
 
 impl Set {
@@ -27,7 +61,9 @@ impl Set {
         Self {
             key: key.to_string(),
             value,
-            expiration,
+            expiry: expiration.map(|t| SetExpiry::Px(t.as_millis() as u64)),
+            condition: None,
+            get: false,
         }
     }
 
@@ -39,8 +75,47 @@ impl Set {
         &self.value
     }
 
-    pub(crate) fn expiration(&self) -> Option<Duration> {
-        self.expiration
+    pub(crate) fn expiry(&self) -> Option<SetExpiry> {
+        self.expiry
+    }
+}
+
+impl SetExpiry {
+    /// Reads the time of an `EX`, `PX`, `EXAT` or `PXAT` option of the command `name`. The
+    /// option is expected in lowercase.
+    pub(super) fn parse(option: &str, name: &str, parser: &mut Parse) -> anyhow::Result<Self> {
+        let time = u64::try_from(parser.next_int()?)
+            .ok()
+            .filter(|t| *t > 0)
+            .ok_or_else(|| anyhow!("invalid expire time in '{name}' command"))?;
+
+        match option {
+            "ex" => Ok(SetExpiry::Ex(time)),
+            "px" => Ok(SetExpiry::Px(time)),
+            "exat" => Ok(SetExpiry::ExAt(time)),
+            "pxat" => Ok(SetExpiry::PxAt(time)),
+            _ => Err(syntax_error()),
+        }
+    }
+
+    /// Resolves the option to the expiration of the stored value. Returns `None` if it cannot
+    /// be represented.
+    #[cfg(feature = "server")]
+    pub(super) fn resolve(self) -> Option<SetExpiration> {
+        let delay_ms = match self {
+            SetExpiry::KeepTtl => return Some(SetExpiration::Keep),
+            SetExpiry::Ex(secs) => secs.checked_mul(1000)?,
+            SetExpiry::Px(ms) => ms,
+            SetExpiry::ExAt(secs) => secs
+                .checked_mul(1000)?
+                .saturating_sub(unix_time_ms().max(0) as u64),
+            SetExpiry::PxAt(ms) => ms.saturating_sub(unix_time_ms().max(0) as u64),
+        };
+
+        // absolute times in the past leave the key expired straight away
+        Instant::now()
+            .checked_add(Duration::from_millis(delay_ms))
+            .map(SetExpiration::At)
     }
 }
 
@@ -54,9 +129,37 @@ impl Execute for Set {
         conn: &mut Connection,
         shutdown: &mut ShutdownListener,
     ) -> anyhow::Result<()> {
-        db.set(self.key, self.value, self.expiration);
+        let expiration = self
+            .expiry
+            .map_or(Some(SetExpiration::Persist), SetExpiry::resolve);
+
+        let Some(expiration) = expiration else {
+            let res = Frame::Error("ERR invalid expire time in 'set' command".to_string());
+            conn.write_frame(&res).await?;
+            return Ok(());
+        };
+
+        let condition = self.condition;
+        let res = db.set(
+            self.key,
+            self.value,
+            expiration,
+            self.get,
+            |exists| match condition {
+                Some(SetCondition::Nx) => !exists,
+                Some(SetCondition::Xx) => exists,
+                None => true,
+            },
+        );
+
+        let res = match res {
+            Ok((Some(previous), _)) if self.get => Frame::Bulk(previous),
+            Ok(_) if self.get => Frame::Null,
+            Ok((_, true)) => Frame::Simple("OK".to_string()),
+            Ok((_, false)) => Frame::Null,
+            Err(e) => Frame::Error(e.to_string()),
+        };
 
-        let res = Frame::Simple("OK".to_string());
         debug!(?res);
         conn.write_frame(&res).await?;
         Ok(())
@@ -72,26 +175,38 @@ impl Command for Set {
         let key = parser.next_string()?;
         let value = parser.next_bytes()?;
 
-        // handle data
-        let expiration = match parser.next_string() {
-            Ok(s) if s.to_lowercase() == "ex" => {
-                let secs = parser.next_int()?;
-                Some(Duration::from_secs(secs))
-            }
-            Ok(s) if s.to_lowercase() == "px" => {
-                let ms = parser.next_int()?;
-                Some(Duration::from_millis(ms))
-            }
-            Ok(_) => return Err(anyhow::anyhow!("`SET` only supports an expiration option.")),
-            Err(ParseError::EndOfStream) => None,
-            Err(e) => return Err(e.into()),
-        };
-
-        Ok(Self {
+        let mut set = Self {
             key,
             value,
-            expiration,
-        })
+            expiry: None,
+            condition: None,
+            get: false,
+        };
+
+        loop {
+            let option = match parser.next_string() {
+                Ok(s) => s.to_lowercase(),
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            };
+
+            match option.as_str() {
+                "nx" | "xx" if set.condition.is_some() => return Err(syntax_error()),
+                "nx" => set.condition = Some(SetCondition::Nx),
+                "xx" => set.condition = Some(SetCondition::Xx),
+                "get" => set.get = true,
+                "ex" | "px" | "exat" | "pxat" | "keepttl" if set.expiry.is_some() => {
+                    return Err(syntax_error())
+                }
+                "keepttl" => set.expiry = Some(SetExpiry::KeepTtl),
+                "ex" | "px" | "exat" | "pxat" => {
+                    set.expiry = Some(SetExpiry::parse(&option, Self::representation(), parser)?);
+                }
+                _ => return Err(syntax_error()),
+            }
+        }
+
+        Ok(set)
     }
 }
 
@@ -105,11 +220,36 @@ impl TryInto<Frame> for Set {
         frame.push_bulk(Bytes::from(self.key.into_bytes()))?;
         frame.push_bulk(self.value)?;
 
-        if let Some(t) = self.expiration {
-            frame.push_bulk(Bytes::from("px".as_bytes()))?;
-            frame.push_int(t.as_millis() as u64)?;
+        if let Some(expiry) = self.expiry {
+            let (option, time) = match expiry {
+                SetExpiry::Ex(t) => ("ex", Some(t)),
+                SetExpiry::Px(t) => ("px", Some(t)),
+                SetExpiry::ExAt(t) => ("exat", Some(t)),
+                SetExpiry::PxAt(t) => ("pxat", Some(t)),
+                SetExpiry::KeepTtl => ("keepttl", None),
+            };
+
+            frame.push_bulk(Bytes::from(option.as_bytes()))?;
+
+            if let Some(t) = time {
+                frame.push_int(t as i64)?;
+            }
+        }
+
+        match self.condition {
+            Some(SetCondition::Nx) => frame.push_bulk(Bytes::from("nx".as_bytes()))?,
+            Some(SetCondition::Xx) => frame.push_bulk(Bytes::from("xx".as_bytes()))?,
+            None => {}
+        }
+
+        if self.get {
+            frame.push_bulk(Bytes::from("get".as_bytes()))?;
         }
 
         Ok(frame)
     }
 }
+
+pub(super) fn syntax_error() -> anyhow::Error {
+    anyhow!("syntax error")
+}
@@ -0,0 +1,406 @@
+#[cfg(feature = "server")]
+use {async_trait::async_trait, bytes::Bytes, std::collections::HashSet};
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+/// How the sets of the set algebra commands are combined. Missing keys stand for empty sets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SetOperation {
+    /// The members of every set.
+    Inter,
+    /// The members of any set.
+    Union,
+    /// The members of the first set that are in none of the others.
+    Diff,
+}
+
+/// Replies with the intersection of sets.
+#[derive(Debug)]
+pub(crate) struct SInter {
+    keys: Vec<String>,
+}
+
+/// Replies with the union of sets.
+#[derive(Debug)]
+pub(crate) struct SUnion {
+    keys: Vec<String>,
+}
+
+/// Replies with the members of the first set that are in none of the others.
+#[derive(Debug)]
+pub(crate) struct SDiff {
+    keys: Vec<String>,
+}
+
+/// Stores the intersection of sets at `dest` and replies with its size.
+#[derive(Debug)]
+pub(crate) struct SInterStore {
+    dest: String,
+    keys: Vec<String>,
+}
+
+/// Stores the union of sets at `dest` and replies with its size.
+#[derive(Debug)]
+pub(crate) struct SUnionStore {
+    dest: String,
+    keys: Vec<String>,
+}
+
+/// Stores the difference of sets at `dest` and replies with its size.
+#[derive(Debug)]
+pub(crate) struct SDiffStore {
+    dest: String,
+    keys: Vec<String>,
+}
+
+impl SetOperation {
+    #[cfg(feature = "server")]
+    fn apply(&self, sets: Vec<Option<&HashSet<Bytes>>>) -> HashSet<Bytes> {
+        let mut sets = sets.into_iter();
+        let first = sets.next().flatten();
+
+        match self {
+            SetOperation::Inter => {
+                let mut rest: Vec<_> = sets.collect();
+
+                if rest.iter().any(Option::is_none) {
+                    return HashSet::new();
+                }
+
+                // probing the smallest sets first rules members out the soonest
+                rest.sort_by_key(|set| set.map_or(0, HashSet::len));
+
+                first
+                    .into_iter()
+                    .flatten()
+                    .filter(|member| rest.iter().flatten().all(|set| set.contains(*member)))
+                    .cloned()
+                    .collect()
+            }
+            SetOperation::Union => first
+                .into_iter()
+                .chain(sets.flatten())
+                .flatten()
+                .cloned()
+                .collect(),
+            SetOperation::Diff => {
+                let rest: Vec<_> = sets.flatten().collect();
+
+                first
+                    .into_iter()
+                    .flatten()
+                    .filter(|member| !rest.iter().any(|set| set.contains(*member)))
+                    .cloned()
+                    .collect()
+            }
+        }
+    }
+}
+
+impl SInter {
+    pub(crate) fn new(keys: Vec<String>) -> Self {
+        Self { keys }
+    }
+}
+
+impl SUnion {
+    pub(crate) fn new(keys: Vec<String>) -> Self {
+        Self { keys }
+    }
+}
+
+impl SDiff {
+    pub(crate) fn new(keys: Vec<String>) -> Self {
+        Self { keys }
+    }
+}
+
+impl SInterStore {
+    pub(crate) fn new(dest: impl ToString, keys: Vec<String>) -> Self {
+        Self {
+            dest: dest.to_string(),
+            keys,
+        }
+    }
+}
+
+impl SUnionStore {
+    pub(crate) fn new(dest: impl ToString, keys: Vec<String>) -> Self {
+        Self {
+            dest: dest.to_string(),
+            keys,
+        }
+    }
+}
+
+impl SDiffStore {
+    pub(crate) fn new(dest: impl ToString, keys: Vec<String>) -> Self {
+        Self {
+            dest: dest.to_string(),
+            keys,
+        }
+    }
+}
+
+/// Replies with the set `operation` computes from the sets at `keys`.
+#[cfg(feature = "server")]
+async fn combine(
+    operation: SetOperation,
+    keys: &[String],
+    db: &Database,
+    conn: &mut Connection,
+) -> anyhow::Result<()> {
+    let members = db.read_collections(keys, |sets| operation.apply(sets));
+
+    let res = match members {
+        Ok(members) => Frame::Set(members.into_iter().map(Frame::Bulk).collect()),
+        Err(e) => Frame::Error(e.to_string()),
+    };
+
+    conn.write_frame(&res).await?;
+    Ok(())
+}
+
+/// Stores the set `operation` computes from the sets at `keys` at `dest`, and replies with
+/// its size.
+#[cfg(feature = "server")]
+async fn store(
+    operation: SetOperation,
+    dest: &str,
+    keys: &[String],
+    db: &Database,
+    conn: &mut Connection,
+) -> anyhow::Result<()> {
+    let len = db.store_collection(dest, keys, |sets| operation.apply(sets));
+
+    let res = match len {
+        Ok(len) => Frame::Integer(len as i64),
+        Err(e) => Frame::Error(e.to_string()),
+    };
+
+    conn.write_frame(&res).await?;
+    Ok(())
+}
+
+/// Reads at least one key.
+fn parse_keys(parser: &mut Parse) -> anyhow::Result<Vec<String>> {
+    let mut keys = vec![parser.next_string()?];
+    keys.extend(parser.remaining_strings()?);
+    Ok(keys)
+}
+
+/// Reads a destination followed by at least one key.
+fn parse_store(parser: &mut Parse) -> anyhow::Result<(String, Vec<String>)> {
+    let dest = parser.next_string()?;
+    Ok((dest, parse_keys(parser)?))
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for SInter {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        combine(SetOperation::Inter, &self.keys, db, conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for SUnion {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        combine(SetOperation::Union, &self.keys, db, conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for SDiff {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        combine(SetOperation::Diff, &self.keys, db, conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for SInterStore {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        store(SetOperation::Inter, &self.dest, &self.keys, db, conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for SUnionStore {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        store(SetOperation::Union, &self.dest, &self.keys, db, conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for SDiffStore {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        store(SetOperation::Diff, &self.dest, &self.keys, db, conn).await
+    }
+}
+
+impl Command for SInter {
+    fn representation<'a>() -> &'a str {
+        "sinter"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            keys: parse_keys(parser)?,
+        })
+    }
+}
+
+impl Command for SUnion {
+    fn representation<'a>() -> &'a str {
+        "sunion"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            keys: parse_keys(parser)?,
+        })
+    }
+}
+
+impl Command for SDiff {
+    fn representation<'a>() -> &'a str {
+        "sdiff"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            keys: parse_keys(parser)?,
+        })
+    }
+}
+
+impl Command for SInterStore {
+    fn representation<'a>() -> &'a str {
+        "sinterstore"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let (dest, keys) = parse_store(parser)?;
+        Ok(Self { dest, keys })
+    }
+}
+
+impl Command for SUnionStore {
+    fn representation<'a>() -> &'a str {
+        "sunionstore"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let (dest, keys) = parse_store(parser)?;
+        Ok(Self { dest, keys })
+    }
+}
+
+impl Command for SDiffStore {
+    fn representation<'a>() -> &'a str {
+        "sdiffstore"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let (dest, keys) = parse_store(parser)?;
+        Ok(Self { dest, keys })
+    }
+}
+
+fn store_frame(name: &str, dest: String, keys: Vec<String>) -> Result<Frame, FrameError> {
+    keys_frame(name, [vec![dest], keys].concat())
+}
+
+impl TryInto<Frame> for SInter {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), self.keys)
+    }
+}
+
+impl TryInto<Frame> for SUnion {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), self.keys)
+    }
+}
+
+impl TryInto<Frame> for SDiff {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), self.keys)
+    }
+}
+
+impl TryInto<Frame> for SInterStore {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        store_frame(Self::representation(), self.dest, self.keys)
+    }
+}
+
+impl TryInto<Frame> for SUnionStore {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        store_frame(Self::representation(), self.dest, self.keys)
+    }
+}
+
+impl TryInto<Frame> for SDiffStore {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        store_frame(Self::representation(), self.dest, self.keys)
+    }
+}
@@ -0,0 +1,191 @@
+use bytes::Bytes;
+use std::collections::HashSet;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+/// Replies with the members of a set.
+#[derive(Debug)]
+pub(crate) struct SMembers {
+    key: String,
+}
+
+/// Replies with 1 if a value is a member of a set, and 0 otherwise.
+#[derive(Debug)]
+pub(crate) struct SIsMember {
+    key: String,
+    member: Bytes,
+}
+
+/// Replies with the number of members of a set.
+#[derive(Debug)]
+pub(crate) struct SCard {
+    key: String,
+}
+
+impl SMembers {
+    pub(crate) fn new(key: impl ToString) -> Self {
+        Self {
+            key: key.to_string(),
+        }
+    }
+}
+
+impl SIsMember {
+    pub(crate) fn new(key: impl ToString, member: Bytes) -> Self {
+        Self {
+            key: key.to_string(),
+            member,
+        }
+    }
+}
+
+impl SCard {
+    pub(crate) fn new(key: impl ToString) -> Self {
+        Self {
+            key: key.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for SMembers {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let members = db.read_collection(&self.key, |set: &HashSet<Bytes>| {
+            set.iter().cloned().map(Frame::Bulk).collect()
+        });
+
+        let res = match members {
+            Ok(members) => Frame::Set(members.unwrap_or_default()),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for SIsMember {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let is_member =
+            db.read_collection(&self.key, |set: &HashSet<Bytes>| set.contains(&self.member));
+
+        let res = match is_member {
+            Ok(is_member) => Frame::Integer(is_member.unwrap_or(false) as i64),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for SCard {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let len = db.read_collection(&self.key, |set: &HashSet<Bytes>| set.len());
+
+        let res = match len {
+            Ok(len) => Frame::Integer(len.unwrap_or(0) as i64),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for SMembers {
+    fn representation<'a>() -> &'a str {
+        "smembers"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+        })
+    }
+}
+
+impl Command for SIsMember {
+    fn representation<'a>() -> &'a str {
+        "sismember"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+            member: parser.next_bytes()?,
+        })
+    }
+}
+
+impl Command for SCard {
+    fn representation<'a>() -> &'a str {
+        "scard"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+        })
+    }
+}
+
+impl TryInto<Frame> for SMembers {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), vec![self.key])
+    }
+}
+
+impl TryInto<Frame> for SIsMember {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_bulk(self.member)?;
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for SCard {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), vec![self.key])
+    }
+}
@@ -0,0 +1,203 @@
+use anyhow::anyhow;
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use {
+    async_trait::async_trait,
+    rand::seq::{IteratorRandom, SliceRandom},
+    std::collections::HashSet,
+};
+
+use crate::{
+    commands::{
+        keys_frame,
+        pop::{count_frame, parse_count},
+        Command,
+    },
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::{Parse, ParseError},
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+/// Replies with random members of a set. A positive count asks for distinct members, and a
+/// negative one for as many members, which may repeat. Without a count, a single member is
+/// replied rather than an array.
+#[derive(Debug)]
+pub(crate) struct SRandMember {
+    key: String,
+    count: Option<i64>,
+}
+
+/// Removes random members of a set and replies with them. Without a count, a single member
+/// is replied rather than an array.
+#[derive(Debug)]
+pub(crate) struct SPop {
+    key: String,
+    count: Option<usize>,
+}
+
+impl SRandMember {
+    pub(crate) fn new(key: impl ToString, count: Option<i64>) -> Self {
+        Self {
+            key: key.to_string(),
+            count,
+        }
+    }
+}
+
+impl SPop {
+    pub(crate) fn new(key: impl ToString, count: Option<usize>) -> Self {
+        Self {
+            key: key.to_string(),
+            count,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for SRandMember {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let count = self.count;
+        let members = db.read_collection(&self.key, |set: &HashSet<Bytes>| match count {
+            Some(count) if count < 0 => set.iter().cloned().collect(),
+            count => {
+                let count = count.map_or(1, |count| count as usize).min(set.len());
+                set.iter()
+                    .cloned()
+                    .choose_multiple(&mut rand::thread_rng(), count)
+            }
+        });
+
+        // members that may repeat are drawn once the state is unlocked, however many
+        let members = match count {
+            Some(count) if count < 0 => members.map(|members| {
+                members.map(|members: Vec<Bytes>| {
+                    let mut rng = rand::thread_rng();
+                    match members.is_empty() {
+                        true => vec![],
+                        false => (0..count.unsigned_abs())
+                            .filter_map(|_| members.choose(&mut rng).cloned())
+                            .collect(),
+                    }
+                })
+            }),
+            _ => members,
+        };
+
+        let res = match members {
+            Ok(Some(mut members)) if self.count.is_none() => {
+                members.pop().map_or(Frame::Null, Frame::Bulk)
+            }
+            Ok(Some(members)) => Frame::Array(members.into_iter().map(Frame::Bulk).collect()),
+            Ok(None) if self.count.is_none() => Frame::Null,
+            Ok(None) => Frame::Array(vec![]),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for SPop {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let count = self.count.unwrap_or(1);
+        let popped = db.edit_collection(&self.key, false, |set: &mut HashSet<Bytes>| {
+            let count = count.min(set.len());
+            let members = set
+                .iter()
+                .cloned()
+                .choose_multiple(&mut rand::thread_rng(), count);
+
+            for member in &members {
+                set.remove(member);
+            }
+
+            members
+        });
+
+        let res = match popped {
+            Ok(Some(mut members)) if self.count.is_none() => {
+                members.pop().map_or(Frame::Null, Frame::Bulk)
+            }
+            Ok(Some(members)) => Frame::Array(members.into_iter().map(Frame::Bulk).collect()),
+            Ok(None) if self.count.is_none() => Frame::Null,
+            Ok(None) => Frame::Array(vec![]),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for SRandMember {
+    fn representation<'a>() -> &'a str {
+        "srandmember"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let key = parser.next_string()?;
+
+        let count = match parser.next_int() {
+            Ok(count) if count < -(i64::MAX / 2) => return Err(anyhow!("value is out of range")),
+            Ok(count) => Some(count),
+            Err(ParseError::EndOfStream) => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self { key, count })
+    }
+}
+
+impl Command for SPop {
+    fn representation<'a>() -> &'a str {
+        "spop"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let (key, count) = parse_count(parser)?;
+        Ok(Self { key, count })
+    }
+}
+
+impl TryInto<Frame> for SRandMember {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+
+        if let Some(count) = self.count {
+            frame.push_int(count)?;
+        }
+
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for SPop {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        count_frame(Self::representation(), self.key, self.count)
+    }
+}
@@ -1,6 +1,6 @@
 use bytes::Bytes;
 
-use super::{Command, MessageStream};
+use super::Command;
 use crate::{
     connection::Connection,
     frame::{Frame, FrameError},
@@ -9,8 +9,11 @@ use crate::{
 
 #[cfg(feature = "server")]
 use {
-    super::Execute,
-    crate::server::{database::database::Database, shutdown_listener::ShutdownListener},
+    super::{from_frame, Execute, MessageStream, SupportedCommand},
+    crate::server::{
+        database::database::Database, handler::reject_malformed,
+        shutdown_listener::ShutdownListener,
+    },
     async_stream::stream,
     async_trait::async_trait,
     tokio::{select, sync::broadcast},
@@ -22,20 +25,46 @@ pub(crate) struct Subscribe {
     channels: Vec<String>,
 }
 
-impl Subscribe {
-    pub(crate) fn new(channels: Vec<String>) -> Self {
-        Self { channels }
-    }
+/// Unsubscribes from the given channels, or from every channel if none is given.
+#[derive(Debug)]
+pub(crate) struct Unsubscribe {
+    channels: Vec<String>,
+}
 
-    async fn subscribe_to_channel(
-        channel_name: String,
-        subs: &mut StreamMap<String, MessageStream>,
-        db: &Database,
-        conn: &mut Connection,
-    ) -> anyhow::Result<()> {
-        let mut rx = db.subscribe(channel_name.clone());
+/// Subscribes to every channel matching any of the given glob-style patterns.
+#[derive(Debug)]
+pub(crate) struct PSubscribe {
+    patterns: Vec<String>,
+}
 
-        let stream = Box::pin(stream! {
+/// Unsubscribes from the given patterns, or from every pattern if none is given.
+#[derive(Debug)]
+pub(crate) struct PUnsubscribe {
+    patterns: Vec<String>,
+}
+
+/// The channels and patterns a client is subscribed to. While subscribed, the client is
+/// sent the messages published to them, and may only change its subscriptions or ping.
+#[cfg(feature = "server")]
+#[derive(Default)]
+struct Subscriptions {
+    channels: StreamMap<String, MessageStream>,
+    /// Messages are received along with the channel they were published to.
+    patterns: StreamMap<String, MessageStream<(String, Bytes)>>,
+}
+
+#[cfg(feature = "server")]
+impl Subscriptions {
+    fn count(&self) -> i64 {
+        (self.channels.len() + self.patterns.len()) as i64
+    }
+
+    /// Turns a receiver into a stream of the messages it receives. Messages a slow
+    /// subscriber lagged behind on are skipped.
+    fn messages<T: Clone + Send + Sync + 'static>(
+        mut rx: broadcast::Receiver<T>,
+    ) -> MessageStream<T> {
+        Box::pin(stream! {
             loop {
                 match rx.recv().await {
                     Ok(m) => yield m,
@@ -43,32 +72,229 @@ impl Subscribe {
                     Err(_) => break,
                 }
             }
-        });
-
-        subs.insert(channel_name.clone(), stream);
-        conn.write_frame(&Self::assemble_subscribe_response(
-            channel_name,
-            subs.len() as u64,
-        )?)
-        .await?;
-        Ok(())
+        })
     }
 
-    fn assemble_subscribe_response(channel_name: String, num: u64) -> anyhow::Result<Frame> {
-        let mut frame = Frame::Array(vec![]);
-        frame.push_bulk(Bytes::from(Self::representation().as_bytes().to_owned()))?;
-        frame.push_bulk(Bytes::from(channel_name.into_bytes()))?;
-        frame.push_int(num)?;
-        Ok(frame)
+    /// Confirms a change of subscription, along with how many subscriptions are left.
+    /// Replies and messages are pushed, which RESP2 clients receive as arrays.
+    fn assemble_response(kind: &str, name: Option<String>, num: i64) -> Frame {
+        Frame::Push(vec![
+            Frame::Bulk(Bytes::from(kind.as_bytes().to_owned())),
+            name.map_or(Frame::Null, |name| {
+                Frame::Bulk(Bytes::from(name.into_bytes()))
+            }),
+            Frame::Integer(num),
+        ])
     }
 
     fn assemble_message(channel_name: String, message: Bytes) -> anyhow::Result<Frame> {
-        let mut frame = Frame::Array(vec![]);
+        let mut frame = Frame::Push(vec![]);
         frame.push_bulk(Bytes::from("message".as_bytes()))?;
         frame.push_bulk(Bytes::from(channel_name.into_bytes()))?;
         frame.push_bulk(message)?;
         Ok(frame)
     }
+
+    fn assemble_pattern_message(
+        pattern: String,
+        channel_name: String,
+        message: Bytes,
+    ) -> anyhow::Result<Frame> {
+        let mut frame = Frame::Push(vec![]);
+        frame.push_bulk(Bytes::from("pmessage".as_bytes()))?;
+        frame.push_bulk(Bytes::from(pattern.into_bytes()))?;
+        frame.push_bulk(Bytes::from(channel_name.into_bytes()))?;
+        frame.push_bulk(message)?;
+        Ok(frame)
+    }
+
+    async fn subscribe(
+        &mut self,
+        channels: Vec<String>,
+        db: &Database,
+        conn: &mut Connection,
+    ) -> anyhow::Result<()> {
+        for channel_name in channels {
+            let rx = db.subscribe(channel_name.clone());
+            self.channels
+                .insert(channel_name.clone(), Self::messages(rx));
+
+            let res = Self::assemble_response(
+                Subscribe::representation(),
+                Some(channel_name),
+                self.count(),
+            );
+            conn.write_frame(&res).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn psubscribe(
+        &mut self,
+        patterns: Vec<String>,
+        db: &Database,
+        conn: &mut Connection,
+    ) -> anyhow::Result<()> {
+        for pattern in patterns {
+            let rx = db.psubscribe(pattern.clone());
+            self.patterns.insert(pattern.clone(), Self::messages(rx));
+
+            let res =
+                Self::assemble_response(PSubscribe::representation(), Some(pattern), self.count());
+            conn.write_frame(&res).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn unsubscribe(
+        &mut self,
+        channels: Vec<String>,
+        conn: &mut Connection,
+    ) -> anyhow::Result<()> {
+        let channels = match channels.is_empty() {
+            true => self.channels.keys().cloned().collect(),
+            false => channels,
+        };
+
+        // unsubscribing from nothing is still confirmed
+        if channels.is_empty() {
+            let res = Self::assemble_response(Unsubscribe::representation(), None, self.count());
+            conn.write_frame(&res).await?;
+        }
+
+        for channel_name in channels {
+            self.channels.remove(&channel_name);
+
+            let res = Self::assemble_response(
+                Unsubscribe::representation(),
+                Some(channel_name),
+                self.count(),
+            );
+            conn.write_frame(&res).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn punsubscribe(
+        &mut self,
+        patterns: Vec<String>,
+        conn: &mut Connection,
+    ) -> anyhow::Result<()> {
+        let patterns = match patterns.is_empty() {
+            true => self.patterns.keys().cloned().collect(),
+            false => patterns,
+        };
+
+        // unsubscribing from nothing is still confirmed
+        if patterns.is_empty() {
+            let res = Self::assemble_response(PUnsubscribe::representation(), None, self.count());
+            conn.write_frame(&res).await?;
+        }
+
+        for pattern in patterns {
+            self.patterns.remove(&pattern);
+
+            let res = Self::assemble_response(
+                PUnsubscribe::representation(),
+                Some(pattern),
+                self.count(),
+            );
+            conn.write_frame(&res).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Sends the client the messages published to its subscriptions, until it unsubscribes
+    /// from everything, disconnects or the server shuts down.
+    async fn run(
+        mut self,
+        db: &Database,
+        conn: &mut Connection,
+        shutdown_listener: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        while self.count() > 0 {
+            select! {
+                Some((ch, m)) = self.channels.next() => {
+                    conn.write_frame(&Self::assemble_message(ch, m)?).await?
+                }
+                Some((pattern, (ch, m))) = self.patterns.next() => {
+                    conn.write_frame(&Self::assemble_pattern_message(pattern, ch, m)?).await?
+                }
+                frame = conn.read_frame() => match reject_malformed(conn, frame).await? {
+                    Some(frame) => self.handle(frame, db, conn).await?,
+                    None => return Ok(()),
+                },
+                _ = shutdown_listener.subscribe() => return Ok(())
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Executes a request received while subscribed.
+    async fn handle(
+        &mut self,
+        frame: Frame,
+        db: &Database,
+        conn: &mut Connection,
+    ) -> anyhow::Result<()> {
+        // empty requests are ignored
+        if matches!(&frame, Frame::Array(args) if args.is_empty()) {
+            return Ok(());
+        }
+
+        match from_frame(frame) {
+            Ok(SupportedCommand::Subscribe(cmd)) => self.subscribe(cmd.channels, db, conn).await,
+            Ok(SupportedCommand::PSubscribe(cmd)) => self.psubscribe(cmd.patterns, db, conn).await,
+            Ok(SupportedCommand::Unsubscribe(cmd)) => self.unsubscribe(cmd.channels, conn).await,
+            Ok(SupportedCommand::PUnsubscribe(cmd)) => self.punsubscribe(cmd.patterns, conn).await,
+            Ok(SupportedCommand::Ping(cmd)) => {
+                let res = cmd.subscribed_reply(conn.protocol());
+                conn.write_frame(&res).await?;
+                Ok(())
+            }
+            Ok(_) => {
+                let res = Frame::Error(
+                    "ERR only (P)SUBSCRIBE / (P)UNSUBSCRIBE / PING are allowed in this context"
+                        .to_string(),
+                );
+                conn.write_frame(&res).await?;
+                Ok(())
+            }
+            Err(e) => {
+                conn.write_frame(&Frame::Error(e.to_string())).await?;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Subscribe {
+    pub(crate) fn new(channels: Vec<String>) -> Self {
+        Self { channels }
+    }
+}
+
+impl Unsubscribe {
+    pub(crate) fn new(channels: Vec<String>) -> Self {
+        Self { channels }
+    }
+}
+
+impl PSubscribe {
+    pub(crate) fn new(patterns: Vec<String>) -> Self {
+        Self { patterns }
+    }
+}
+
+impl PUnsubscribe {
+    pub(crate) fn new(patterns: Vec<String>) -> Self {
+        Self { patterns }
+    }
 }
 
 #[cfg(feature = "server")]
@@ -80,22 +306,58 @@ impl Execute for Subscribe {
         conn: &mut Connection,
         shutdown_listener: &mut ShutdownListener,
     ) -> anyhow::Result<()> {
-        // Some options for supporting other commands while subscribed:
-        // * [client/server] Use a channel for message passing.
-        // * [client] Spin up a unique thread for each command which awaits responses from the server.
+        let mut subs = Subscriptions::default();
+        subs.subscribe(self.channels, db, conn).await?;
+        subs.run(db, conn, shutdown_listener).await
+    }
+}
 
-        let mut subs = StreamMap::new();
-        loop {
-            // this can be optimized
-            for ch in self.channels.iter() {
-                Self::subscribe_to_channel(ch.clone(), &mut subs, db, conn).await?;
-            }
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for PSubscribe {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        shutdown_listener: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let mut subs = Subscriptions::default();
+        subs.psubscribe(self.patterns, db, conn).await?;
+        subs.run(db, conn, shutdown_listener).await
+    }
+}
 
-            select! {
-                Some((ch, m)) = subs.next() => conn.write_frame(&Self::assemble_message(ch, m)?).await?,
-                _ = shutdown_listener.subscribe() => return Ok(())
-            }
-        }
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for Unsubscribe {
+    /// Outside of a subscription there is nothing to unsubscribe from, which is confirmed
+    /// all the same.
+    async fn execute(
+        self,
+        _: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        Subscriptions::default()
+            .unsubscribe(self.channels, conn)
+            .await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for PUnsubscribe {
+    /// Outside of a subscription there is nothing to unsubscribe from, which is confirmed
+    /// all the same.
+    async fn execute(
+        self,
+        _: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        Subscriptions::default()
+            .punsubscribe(self.patterns, conn)
+            .await
     }
 }
 
@@ -119,6 +381,43 @@ impl Command for Subscribe {
     }
 }
 
+impl Command for Unsubscribe {
+    fn representation<'a>() -> &'a str {
+        "unsubscribe"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            channels: parser.remaining_strings()?,
+        })
+    }
+}
+
+impl Command for PSubscribe {
+    fn representation<'a>() -> &'a str {
+        "psubscribe"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let mut patterns = vec![parser.next_string()?];
+        patterns.extend(parser.remaining_strings()?);
+
+        Ok(Self { patterns })
+    }
+}
+
+impl Command for PUnsubscribe {
+    fn representation<'a>() -> &'a str {
+        "punsubscribe"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            patterns: parser.remaining_strings()?,
+        })
+    }
+}
+
 impl TryInto<Frame> for Subscribe {
     type Error = FrameError;
 
@@ -133,3 +432,48 @@ impl TryInto<Frame> for Subscribe {
         Ok(frame)
     }
 }
+
+impl TryInto<Frame> for Unsubscribe {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = Frame::Array(vec![]);
+        frame.push_bulk(Bytes::from(Self::representation().as_bytes().to_owned()))?;
+
+        for ch in self.channels {
+            frame.push_bulk(Bytes::from(ch.into_bytes()))?;
+        }
+
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for PSubscribe {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = Frame::Array(vec![]);
+        frame.push_bulk(Bytes::from(Self::representation().as_bytes().to_owned()))?;
+
+        for pattern in self.patterns {
+            frame.push_bulk(Bytes::from(pattern.into_bytes()))?;
+        }
+
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for PUnsubscribe {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = Frame::Array(vec![]);
+        frame.push_bulk(Bytes::from(Self::representation().as_bytes().to_owned()))?;
+
+        for pattern in self.patterns {
+            frame.push_bulk(Bytes::from(pattern.into_bytes()))?;
+        }
+
+        Ok(frame)
+    }
+}
@@ -0,0 +1,224 @@
+#[cfg(feature = "server")]
+use {async_trait::async_trait, tokio::time::Instant};
+
+use crate::{
+    commands::{keys_frame, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::{expire::unix_time_ms, Execute},
+    server::{database::database::Database, shutdown_listener::ShutdownListener},
+};
+
+/// The remaining time to live of a key, in seconds.
+#[derive(Debug)]
+pub(crate) struct Ttl {
+    key: String,
+}
+
+/// The remaining time to live of a key, in milliseconds.
+#[derive(Debug)]
+pub(crate) struct PTtl {
+    key: String,
+}
+
+/// The unix time, in seconds, at which a key expires.
+#[derive(Debug)]
+pub(crate) struct ExpireTime {
+    key: String,
+}
+
+/// The unix time, in milliseconds, at which a key expires.
+#[derive(Debug)]
+pub(crate) struct PExpireTime {
+    key: String,
+}
+
+impl Ttl {
+    pub(crate) fn new(key: impl ToString) -> Self {
+        Self {
+            key: key.to_string(),
+        }
+    }
+}
+
+impl PTtl {
+    pub(crate) fn new(key: impl ToString) -> Self {
+        Self {
+            key: key.to_string(),
+        }
+    }
+}
+
+impl ExpireTime {
+    pub(crate) fn new(key: impl ToString) -> Self {
+        Self {
+            key: key.to_string(),
+        }
+    }
+}
+
+impl PExpireTime {
+    pub(crate) fn new(key: impl ToString) -> Self {
+        Self {
+            key: key.to_string(),
+        }
+    }
+}
+
+/// Replies with `-2` if the key does not exist, `-1` if it never expires and otherwise with
+/// its expiration as given by `describe`, which is passed the remaining milliseconds.
+#[cfg(feature = "server")]
+async fn reply(
+    key: &str,
+    describe: impl FnOnce(i64) -> i64,
+    db: &Database,
+    conn: &mut Connection,
+) -> anyhow::Result<()> {
+    let res = match db.expiration(key) {
+        None => -2,
+        Some(None) => -1,
+        Some(Some(time)) => {
+            describe(time.saturating_duration_since(Instant::now()).as_millis() as i64)
+        }
+    };
+
+    conn.write_frame(&Frame::Integer(res)).await?;
+    Ok(())
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for Ttl {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        reply(&self.key, |ms| (ms + 500) / 1000, db, conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for PTtl {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        reply(&self.key, |ms| ms, db, conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for ExpireTime {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        reply(&self.key, |ms| (unix_time_ms() + ms) / 1000, db, conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for PExpireTime {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        reply(&self.key, |ms| unix_time_ms() + ms, db, conn).await
+    }
+}
+
+impl Command for Ttl {
+    fn representation<'a>() -> &'a str {
+        "ttl"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+        })
+    }
+}
+
+impl Command for PTtl {
+    fn representation<'a>() -> &'a str {
+        "pttl"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+        })
+    }
+}
+
+impl Command for ExpireTime {
+    fn representation<'a>() -> &'a str {
+        "expiretime"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+        })
+    }
+}
+
+impl Command for PExpireTime {
+    fn representation<'a>() -> &'a str {
+        "pexpiretime"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+        })
+    }
+}
+
+impl TryInto<Frame> for Ttl {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), vec![self.key])
+    }
+}
+
+impl TryInto<Frame> for PTtl {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), vec![self.key])
+    }
+}
+
+impl TryInto<Frame> for ExpireTime {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), vec![self.key])
+    }
+}
+
+impl TryInto<Frame> for PExpireTime {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), vec![self.key])
+    }
+}
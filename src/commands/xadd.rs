@@ -0,0 +1,393 @@
+use anyhow::anyhow;
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{
+        keys_frame,
+        set::syntax_error,
+        xrange::{id_bytes, parse_id},
+        Command,
+    },
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::{Parse, ParseError},
+    server::database::stream::{Fields, NewId, StreamId, Trim},
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::{expire::unix_time_ms, Execute},
+    server::{
+        database::{database::Database, stream::Stream},
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// How XADD and XTRIM evict the oldest entries of a stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TrimArgs {
+    trim: Trim,
+    /// Whether trimming may be approximate, `~`. Streams are always trimmed exactly.
+    approximate: bool,
+    /// How many entries to evict at most.
+    limit: Option<usize>,
+}
+
+/// Appends an entry to a stream, creating the stream if needed, and replies with its ID.
+#[derive(Debug)]
+pub(crate) struct XAdd {
+    key: String,
+    /// Leaves a missing stream missing rather than creating it.
+    no_mkstream: bool,
+    trim: Option<TrimArgs>,
+    id: NewId,
+    fields: Fields,
+}
+
+/// Evicts the oldest entries of a stream and replies with how many were evicted.
+#[derive(Debug)]
+pub(crate) struct XTrim {
+    key: String,
+    trim: TrimArgs,
+}
+
+/// Replies with the number of entries of a stream.
+#[derive(Debug)]
+pub(crate) struct XLen {
+    key: String,
+}
+
+const ID_TOO_SMALL: &str =
+    "ERR The ID specified in XADD is equal or smaller than the target stream top item";
+
+impl TrimArgs {
+    pub(crate) fn new(trim: Trim, approximate: bool, limit: Option<usize>) -> Self {
+        Self {
+            trim,
+            approximate,
+            limit,
+        }
+    }
+
+    /// Reads the threshold and limit of a trim, once `MAXLEN` or `MINID` was read as
+    /// `strategy`.
+    fn parse(parser: &mut Parse, strategy: &str) -> anyhow::Result<Self> {
+        let mut threshold = parser.next_string()?;
+        let mut approximate = false;
+
+        if threshold == "~" || threshold == "=" {
+            approximate = threshold == "~";
+            threshold = parser.next_string()?;
+        }
+
+        let trim = match strategy {
+            "maxlen" => threshold
+                .parse::<usize>()
+                .map(Trim::MaxLen)
+                .map_err(|_| anyhow!("The MAXLEN argument must be >= 0."))?,
+            _ => Trim::MinId(parse_id(&threshold, 0)?),
+        };
+
+        let limit = match parser.peek_string() {
+            Ok(s) if s.eq_ignore_ascii_case("limit") => {
+                parser.next_string()?;
+                let limit = parser.next_int()?;
+
+                if !approximate {
+                    return Err(anyhow!(
+                        "syntax error, LIMIT cannot be used without the special ~ option"
+                    ));
+                }
+
+                Some(
+                    usize::try_from(limit)
+                        .map_err(|_| anyhow!("The LIMIT argument must be >= 0."))?,
+                )
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            trim,
+            approximate,
+            limit,
+        })
+    }
+
+    fn push_to(&self, frame: &mut Frame) -> Result<(), FrameError> {
+        let (strategy, threshold) = match self.trim {
+            Trim::MaxLen(len) => ("MAXLEN", len.to_string()),
+            Trim::MinId(id) => ("MINID", id.to_string()),
+        };
+
+        frame.push_bulk(Bytes::from_static(strategy.as_bytes()))?;
+
+        if self.approximate {
+            frame.push_bulk(Bytes::from_static(b"~"))?;
+        }
+
+        frame.push_bulk(Bytes::from(threshold.into_bytes()))?;
+
+        if let Some(limit) = self.limit {
+            frame.push_bulk(Bytes::from_static(b"LIMIT"))?;
+            frame.push_int(limit as i64)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses the ID of a new entry: `*`, `<ms>-*` or an exact ID above `0-0`.
+fn parse_new_id(arg: &str) -> anyhow::Result<NewId> {
+    if arg == "*" {
+        return Ok(NewId::Auto);
+    }
+
+    if let Some(ms) = arg.strip_suffix("-*") {
+        return parse_id(ms, 0).map(|id| NewId::AutoSeq(id.ms));
+    }
+
+    match parse_id(arg, 0)? {
+        id if id == StreamId::default() => {
+            Err(anyhow!("The ID specified in XADD must be greater than 0-0"))
+        }
+        id => Ok(NewId::Exact(id)),
+    }
+}
+
+fn new_id_bytes(id: NewId) -> Bytes {
+    match id {
+        NewId::Auto => Bytes::from_static(b"*"),
+        NewId::AutoSeq(ms) => Bytes::from(format!("{ms}-*").into_bytes()),
+        NewId::Exact(id) => id_bytes(id),
+    }
+}
+
+impl XAdd {
+    pub(crate) fn new(
+        key: impl ToString,
+        no_mkstream: bool,
+        trim: Option<TrimArgs>,
+        id: NewId,
+        fields: Fields,
+    ) -> Self {
+        Self {
+            key: key.to_string(),
+            no_mkstream,
+            trim,
+            id,
+            fields,
+        }
+    }
+}
+
+impl XTrim {
+    pub(crate) fn new(key: impl ToString, trim: TrimArgs) -> Self {
+        Self {
+            key: key.to_string(),
+            trim,
+        }
+    }
+}
+
+impl XLen {
+    pub(crate) fn new(key: impl ToString) -> Self {
+        Self {
+            key: key.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for XAdd {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let now_ms = unix_time_ms().max(0) as u64;
+        let added = db.edit_collection(&self.key, !self.no_mkstream, |stream: &mut Stream| {
+            let id = stream.add(self.id, self.fields, now_ms)?;
+
+            if let Some(trim) = self.trim {
+                stream.trim(trim.trim, trim.limit);
+            }
+
+            Some(id)
+        });
+
+        let res = match added {
+            Ok(Some(Some(id))) => Frame::Bulk(id_bytes(id)),
+            Ok(Some(None)) => Frame::Error(ID_TOO_SMALL.to_string()),
+            Ok(None) => Frame::Null,
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for XTrim {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let evicted = db.edit_collection(&self.key, false, |stream: &mut Stream| {
+            stream.trim(self.trim.trim, self.trim.limit)
+        });
+
+        let res = match evicted {
+            Ok(evicted) => Frame::Integer(evicted.unwrap_or(0) as i64),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for XLen {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let len = db.read_collection(&self.key, |stream: &Stream| stream.len());
+
+        let res = match len {
+            Ok(len) => Frame::Integer(len.unwrap_or(0) as i64),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for XAdd {
+    fn representation<'a>() -> &'a str {
+        "xadd"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let key = parser.next_string()?;
+        let (mut no_mkstream, mut trim) = (false, None);
+
+        let id = loop {
+            let arg = parser.next_string()?;
+
+            match arg.to_lowercase().as_str() {
+                "nomkstream" => no_mkstream = true,
+                strategy @ ("maxlen" | "minid") => {
+                    trim = Some(TrimArgs::parse(parser, strategy)?);
+                }
+                _ => break parse_new_id(&arg)?,
+            }
+        };
+
+        let args = parser.remaining_bytes()?;
+
+        // fields and values come in pairs
+        if args.is_empty() || args.len() % 2 != 0 {
+            return Err(ParseError::EndOfStream.into());
+        }
+
+        let fields = args
+            .chunks(2)
+            .map(|pair| (pair[0].clone(), pair[1].clone()))
+            .collect();
+
+        Ok(Self {
+            key,
+            no_mkstream,
+            trim,
+            id,
+            fields,
+        })
+    }
+}
+
+impl Command for XTrim {
+    fn representation<'a>() -> &'a str {
+        "xtrim"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let key = parser.next_string()?;
+
+        let trim = match parser.next_string()?.to_lowercase().as_str() {
+            strategy @ ("maxlen" | "minid") => TrimArgs::parse(parser, strategy)?,
+            _ => return Err(syntax_error()),
+        };
+
+        Ok(Self { key, trim })
+    }
+}
+
+impl Command for XLen {
+    fn representation<'a>() -> &'a str {
+        "xlen"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+        })
+    }
+}
+
+impl TryInto<Frame> for XAdd {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+
+        if self.no_mkstream {
+            frame.push_bulk(Bytes::from_static(b"NOMKSTREAM"))?;
+        }
+
+        if let Some(trim) = self.trim {
+            trim.push_to(&mut frame)?;
+        }
+
+        frame.push_bulk(new_id_bytes(self.id))?;
+
+        for (field, value) in self.fields {
+            frame.push_bulk(field)?;
+            frame.push_bulk(value)?;
+        }
+
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for XTrim {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        self.trim.push_to(&mut frame)?;
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for XLen {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), vec![self.key])
+    }
+}
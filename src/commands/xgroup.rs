@@ -0,0 +1,297 @@
+use anyhow::anyhow;
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{
+        keys_frame,
+        set::syntax_error,
+        xrange::{id_bytes, parse_id},
+        Command,
+    },
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+    server::database::stream::StreamId,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{
+        database::{database::Database, stream::Stream},
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// A subcommand of `XGROUP`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum GroupAction {
+    /// Creates a group delivered the entries after an ID, `None` standing for the last ID of
+    /// the stream. The stream is created too if `mkstream` is set.
+    Create {
+        id: Option<StreamId>,
+        mkstream: bool,
+    },
+    /// Sets the last ID delivered to a group, `None` standing for the last ID of the stream.
+    SetId(Option<StreamId>),
+    Destroy,
+    CreateConsumer(String),
+    /// Removes a consumer, and the entries pending for it.
+    DelConsumer(String),
+}
+
+/// Manages the consumer groups of a stream.
+#[derive(Debug)]
+pub(crate) struct XGroup {
+    key: String,
+    group: String,
+    action: GroupAction,
+}
+
+/// Acknowledges entries delivered to a consumer group, and replies with how many were
+/// pending.
+#[derive(Debug)]
+pub(crate) struct XAck {
+    key: String,
+    group: String,
+    ids: Vec<StreamId>,
+}
+
+/// Parses an ID, or `$` for the last ID of the stream.
+fn parse_last_id(arg: &str) -> anyhow::Result<Option<StreamId>> {
+    match arg {
+        "$" => Ok(None),
+        arg => parse_id(arg, 0).map(Some),
+    }
+}
+
+fn last_id_bytes(id: Option<StreamId>) -> Bytes {
+    id.map_or(Bytes::from_static(b"$"), id_bytes)
+}
+
+impl GroupAction {
+    fn name(&self) -> &'static str {
+        match self {
+            GroupAction::Create { .. } => "create",
+            GroupAction::SetId(_) => "setid",
+            GroupAction::Destroy => "destroy",
+            GroupAction::CreateConsumer(_) => "createconsumer",
+            GroupAction::DelConsumer(_) => "delconsumer",
+        }
+    }
+
+    /// Applies the action to `group` of `stream`, and replies with the outcome.
+    #[cfg(feature = "server")]
+    fn apply(self, stream: &mut Stream, key: &str, group: &str) -> Frame {
+        let ok = || Frame::Simple("OK".to_string());
+        let last = stream.last_id();
+
+        let no_group = || {
+            Frame::Error(format!(
+                "NOGROUP No such consumer group '{group}' for key name '{key}'"
+            ))
+        };
+
+        match self {
+            GroupAction::Create { id, .. } => {
+                match stream.create_group(group, id.unwrap_or(last)) {
+                    true => ok(),
+                    false => {
+                        Frame::Error("BUSYGROUP Consumer Group name already exists".to_string())
+                    }
+                }
+            }
+            GroupAction::SetId(id) => match stream.group_mut(group) {
+                Some(group) => {
+                    group.set_last_delivered(id.unwrap_or(last));
+                    ok()
+                }
+                None => no_group(),
+            },
+            GroupAction::Destroy => Frame::Integer(stream.destroy_group(group) as i64),
+            GroupAction::CreateConsumer(consumer) => match stream.group_mut(group) {
+                Some(group) => Frame::Integer(group.create_consumer(&consumer) as i64),
+                None => no_group(),
+            },
+            GroupAction::DelConsumer(consumer) => match stream.group_mut(group) {
+                Some(group) => Frame::Integer(group.delete_consumer(&consumer).unwrap_or(0) as i64),
+                None => no_group(),
+            },
+        }
+    }
+}
+
+impl XGroup {
+    pub(crate) fn new(key: impl ToString, group: impl ToString, action: GroupAction) -> Self {
+        Self {
+            key: key.to_string(),
+            group: group.to_string(),
+            action,
+        }
+    }
+}
+
+impl XAck {
+    pub(crate) fn new(key: impl ToString, group: impl ToString, ids: Vec<StreamId>) -> Self {
+        Self {
+            key: key.to_string(),
+            group: group.to_string(),
+            ids,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for XGroup {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let mkstream = matches!(self.action, GroupAction::Create { mkstream: true, .. });
+        let res = db.edit_collection(&self.key, mkstream, |stream: &mut Stream| {
+            self.action.apply(stream, &self.key, &self.group)
+        });
+
+        let res = match res {
+            Ok(Some(frame)) => frame,
+            Ok(None) => Frame::Error(
+                "ERR The XGROUP subcommand requires the key to exist. Note that for CREATE you \
+                 may want to use the MKSTREAM option to create an empty stream automatically."
+                    .to_string(),
+            ),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for XAck {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let acked = db.edit_collection(&self.key, false, |stream: &mut Stream| {
+            stream
+                .group_mut(&self.group)
+                .map(|group| group.ack(&self.ids))
+        });
+
+        let res = match acked {
+            Ok(acked) => Frame::Integer(acked.flatten().unwrap_or(0) as i64),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for XGroup {
+    fn representation<'a>() -> &'a str {
+        "xgroup"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let subcommand = parser.next_string()?;
+        let (key, group) = (parser.next_string()?, parser.next_string()?);
+
+        let action = match subcommand.to_lowercase().as_str() {
+            "create" => {
+                let id = parse_last_id(&parser.next_string()?)?;
+                let mut mkstream = false;
+
+                for arg in parser.remaining_strings()? {
+                    match arg.to_lowercase().as_str() {
+                        "mkstream" => mkstream = true,
+                        _ => return Err(syntax_error()),
+                    }
+                }
+
+                GroupAction::Create { id, mkstream }
+            }
+            "setid" => GroupAction::SetId(parse_last_id(&parser.next_string()?)?),
+            "destroy" => GroupAction::Destroy,
+            "createconsumer" => GroupAction::CreateConsumer(parser.next_string()?),
+            "delconsumer" => GroupAction::DelConsumer(parser.next_string()?),
+            _ => {
+                return Err(anyhow!(
+                    "unknown subcommand '{subcommand}'. Try XGROUP HELP."
+                ))
+            }
+        };
+
+        Ok(Self { key, group, action })
+    }
+}
+
+impl Command for XAck {
+    fn representation<'a>() -> &'a str {
+        "xack"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let (key, group) = (parser.next_string()?, parser.next_string()?);
+        let mut ids = vec![parse_id(&parser.next_string()?, 0)?];
+
+        for id in parser.remaining_strings()? {
+            ids.push(parse_id(&id, 0)?);
+        }
+
+        Ok(Self { key, group, ids })
+    }
+}
+
+impl TryInto<Frame> for XGroup {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![])?;
+        frame.push_bulk(Bytes::from_static(self.action.name().as_bytes()))?;
+        frame.push_bulk(Bytes::from(self.key.into_bytes()))?;
+        frame.push_bulk(Bytes::from(self.group.into_bytes()))?;
+
+        match self.action {
+            GroupAction::Create { id, mkstream } => {
+                frame.push_bulk(last_id_bytes(id))?;
+
+                if mkstream {
+                    frame.push_bulk(Bytes::from_static(b"MKSTREAM"))?;
+                }
+            }
+            GroupAction::SetId(id) => frame.push_bulk(last_id_bytes(id))?,
+            GroupAction::Destroy => {}
+            GroupAction::CreateConsumer(consumer) | GroupAction::DelConsumer(consumer) => {
+                frame.push_bulk(Bytes::from(consumer.into_bytes()))?
+            }
+        }
+
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for XAck {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_bulk(Bytes::from(self.group.into_bytes()))?;
+
+        for id in self.ids {
+            frame.push_bulk(id_bytes(id))?;
+        }
+
+        Ok(frame)
+    }
+}
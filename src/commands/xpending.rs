@@ -0,0 +1,610 @@
+use anyhow::anyhow;
+use bytes::Bytes;
+use std::{ops::Bound, time::Duration};
+
+#[cfg(feature = "server")]
+use {async_trait::async_trait, tokio::time::Instant};
+
+use crate::{
+    commands::{
+        keys_frame,
+        set::syntax_error,
+        xrange::{id_bytes, parse_count, parse_id},
+        Command,
+    },
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::{Parse, ParseError},
+    server::database::stream::{ClaimOptions, StreamId},
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::{expire::unix_time_ms, xrange::entries_frame, Execute},
+    server::{
+        database::{
+            database::Database,
+            stream::{Stream, StreamEntry},
+        },
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// The pending entries `XPENDING` lists, rather than summing them up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PendingRange {
+    /// Only lists entries idle for at least this long.
+    min_idle: Option<Duration>,
+    start: Bound<StreamId>,
+    end: Bound<StreamId>,
+    count: usize,
+    /// Only lists the entries pending for this consumer.
+    consumer: Option<String>,
+}
+
+/// Replies with the entries delivered to a consumer group and not acknowledged yet: how
+/// many there are, their lowest and highest IDs and how many each consumer has, or the
+/// entries themselves in a range.
+#[derive(Debug)]
+pub(crate) struct XPending {
+    key: String,
+    group: String,
+    range: Option<PendingRange>,
+}
+
+/// Hands pending entries idle for long enough to another consumer, and replies with them.
+#[derive(Debug)]
+pub(crate) struct XClaim {
+    key: String,
+    group: String,
+    consumer: String,
+    ids: Vec<StreamId>,
+    options: ClaimOptions,
+    /// The unix time in milliseconds the entries count as delivered at, unless an idle time
+    /// is given.
+    time: Option<i64>,
+}
+
+/// `XCLAIM` for the pending entries from an ID on, replying with the ID to continue from as
+/// well.
+#[derive(Debug)]
+pub(crate) struct XAutoClaim {
+    key: String,
+    group: String,
+    consumer: String,
+    start: StreamId,
+    count: usize,
+    /// The minimum idle time, and whether to reply with IDs only.
+    options: ClaimOptions,
+}
+
+/// Parses a duration in milliseconds. Negative durations count as zero.
+fn parse_ms(parser: &mut Parse) -> anyhow::Result<Duration> {
+    Ok(Duration::from_millis(parser.next_int()?.max(0) as u64))
+}
+
+fn ms_bytes(duration: Duration) -> Bytes {
+    Bytes::from(duration.as_millis().to_string().into_bytes())
+}
+
+#[cfg(feature = "server")]
+fn no_group(key: &str, group: &str) -> Frame {
+    Frame::Error(format!(
+        "NOGROUP No such key '{key}' or consumer group '{group}'"
+    ))
+}
+
+/// Replies with claimed entries, or with their IDs only if `just_id` is set.
+#[cfg(feature = "server")]
+fn claimed_frame(claimed: Vec<StreamEntry>, just_id: bool) -> Frame {
+    match just_id {
+        true => Frame::Array(
+            claimed
+                .into_iter()
+                .map(|(id, _)| Frame::Bulk(id_bytes(id)))
+                .collect(),
+        ),
+        false => entries_frame(claimed),
+    }
+}
+
+impl PendingRange {
+    pub(crate) fn new(
+        min_idle: Option<Duration>,
+        start: Bound<StreamId>,
+        end: Bound<StreamId>,
+        count: usize,
+        consumer: Option<String>,
+    ) -> Self {
+        Self {
+            min_idle,
+            start,
+            end,
+            count,
+            consumer,
+        }
+    }
+}
+
+impl XPending {
+    pub(crate) fn new(
+        key: impl ToString,
+        group: impl ToString,
+        range: Option<PendingRange>,
+    ) -> Self {
+        Self {
+            key: key.to_string(),
+            group: group.to_string(),
+            range,
+        }
+    }
+
+    /// Sums up the pending entries of a group.
+    #[cfg(feature = "server")]
+    fn summary(stream: &Stream, group: &str) -> Option<Frame> {
+        let group = stream.group(group)?;
+        let mut ids = group.pending(Bound::Unbounded, Bound::Unbounded);
+        let first = ids.next().map(|(id, _)| *id);
+        let last = ids.next_back().map(|(id, _)| *id).or(first);
+
+        let consumers: Vec<_> = group
+            .pending_by_consumer()
+            .into_iter()
+            .map(|(consumer, count)| {
+                Frame::Array(vec![
+                    Frame::Bulk(Bytes::from(consumer.to_string().into_bytes())),
+                    Frame::Bulk(Bytes::from(count.to_string().into_bytes())),
+                ])
+            })
+            .collect();
+
+        let id_frame =
+            |id: Option<StreamId>| id.map_or(Frame::Null, |id| Frame::Bulk(id_bytes(id)));
+
+        Some(Frame::Array(vec![
+            Frame::Integer(group.pending_len() as i64),
+            id_frame(first),
+            id_frame(last),
+            match consumers.is_empty() {
+                true => Frame::NullArray,
+                false => Frame::Array(consumers),
+            },
+        ]))
+    }
+
+    /// Lists the pending entries of a group within `range`.
+    #[cfg(feature = "server")]
+    fn list(stream: &Stream, group: &str, range: &PendingRange, now: Instant) -> Option<Frame> {
+        let min_idle = range.min_idle.unwrap_or_default();
+
+        let entries = stream
+            .group(group)?
+            .pending(range.start, range.end)
+            .filter(|(_, pending)| {
+                range
+                    .consumer
+                    .as_ref()
+                    .is_none_or(|consumer| *consumer == pending.consumer)
+            })
+            .map(|(id, pending)| (id, pending, now.duration_since(pending.delivered)))
+            .filter(|(.., idle)| *idle >= min_idle)
+            .take(range.count)
+            .map(|(id, pending, idle)| {
+                Frame::Array(vec![
+                    Frame::Bulk(id_bytes(*id)),
+                    Frame::Bulk(Bytes::from(pending.consumer.clone().into_bytes())),
+                    Frame::Integer(idle.as_millis() as i64),
+                    Frame::Integer(pending.deliveries as i64),
+                ])
+            })
+            .collect();
+
+        Some(Frame::Array(entries))
+    }
+}
+
+impl XClaim {
+    pub(crate) fn new(
+        key: impl ToString,
+        group: impl ToString,
+        consumer: impl ToString,
+        ids: Vec<StreamId>,
+        options: ClaimOptions,
+        time: Option<i64>,
+    ) -> Self {
+        Self {
+            key: key.to_string(),
+            group: group.to_string(),
+            consumer: consumer.to_string(),
+            ids,
+            options,
+            time,
+        }
+    }
+}
+
+impl XAutoClaim {
+    pub(crate) fn new(
+        key: impl ToString,
+        group: impl ToString,
+        consumer: impl ToString,
+        start: StreamId,
+        count: usize,
+        options: ClaimOptions,
+    ) -> Self {
+        Self {
+            key: key.to_string(),
+            group: group.to_string(),
+            consumer: consumer.to_string(),
+            start,
+            count,
+            options,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for XPending {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let now = Instant::now();
+        let pending = db.read_collection(&self.key, |stream: &Stream| match &self.range {
+            Some(range) => XPending::list(stream, &self.group, range, now),
+            None => XPending::summary(stream, &self.group),
+        });
+
+        let res = match pending {
+            Ok(Some(Some(frame))) => frame,
+            Ok(_) => no_group(&self.key, &self.group),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for XClaim {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let mut options = self.options;
+
+        // a delivery time is given as how long ago the entries were delivered
+        if let (None, Some(time)) = (options.idle, self.time) {
+            let idle = unix_time_ms().saturating_sub(time).max(0) as u64;
+            options.idle = Some(Duration::from_millis(idle));
+        }
+
+        let claimed = db.edit_collection(&self.key, false, |stream: &mut Stream| {
+            stream.claim(
+                &self.group,
+                &self.consumer,
+                &self.ids,
+                options,
+                Instant::now(),
+            )
+        });
+
+        let res = match claimed {
+            Ok(Some(Some(claimed))) => claimed_frame(claimed, options.just_id),
+            Ok(_) => no_group(&self.key, &self.group),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for XAutoClaim {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let claimed = db.edit_collection(&self.key, false, |stream: &mut Stream| {
+            stream.auto_claim(
+                &self.group,
+                &self.consumer,
+                self.start,
+                self.count,
+                self.options,
+                Instant::now(),
+            )
+        });
+
+        let res = match claimed {
+            Ok(Some(Some((next, claimed, deleted)))) => Frame::Array(vec![
+                Frame::Bulk(id_bytes(next)),
+                claimed_frame(claimed, self.options.just_id),
+                Frame::Array(
+                    deleted
+                        .into_iter()
+                        .map(|id| Frame::Bulk(id_bytes(id)))
+                        .collect(),
+                ),
+            ]),
+            Ok(_) => no_group(&self.key, &self.group),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for XPending {
+    fn representation<'a>() -> &'a str {
+        "xpending"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let (key, group) = (parser.next_string()?, parser.next_string()?);
+
+        let mut arg = match parser.next_string() {
+            Ok(arg) => arg,
+            Err(ParseError::EndOfStream) => {
+                return Ok(Self {
+                    key,
+                    group,
+                    range: None,
+                })
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut min_idle = None;
+
+        if arg.eq_ignore_ascii_case("idle") {
+            min_idle = Some(parse_ms(parser)?);
+            arg = parser.next_string()?;
+        }
+
+        let parse_bound = |arg: &str, seq| match arg {
+            "-" => Ok(Bound::Unbounded),
+            "+" => Ok(Bound::Unbounded),
+            arg => match arg.strip_prefix('(') {
+                Some(id) => parse_id(id, seq).map(Bound::Excluded),
+                None => parse_id(arg, seq).map(Bound::Included),
+            },
+        };
+
+        let start = parse_bound(&arg, 0)?;
+        let end = parse_bound(&parser.next_string()?, u64::MAX)?;
+        let count = parse_count(parser)?;
+
+        let consumer = match parser.next_string() {
+            Ok(consumer) => Some(consumer),
+            Err(ParseError::EndOfStream) => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        let range = PendingRange {
+            min_idle,
+            start,
+            end,
+            count,
+            consumer,
+        };
+
+        Ok(Self {
+            key,
+            group,
+            range: Some(range),
+        })
+    }
+}
+
+impl Command for XClaim {
+    fn representation<'a>() -> &'a str {
+        "xclaim"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let key = parser.next_string()?;
+        let (group, consumer) = (parser.next_string()?, parser.next_string()?);
+        // the options beyond the minimum idle time come after the IDs
+        let no_options = ClaimOptions {
+            min_idle: parse_ms(parser)?,
+            ..Default::default()
+        };
+
+        let mut claim = Self {
+            key,
+            group,
+            consumer,
+            ids: vec![parse_id(&parser.next_string()?, 0)?],
+            options: no_options,
+            time: None,
+        };
+
+        loop {
+            let arg = match parser.next_string() {
+                Ok(arg) => arg,
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            };
+
+            let options = &mut claim.options;
+
+            match arg.to_lowercase().as_str() {
+                "idle" => options.idle = Some(parse_ms(parser)?),
+                "time" => claim.time = Some(parser.next_int()?),
+                "retrycount" => {
+                    let count = parser.next_int()?;
+                    options.retry_count =
+                        Some(u64::try_from(count).map_err(|_| {
+                            anyhow!("Invalid RETRYCOUNT option argument for XCLAIM")
+                        })?);
+                }
+                "force" => options.force = true,
+                "justid" => options.just_id = true,
+                _ if *options == no_options && claim.time.is_none() => {
+                    claim.ids.push(parse_id(&arg, 0)?)
+                }
+                _ => return Err(syntax_error()),
+            }
+        }
+
+        Ok(claim)
+    }
+}
+
+impl Command for XAutoClaim {
+    fn representation<'a>() -> &'a str {
+        "xautoclaim"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let key = parser.next_string()?;
+        let (group, consumer) = (parser.next_string()?, parser.next_string()?);
+        let mut options = ClaimOptions {
+            min_idle: parse_ms(parser)?,
+            ..Default::default()
+        };
+
+        let start = match parser.next_string()?.as_str() {
+            "-" => StreamId::default(),
+            start => parse_id(start, 0)?,
+        };
+
+        let mut count = 100;
+
+        loop {
+            let arg = match parser.next_string() {
+                Ok(arg) => arg,
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            };
+
+            match arg.to_lowercase().as_str() {
+                "count" => {
+                    count = parse_count(parser)?;
+
+                    if count == 0 {
+                        return Err(anyhow!("COUNT must be > 0"));
+                    }
+                }
+                "justid" => options.just_id = true,
+                _ => return Err(syntax_error()),
+            }
+        }
+
+        Ok(Self {
+            key,
+            group,
+            consumer,
+            start,
+            count,
+            options,
+        })
+    }
+}
+
+impl TryInto<Frame> for XPending {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_bulk(Bytes::from(self.group.into_bytes()))?;
+
+        let Some(range) = self.range else {
+            return Ok(frame);
+        };
+
+        if let Some(min_idle) = range.min_idle {
+            frame.push_bulk(Bytes::from_static(b"IDLE"))?;
+            frame.push_bulk(ms_bytes(min_idle))?;
+        }
+
+        let bound_bytes = |bound: Bound<StreamId>, unbounded: &'static [u8]| match bound {
+            Bound::Included(id) => id_bytes(id),
+            Bound::Excluded(id) => Bytes::from(format!("({id}").into_bytes()),
+            Bound::Unbounded => Bytes::from_static(unbounded),
+        };
+
+        frame.push_bulk(bound_bytes(range.start, b"-"))?;
+        frame.push_bulk(bound_bytes(range.end, b"+"))?;
+        frame.push_int(range.count as i64)?;
+
+        if let Some(consumer) = range.consumer {
+            frame.push_bulk(Bytes::from(consumer.into_bytes()))?;
+        }
+
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for XClaim {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_bulk(Bytes::from(self.group.into_bytes()))?;
+        frame.push_bulk(Bytes::from(self.consumer.into_bytes()))?;
+        frame.push_bulk(ms_bytes(self.options.min_idle))?;
+
+        for id in self.ids {
+            frame.push_bulk(id_bytes(id))?;
+        }
+
+        if let Some(idle) = self.options.idle {
+            frame.push_bulk(Bytes::from_static(b"IDLE"))?;
+            frame.push_bulk(ms_bytes(idle))?;
+        }
+
+        if let Some(time) = self.time {
+            frame.push_bulk(Bytes::from_static(b"TIME"))?;
+            frame.push_int(time)?;
+        }
+
+        if let Some(count) = self.options.retry_count {
+            frame.push_bulk(Bytes::from_static(b"RETRYCOUNT"))?;
+            frame.push_int(count as i64)?;
+        }
+
+        if self.options.force {
+            frame.push_bulk(Bytes::from_static(b"FORCE"))?;
+        }
+
+        if self.options.just_id {
+            frame.push_bulk(Bytes::from_static(b"JUSTID"))?;
+        }
+
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for XAutoClaim {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_bulk(Bytes::from(self.group.into_bytes()))?;
+        frame.push_bulk(Bytes::from(self.consumer.into_bytes()))?;
+        frame.push_bulk(ms_bytes(self.options.min_idle))?;
+        frame.push_bulk(id_bytes(self.start))?;
+        frame.push_bulk(Bytes::from_static(b"COUNT"))?;
+        frame.push_int(self.count as i64)?;
+
+        if self.options.just_id {
+            frame.push_bulk(Bytes::from_static(b"JUSTID"))?;
+        }
+
+        Ok(frame)
+    }
+}
@@ -0,0 +1,261 @@
+use anyhow::anyhow;
+use bytes::Bytes;
+use std::ops::Bound;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{keys_frame, set::syntax_error, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::{Parse, ParseError},
+    server::database::stream::{StreamEntry, StreamId},
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{
+        database::{database::Database, stream::Stream},
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// Arguments shared by XRANGE and XREVRANGE.
+#[derive(Debug)]
+pub(crate) struct XRangeArgs {
+    key: String,
+    start: Bound<StreamId>,
+    end: Bound<StreamId>,
+    /// How many entries to reply with at most.
+    count: Option<usize>,
+}
+
+/// Replies with the entries of a stream between two IDs, oldest first.
+#[derive(Debug)]
+pub(crate) struct XRange(XRangeArgs);
+
+/// Replies with the entries of a stream between two IDs, newest first. The end of the range
+/// is given first.
+#[derive(Debug)]
+pub(crate) struct XRevRange(XRangeArgs);
+
+pub(super) fn invalid_id() -> anyhow::Error {
+    anyhow!("Invalid stream ID specified as stream command argument")
+}
+
+/// Parses an ID given as `<ms>-<seq>`, or as `<ms>` alone with `seq` as its sequence number.
+pub(super) fn parse_id(arg: &str, seq: u64) -> anyhow::Result<StreamId> {
+    let id = match arg.split_once('-') {
+        Some((ms, seq)) => ms.parse().ok().zip(seq.parse().ok()),
+        None => arg.parse().ok().map(|ms| (ms, seq)),
+    };
+
+    id.map(|(ms, seq)| StreamId::new(ms, seq))
+        .ok_or_else(invalid_id)
+}
+
+pub(super) fn id_bytes(id: StreamId) -> Bytes {
+    Bytes::from(id.to_string().into_bytes())
+}
+
+/// Replies with an entry as its ID followed by its fields and values, flattened.
+pub(super) fn entry_frame((id, fields): StreamEntry) -> Frame {
+    let fields = fields
+        .into_iter()
+        .flat_map(|(field, value)| [Frame::Bulk(field), Frame::Bulk(value)])
+        .collect();
+
+    Frame::Array(vec![Frame::Bulk(id_bytes(id)), Frame::Array(fields)])
+}
+
+pub(super) fn entries_frame(entries: Vec<StreamEntry>) -> Frame {
+    Frame::Array(entries.into_iter().map(entry_frame).collect())
+}
+
+/// Reads `COUNT count`, once `COUNT` was read. Negative counts count as zero.
+pub(super) fn parse_count(parser: &mut Parse) -> anyhow::Result<usize> {
+    Ok(parser.next_int()?.max(0) as usize)
+}
+
+/// Parses a bound of a range. `-` and `+` stand for the lowest and highest IDs, and bounds
+/// starting with `(` are excluded. A missing sequence number is taken as `seq`.
+fn parse_bound(arg: &str, seq: u64) -> anyhow::Result<Bound<StreamId>> {
+    match arg {
+        "-" => Ok(Bound::Included(StreamId::default())),
+        "+" => Ok(Bound::Included(StreamId::MAX)),
+        arg => match arg.strip_prefix('(') {
+            Some(id) => parse_id(id, seq).map(Bound::Excluded),
+            None => parse_id(arg, seq).map(Bound::Included),
+        },
+    }
+}
+
+fn bound_bytes(bound: &Bound<StreamId>, unbounded: &'static str) -> Bytes {
+    match bound {
+        Bound::Included(id) => id_bytes(*id),
+        Bound::Excluded(id) => Bytes::from(format!("({id}").into_bytes()),
+        Bound::Unbounded => Bytes::from_static(unbounded.as_bytes()),
+    }
+}
+
+impl XRangeArgs {
+    pub(crate) fn new(
+        key: impl ToString,
+        start: Bound<StreamId>,
+        end: Bound<StreamId>,
+        count: Option<usize>,
+    ) -> Self {
+        Self {
+            key: key.to_string(),
+            start,
+            end,
+            count,
+        }
+    }
+
+    fn parse(parser: &mut Parse, rev: bool) -> anyhow::Result<Self> {
+        let key = parser.next_string()?;
+        let (first, second) = (parser.next_string()?, parser.next_string()?);
+
+        let (start, end) = match rev {
+            true => (second, first),
+            false => (first, second),
+        };
+
+        let start = parse_bound(&start, 0)?;
+        let end = parse_bound(&end, u64::MAX)?;
+
+        let count = match parser.next_string() {
+            Ok(s) if s.eq_ignore_ascii_case("count") => Some(parse_count(parser)?),
+            Ok(_) => return Err(syntax_error()),
+            Err(ParseError::EndOfStream) => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self {
+            key,
+            start,
+            end,
+            count,
+        })
+    }
+
+    #[cfg(feature = "server")]
+    async fn apply(self, rev: bool, db: &Database, conn: &mut Connection) -> anyhow::Result<()> {
+        let count = self.count.unwrap_or(usize::MAX);
+        let entries = db.read_collection(&self.key, |stream: &Stream| {
+            let entries = stream.range(self.start, self.end);
+            let owned = |(id, fields): (&StreamId, &_)| (*id, Vec::clone(fields));
+
+            match rev {
+                true => entries.rev().take(count).map(owned).collect(),
+                false => entries.take(count).map(owned).collect(),
+            }
+        });
+
+        let res = match entries {
+            Ok(entries) => entries_frame(entries.unwrap_or_default()),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+
+    fn into_frame(self, name: &str, rev: bool) -> Result<Frame, FrameError> {
+        let mut frame = keys_frame(name, vec![self.key])?;
+        let start = bound_bytes(&self.start, "-");
+        let end = bound_bytes(&self.end, "+");
+
+        let (first, second) = match rev {
+            true => (end, start),
+            false => (start, end),
+        };
+
+        frame.push_bulk(first)?;
+        frame.push_bulk(second)?;
+
+        if let Some(count) = self.count {
+            frame.push_bulk(Bytes::from_static(b"COUNT"))?;
+            frame.push_int(count as i64)?;
+        }
+
+        Ok(frame)
+    }
+}
+
+impl XRange {
+    pub(crate) fn new(args: XRangeArgs) -> Self {
+        Self(args)
+    }
+}
+
+impl XRevRange {
+    pub(crate) fn new(args: XRangeArgs) -> Self {
+        Self(args)
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for XRange {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        self.0.apply(false, db, conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for XRevRange {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        self.0.apply(true, db, conn).await
+    }
+}
+
+impl Command for XRange {
+    fn representation<'a>() -> &'a str {
+        "xrange"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self(XRangeArgs::parse(parser, false)?))
+    }
+}
+
+impl Command for XRevRange {
+    fn representation<'a>() -> &'a str {
+        "xrevrange"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self(XRangeArgs::parse(parser, true)?))
+    }
+}
+
+impl TryInto<Frame> for XRange {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        self.0.into_frame(Self::representation(), false)
+    }
+}
+
+impl TryInto<Frame> for XRevRange {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        self.0.into_frame(Self::representation(), true)
+    }
+}
@@ -0,0 +1,338 @@
+use anyhow::anyhow;
+use bytes::Bytes;
+use std::time::Duration;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{
+        keys_frame,
+        set::syntax_error,
+        xrange::{id_bytes, parse_count, parse_id},
+        Command,
+    },
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+    server::database::stream::{GroupRead, StreamId},
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    codec::Protocol,
+    commands::{
+        bpop::{wait, Waited},
+        xrange::entries_frame,
+        Execute,
+    },
+    server::{
+        database::{
+            database::{Database, StreamReads},
+            stream::StreamEntry,
+        },
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// Options shared by XREAD and XREADGROUP, along with the streams they read.
+#[derive(Debug)]
+pub(crate) struct XReadArgs {
+    keys: Vec<String>,
+    /// The ID to read each stream after. `None` stands for `$`, the last ID of the stream,
+    /// for XREAD, and for `>`, the entries never delivered to the group, for XREADGROUP.
+    ids: Vec<Option<StreamId>>,
+    /// How many entries to read from each stream at most.
+    count: Option<usize>,
+    /// How long to wait for entries if there are none, `Some(None)` waiting forever.
+    block: Option<Option<Duration>>,
+}
+
+/// Replies with the entries of streams after the given IDs, waiting for entries to be added
+/// if asked to.
+#[derive(Debug)]
+pub(crate) struct XRead(XReadArgs);
+
+/// `XREAD` for a consumer of a group. New entries are delivered to the consumer and added
+/// to its pending entries, unless `NOACK` is given.
+#[derive(Debug)]
+pub(crate) struct XReadGroup {
+    read: GroupRead,
+    args: XReadArgs,
+}
+
+impl XReadArgs {
+    pub(crate) fn new(
+        keys: Vec<String>,
+        ids: Vec<Option<StreamId>>,
+        count: Option<usize>,
+        block: Option<Option<Duration>>,
+    ) -> Self {
+        Self {
+            keys,
+            ids,
+            count,
+            block,
+        }
+    }
+
+    /// Reads the options and streams following `XREAD`, or following the group and consumer
+    /// of `XREADGROUP` if `group` is set. `last` is how the ID standing for `None` is given.
+    /// Returns whether `NOACK` was given, along with the arguments.
+    fn parse(
+        parser: &mut Parse,
+        name: &str,
+        last: &str,
+        group: bool,
+    ) -> anyhow::Result<(Self, bool)> {
+        let (mut count, mut block, mut noack) = (None, None, false);
+
+        loop {
+            match parser.next_string()?.to_lowercase().as_str() {
+                // a count of zero reads every entry
+                "count" => count = Some(parse_count(parser)?).filter(|count| *count > 0),
+                "block" => {
+                    let ms = parser.next_int()?;
+
+                    if ms < 0 {
+                        return Err(anyhow!("timeout is negative"));
+                    }
+
+                    block = Some((ms > 0).then(|| Duration::from_millis(ms as u64)));
+                }
+                "noack" if group => noack = true,
+                "streams" => break,
+                _ => return Err(syntax_error()),
+            }
+        }
+
+        let mut args = parser.remaining_strings()?;
+
+        if args.is_empty() || args.len() % 2 != 0 {
+            return Err(anyhow!(
+                "Unbalanced '{name}' list of streams: for each stream key an ID or '{last}' must be specified."
+            ));
+        }
+
+        let ids = args
+            .split_off(args.len() / 2)
+            .iter()
+            .map(|id| match id.as_str() {
+                id if id == last => Ok(None),
+                "$" | ">" => Err(anyhow!(
+                    "The {id} ID is meaningless in the context of {}",
+                    name.to_uppercase()
+                )),
+                id => parse_id(id, 0).map(Some),
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        let args = Self {
+            keys: args,
+            ids,
+            count,
+            block,
+        };
+
+        Ok((args, noack))
+    }
+
+    fn push_to(self, frame: &mut Frame, last: &'static str) -> Result<(), FrameError> {
+        if let Some(count) = self.count {
+            frame.push_bulk(Bytes::from_static(b"COUNT"))?;
+            frame.push_int(count as i64)?;
+        }
+
+        if let Some(timeout) = self.block {
+            frame.push_bulk(Bytes::from_static(b"BLOCK"))?;
+            frame.push_int(timeout.map_or(0, |t| t.as_millis() as i64))?;
+        }
+
+        frame.push_bulk(Bytes::from_static(b"STREAMS"))?;
+
+        for key in self.keys {
+            frame.push_bulk(Bytes::from(key.into_bytes()))?;
+        }
+
+        for id in self.ids {
+            frame.push_bulk(id.map_or(Bytes::from_static(last.as_bytes()), id_bytes))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Replies with the entries read from each stream, as a map under RESP3 and as pairs under
+/// RESP2. Nothing read is replied as a null array.
+#[cfg(feature = "server")]
+fn streams_frame(read: Vec<(String, Vec<StreamEntry>)>, protocol: Protocol) -> Frame {
+    if read.is_empty() {
+        return Frame::NullArray;
+    }
+
+    let streams = read.into_iter().map(|(key, entries)| {
+        (
+            Frame::Bulk(Bytes::from(key.into_bytes())),
+            entries_frame(entries),
+        )
+    });
+
+    match protocol {
+        Protocol::Resp3 => Frame::Map(streams.collect()),
+        Protocol::Resp2 => Frame::Array(
+            streams
+                .map(|(key, entries)| Frame::Array(vec![key, entries]))
+                .collect(),
+        ),
+    }
+}
+
+/// Replies with what was read, waiting for entries first if the streams had none.
+#[cfg(feature = "server")]
+async fn reply(
+    read: Result<StreamReads, impl ToString>,
+    timeout: Option<Duration>,
+    conn: &mut Connection,
+    shutdown: &mut ShutdownListener,
+) -> anyhow::Result<()> {
+    let waited = match read {
+        Ok(Ok(read)) => Ok(read),
+        Ok(Err(blocked)) => match wait(blocked, timeout, conn, shutdown).await {
+            Waited::Served(Ok(read)) => Ok(vec![read]),
+            Waited::Served(Err(e)) => Err(e.to_string()),
+            Waited::TimedOut => Ok(vec![]),
+            Waited::Interrupted => return Ok(()),
+        },
+        Err(e) => Err(e.to_string()),
+    };
+
+    let res = match waited {
+        Ok(read) => streams_frame(read, conn.protocol()),
+        Err(e) => Frame::Error(e),
+    };
+
+    conn.write_frame(&res).await?;
+    Ok(())
+}
+
+impl XRead {
+    pub(crate) fn new(args: XReadArgs) -> Self {
+        Self(args)
+    }
+}
+
+impl XReadGroup {
+    pub(crate) fn new(read: GroupRead, args: XReadArgs) -> Self {
+        Self { read, args }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for XRead {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        shutdown: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let XReadArgs {
+            keys,
+            ids,
+            count,
+            block,
+        } = self.0;
+
+        let read = db.read_streams_or_block(keys, ids, count, block.is_some());
+        reply(read, block.flatten(), conn, shutdown).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for XReadGroup {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        shutdown: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let XReadArgs {
+            keys,
+            ids,
+            count,
+            block,
+        } = self.args;
+
+        let read = db.read_group_or_block(keys, ids, self.read, count, block.is_some());
+        reply(read, block.flatten(), conn, shutdown).await
+    }
+}
+
+impl Command for XRead {
+    fn representation<'a>() -> &'a str {
+        "xread"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let (args, _) = XReadArgs::parse(parser, Self::representation(), "$", false)?;
+        Ok(Self(args))
+    }
+}
+
+impl Command for XReadGroup {
+    fn representation<'a>() -> &'a str {
+        "xreadgroup"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        if !parser.next_string()?.eq_ignore_ascii_case("group") {
+            return Err(syntax_error());
+        }
+
+        let (group, consumer) = (parser.next_string()?, parser.next_string()?);
+        let (args, noack) = XReadArgs::parse(parser, Self::representation(), ">", true)?;
+
+        let read = GroupRead {
+            group,
+            consumer,
+            noack,
+        };
+
+        Ok(Self { read, args })
+    }
+}
+
+impl TryInto<Frame> for XRead {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![])?;
+        self.0.push_to(&mut frame, "$")?;
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for XReadGroup {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let GroupRead {
+            group,
+            consumer,
+            noack,
+        } = self.read;
+
+        let mut frame = keys_frame(Self::representation(), vec![])?;
+        frame.push_bulk(Bytes::from_static(b"GROUP"))?;
+        frame.push_bulk(Bytes::from(group.into_bytes()))?;
+        frame.push_bulk(Bytes::from(consumer.into_bytes()))?;
+
+        if noack {
+            frame.push_bulk(Bytes::from_static(b"NOACK"))?;
+        }
+
+        self.args.push_to(&mut frame, ">")?;
+        Ok(frame)
+    }
+}
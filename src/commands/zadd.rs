@@ -0,0 +1,399 @@
+use anyhow::anyhow;
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{keys_frame, set::syntax_error, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{
+        database::{database::Database, sorted_set::SortedSet},
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// Options of `ZADD`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct ZAddOptions {
+    /// Only adds new members.
+    pub(crate) nx: bool,
+    /// Only updates existing members.
+    pub(crate) xx: bool,
+    /// Only updates members whose score increases.
+    pub(crate) gt: bool,
+    /// Only updates members whose score decreases.
+    pub(crate) lt: bool,
+    /// Counts updated members along with the new ones.
+    pub(crate) ch: bool,
+    /// Adds the score to the member's, as `ZINCRBY`.
+    pub(crate) incr: bool,
+}
+
+/// Adds members to a sorted set, or updates their score, creating the set if needed.
+/// Replies with how many members are new, or with the new score under `INCR`.
+#[derive(Debug)]
+pub(crate) struct ZAdd {
+    key: String,
+    options: ZAddOptions,
+    pairs: Vec<(f64, Bytes)>,
+}
+
+/// Adds to the score of a member of a sorted set, a missing member counting as `0`, and
+/// replies with the result.
+#[derive(Debug)]
+pub(crate) struct ZIncrBy {
+    key: String,
+    increment: f64,
+    member: Bytes,
+}
+
+/// Removes members of a sorted set and replies with how many existed.
+#[derive(Debug)]
+pub(crate) struct ZRem {
+    key: String,
+    members: Vec<Bytes>,
+}
+
+const NAN_SCORE: &str = "ERR resulting score is not a number (NaN)";
+
+/// Parses a score. Scores may be infinite, but not NaN.
+pub(super) fn parse_score(arg: &str) -> Option<f64> {
+    arg.parse::<f64>().ok().filter(|score| !score.is_nan())
+}
+
+impl ZAddOptions {
+    /// Reads the options leading the score and member pairs.
+    fn parse(parser: &mut Parse) -> anyhow::Result<Self> {
+        let mut options = Self::default();
+
+        loop {
+            let option = match parser.peek_string()?.to_lowercase().as_str() {
+                "nx" => &mut options.nx,
+                "xx" => &mut options.xx,
+                "gt" => &mut options.gt,
+                "lt" => &mut options.lt,
+                "ch" => &mut options.ch,
+                "incr" => &mut options.incr,
+                _ => break,
+            };
+
+            *option = true;
+            parser.next_string()?;
+        }
+
+        if options.nx && options.xx {
+            return Err(anyhow!(
+                "XX and NX options at the same time are not compatible"
+            ));
+        }
+
+        if [options.nx, options.gt, options.lt]
+            .iter()
+            .filter(|set| **set)
+            .count()
+            > 1
+        {
+            return Err(anyhow!(
+                "GT, LT, and/or NX options at the same time are not compatible"
+            ));
+        }
+
+        Ok(options)
+    }
+
+    fn push_to(&self, frame: &mut Frame) -> Result<(), FrameError> {
+        let options = [
+            (self.nx, "nx"),
+            (self.xx, "xx"),
+            (self.gt, "gt"),
+            (self.lt, "lt"),
+            (self.ch, "ch"),
+            (self.incr, "incr"),
+        ];
+
+        for (_, name) in options.iter().filter(|(set, _)| *set) {
+            frame.push_bulk(Bytes::from(name.as_bytes()))?;
+        }
+
+        Ok(())
+    }
+
+    /// The score `member` is given, if any, when its current score is `current`.
+    #[cfg(feature = "server")]
+    fn apply(&self, current: Option<f64>, score: f64) -> Option<f64> {
+        match current {
+            Some(_) if self.nx => None,
+            None if self.xx => None,
+            None => Some(score),
+            Some(current) => {
+                let score = if self.incr { current + score } else { score };
+
+                if self.gt && score <= current || self.lt && score >= current {
+                    return None;
+                }
+
+                Some(score)
+            }
+        }
+    }
+}
+
+impl ZAdd {
+    pub(crate) fn new(key: impl ToString, options: ZAddOptions, pairs: Vec<(f64, Bytes)>) -> Self {
+        Self {
+            key: key.to_string(),
+            options,
+            pairs,
+        }
+    }
+}
+
+impl ZIncrBy {
+    pub(crate) fn new(key: impl ToString, increment: f64, member: Bytes) -> Self {
+        Self {
+            key: key.to_string(),
+            increment,
+            member,
+        }
+    }
+}
+
+impl ZRem {
+    pub(crate) fn new(key: impl ToString, members: Vec<Bytes>) -> Self {
+        Self {
+            key: key.to_string(),
+            members,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for ZAdd {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let options = self.options;
+        let res = db.edit_collection(&self.key, !options.xx, |set: &mut SortedSet| {
+            let (mut added, mut changed, mut last) = (0, 0, None);
+
+            for (score, member) in self.pairs {
+                let current = set.score(&member);
+                last = options.apply(current, score);
+
+                let Some(score) = last else {
+                    continue;
+                };
+
+                if score.is_nan() {
+                    return Err(NAN_SCORE);
+                }
+
+                match set.insert(member, score) {
+                    None => added += 1,
+                    Some(previous) if previous != score => changed += 1,
+                    Some(_) => {}
+                }
+            }
+
+            Ok(match options {
+                ZAddOptions { incr: true, .. } => last.map_or(Frame::Null, Frame::Double),
+                ZAddOptions { ch: true, .. } => Frame::Integer(added + changed),
+                _ => Frame::Integer(added),
+            })
+        });
+
+        let res = match res {
+            Ok(Some(Ok(frame))) => frame,
+            Ok(Some(Err(e))) => Frame::Error(e.to_string()),
+            Ok(None) if options.incr => Frame::Null,
+            Ok(None) => Frame::Integer(0),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for ZIncrBy {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let res = db.edit_collection(&self.key, true, |set: &mut SortedSet| {
+            let score = set.score(&self.member).unwrap_or(0.0) + self.increment;
+
+            if score.is_nan() {
+                return Err(NAN_SCORE);
+            }
+
+            set.insert(self.member, score);
+            Ok(score)
+        });
+
+        let res = match res {
+            Ok(Some(Ok(score))) => Frame::Double(score),
+            Ok(Some(Err(e))) => Frame::Error(e.to_string()),
+            Ok(None) => unreachable!("missing sorted sets are created"),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for ZRem {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let removed = db.edit_collection(&self.key, false, |set: &mut SortedSet| {
+            self.members
+                .iter()
+                .filter(|member| set.remove(member).is_some())
+                .count()
+        });
+
+        let res = match removed {
+            Ok(removed) => Frame::Integer(removed.unwrap_or(0) as i64),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+impl Command for ZAdd {
+    fn representation<'a>() -> &'a str {
+        "zadd"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let key = parser.next_string()?;
+        let options = ZAddOptions::parse(parser)?;
+        let args = parser.remaining_bytes()?;
+
+        if args.is_empty() || args.len() % 2 != 0 {
+            return Err(syntax_error());
+        }
+
+        if options.incr && args.len() > 2 {
+            return Err(anyhow!(
+                "INCR option supports a single increment-element pair"
+            ));
+        }
+
+        let pairs = args
+            .chunks(2)
+            .map(|pair| {
+                let score = std::str::from_utf8(&pair[0])
+                    .ok()
+                    .and_then(parse_score)
+                    .ok_or_else(|| anyhow!("value is not a valid float"))?;
+
+                Ok((score, pair[1].clone()))
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Self {
+            key,
+            options,
+            pairs,
+        })
+    }
+}
+
+impl Command for ZIncrBy {
+    fn representation<'a>() -> &'a str {
+        "zincrby"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let key = parser.next_string()?;
+        let increment = parse_score(&parser.next_string()?)
+            .ok_or_else(|| anyhow!("value is not a valid float"))?;
+
+        Ok(Self {
+            key,
+            increment,
+            member: parser.next_bytes()?,
+        })
+    }
+}
+
+impl Command for ZRem {
+    fn representation<'a>() -> &'a str {
+        "zrem"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let key = parser.next_string()?;
+        let mut members = vec![parser.next_bytes()?];
+        members.extend(parser.remaining_bytes()?);
+
+        Ok(Self { key, members })
+    }
+}
+
+impl TryInto<Frame> for ZAdd {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        self.options.push_to(&mut frame)?;
+
+        for (score, member) in self.pairs {
+            frame.push_bulk(Bytes::from(score.to_string().into_bytes()))?;
+            frame.push_bulk(member)?;
+        }
+
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for ZIncrBy {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_bulk(Bytes::from(self.increment.to_string().into_bytes()))?;
+        frame.push_bulk(self.member)?;
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for ZRem {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+
+        for member in self.members {
+            frame.push_bulk(member)?;
+        }
+
+        Ok(frame)
+    }
+}
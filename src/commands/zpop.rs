@@ -0,0 +1,279 @@
+use std::time::Duration;
+
+#[cfg(feature = "server")]
+use {async_trait::async_trait, bytes::Bytes};
+
+use crate::{
+    commands::{
+        bpop::{parse_keys, timeout_bytes},
+        keys_frame,
+        pop::{count_frame, parse_count},
+        Command,
+    },
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::Parse,
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    codec::Protocol,
+    commands::{
+        bpop::{wait, Waited},
+        zrange::scored_frame,
+        Execute,
+    },
+    server::{
+        database::{database::Database, sorted_set::SortedSet},
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// Pops the members with the lowest scores of a sorted set, and replies with them and their
+/// scores.
+#[derive(Debug)]
+pub(crate) struct ZPopMin {
+    key: String,
+    count: Option<usize>,
+}
+
+/// Pops the members with the highest scores of a sorted set.
+#[derive(Debug)]
+pub(crate) struct ZPopMax {
+    key: String,
+    count: Option<usize>,
+}
+
+/// Pops the member with the lowest score of the first non-empty sorted set, waiting for one
+/// to be added if they are all empty.
+#[derive(Debug)]
+pub(crate) struct BZPopMin {
+    keys: Vec<String>,
+    /// How long to wait, forever if `None`.
+    timeout: Option<Duration>,
+}
+
+/// Pops the member with the highest score of the first non-empty sorted set, waiting for one
+/// to be added if they are all empty.
+#[derive(Debug)]
+pub(crate) struct BZPopMax {
+    keys: Vec<String>,
+    timeout: Option<Duration>,
+}
+
+impl ZPopMin {
+    pub(crate) fn new(key: impl ToString, count: Option<usize>) -> Self {
+        Self {
+            key: key.to_string(),
+            count,
+        }
+    }
+}
+
+impl ZPopMax {
+    pub(crate) fn new(key: impl ToString, count: Option<usize>) -> Self {
+        Self {
+            key: key.to_string(),
+            count,
+        }
+    }
+}
+
+impl BZPopMin {
+    pub(crate) fn new(keys: Vec<String>, timeout: Option<Duration>) -> Self {
+        Self { keys, timeout }
+    }
+}
+
+impl BZPopMax {
+    pub(crate) fn new(keys: Vec<String>, timeout: Option<Duration>) -> Self {
+        Self { keys, timeout }
+    }
+}
+
+#[cfg(feature = "server")]
+async fn pop(
+    key: &str,
+    count: Option<usize>,
+    max: bool,
+    db: &Database,
+    conn: &mut Connection,
+) -> anyhow::Result<()> {
+    let popped = db.edit_collection(key, false, |set: &mut SortedSet| {
+        (0..count.unwrap_or(1))
+            .map_while(|_| set.pop(max))
+            .collect::<Vec<_>>()
+    });
+
+    let res = match popped {
+        // a single member is replied flattened whatever the protocol
+        Ok(popped) if count.is_none() => scored_frame(popped.unwrap_or_default(), Protocol::Resp2),
+        Ok(popped) => scored_frame(popped.unwrap_or_default(), conn.protocol()),
+        Err(e) => Frame::Error(e.to_string()),
+    };
+
+    conn.write_frame(&res).await?;
+    Ok(())
+}
+
+#[cfg(feature = "server")]
+async fn blocking_pop(
+    keys: Vec<String>,
+    max: bool,
+    timeout: Option<Duration>,
+    db: &Database,
+    conn: &mut Connection,
+    shutdown: &mut ShutdownListener,
+) -> anyhow::Result<()> {
+    let waited = match db.pop_member_or_block(keys, max) {
+        Ok(Ok(popped)) => Waited::Served(Ok(popped)),
+        Ok(Err(blocked)) => wait(blocked, timeout, conn, shutdown).await,
+        Err(e) => Waited::Served(Err(e)),
+    };
+
+    let res = match waited {
+        Waited::Served(Ok((key, (member, score)))) => Frame::Array(vec![
+            Frame::Bulk(Bytes::from(key.into_bytes())),
+            Frame::Bulk(member),
+            Frame::Double(score),
+        ]),
+        Waited::Served(Err(e)) => Frame::Error(e.to_string()),
+        Waited::TimedOut => Frame::NullArray,
+        Waited::Interrupted => return Ok(()),
+    };
+
+    conn.write_frame(&res).await?;
+    Ok(())
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for ZPopMin {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        pop(&self.key, self.count, false, db, conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for ZPopMax {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        pop(&self.key, self.count, true, db, conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for BZPopMin {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        shutdown: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        blocking_pop(self.keys, false, self.timeout, db, conn, shutdown).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for BZPopMax {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        shutdown: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        blocking_pop(self.keys, true, self.timeout, db, conn, shutdown).await
+    }
+}
+
+impl Command for ZPopMin {
+    fn representation<'a>() -> &'a str {
+        "zpopmin"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let (key, count) = parse_count(parser)?;
+        Ok(Self { key, count })
+    }
+}
+
+impl Command for ZPopMax {
+    fn representation<'a>() -> &'a str {
+        "zpopmax"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let (key, count) = parse_count(parser)?;
+        Ok(Self { key, count })
+    }
+}
+
+impl Command for BZPopMin {
+    fn representation<'a>() -> &'a str {
+        "bzpopmin"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let (keys, timeout) = parse_keys(parser)?;
+        Ok(Self { keys, timeout })
+    }
+}
+
+impl Command for BZPopMax {
+    fn representation<'a>() -> &'a str {
+        "bzpopmax"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let (keys, timeout) = parse_keys(parser)?;
+        Ok(Self { keys, timeout })
+    }
+}
+
+impl TryInto<Frame> for ZPopMin {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        count_frame(Self::representation(), self.key, self.count)
+    }
+}
+
+impl TryInto<Frame> for ZPopMax {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        count_frame(Self::representation(), self.key, self.count)
+    }
+}
+
+impl TryInto<Frame> for BZPopMin {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), self.keys)?;
+        frame.push_bulk(timeout_bytes(self.timeout))?;
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for BZPopMax {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), self.keys)?;
+        frame.push_bulk(timeout_bytes(self.timeout))?;
+        Ok(frame)
+    }
+}
@@ -0,0 +1,472 @@
+use anyhow::anyhow;
+use bytes::Bytes;
+use std::ops::Bound;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    codec::Protocol,
+    commands::{keys_frame, set::syntax_error, zadd::parse_score, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::{Parse, ParseError},
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::{lrange::list_range, Execute},
+    server::{
+        database::{database::Database, sorted_set::SortedSet},
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// A bound of a lexicographic range. `-` and `+` stand for the lowest and highest members.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum LexBound {
+    Lowest,
+    Highest,
+    Included(Bytes),
+    Excluded(Bytes),
+}
+
+/// How the members of a `ZRANGE` are selected. Bounds are given lowest first, whichever
+/// order the members are replied in.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum RangeBy {
+    /// Members by rank, negative ranks counting from the end.
+    Rank(i64, i64),
+    Score(Bound<f64>, Bound<f64>),
+    Lex(LexBound, LexBound),
+}
+
+/// Replies with the members of a sorted set within a range of ranks, scores or members,
+/// lowest score first unless reversed.
+#[derive(Debug)]
+pub(crate) struct ZRange {
+    key: String,
+    by: RangeBy,
+    rev: bool,
+    /// How many of the members in range to skip, and how many to reply with at most.
+    limit: Option<(i64, i64)>,
+    with_scores: bool,
+}
+
+/// `ZRANGE BYSCORE`, in the older syntax.
+#[derive(Debug)]
+pub(crate) struct ZRangeByScore {
+    key: String,
+    min: Bound<f64>,
+    max: Bound<f64>,
+    limit: Option<(i64, i64)>,
+    with_scores: bool,
+}
+
+/// Replies with members and their scores, as pairs under RESP3 and flattened under RESP2.
+pub(super) fn scored_frame(members: Vec<(Bytes, f64)>, protocol: Protocol) -> Frame {
+    let pairs = members
+        .into_iter()
+        .map(|(member, score)| [Frame::Bulk(member), Frame::Double(score)]);
+
+    match protocol {
+        Protocol::Resp3 => Frame::Array(pairs.map(|pair| Frame::Array(pair.into())).collect()),
+        Protocol::Resp2 => Frame::Array(pairs.flatten().collect()),
+    }
+}
+
+fn parse_score_bound(arg: &str) -> anyhow::Result<Bound<f64>> {
+    let (bound, score): (fn(f64) -> Bound<f64>, _) = match arg.strip_prefix('(') {
+        Some(score) => (Bound::Excluded, score),
+        None => (Bound::Included, arg),
+    };
+
+    parse_score(score)
+        .map(bound)
+        .ok_or_else(|| anyhow!("min or max is not a float"))
+}
+
+/// The argument a score bound is given as, `unbounded` standing for no bound.
+fn score_bound_bytes(bound: &Bound<f64>, unbounded: &str) -> Bytes {
+    let arg = match bound {
+        Bound::Included(score) => score.to_string(),
+        Bound::Excluded(score) => format!("({score}"),
+        Bound::Unbounded => unbounded.to_string(),
+    };
+
+    Bytes::from(arg.into_bytes())
+}
+
+impl LexBound {
+    fn parse(arg: Bytes) -> anyhow::Result<Self> {
+        match arg.first() {
+            Some(b'-') if arg.len() == 1 => Ok(LexBound::Lowest),
+            Some(b'+') if arg.len() == 1 => Ok(LexBound::Highest),
+            Some(b'[') => Ok(LexBound::Included(arg.slice(1..))),
+            Some(b'(') => Ok(LexBound::Excluded(arg.slice(1..))),
+            _ => Err(anyhow!("min or max not valid string range item")),
+        }
+    }
+
+    fn to_bytes(&self) -> Bytes {
+        match self {
+            LexBound::Lowest => Bytes::from_static(b"-"),
+            LexBound::Highest => Bytes::from_static(b"+"),
+            LexBound::Included(member) => [&b"["[..], member].concat().into(),
+            LexBound::Excluded(member) => [&b"("[..], member].concat().into(),
+        }
+    }
+
+    /// The bound as the lower bound of a range, `None` if nothing is above it.
+    #[cfg(feature = "server")]
+    fn as_min(&self) -> Option<Bound<&[u8]>> {
+        match self {
+            LexBound::Lowest => Some(Bound::Unbounded),
+            LexBound::Highest => None,
+            LexBound::Included(member) => Some(Bound::Included(member)),
+            LexBound::Excluded(member) => Some(Bound::Excluded(member)),
+        }
+    }
+
+    /// The bound as the upper bound of a range, `None` if nothing is below it.
+    #[cfg(feature = "server")]
+    fn as_max(&self) -> Option<Bound<&[u8]>> {
+        match self {
+            LexBound::Lowest => None,
+            LexBound::Highest => Some(Bound::Unbounded),
+            LexBound::Included(member) => Some(Bound::Included(member)),
+            LexBound::Excluded(member) => Some(Bound::Excluded(member)),
+        }
+    }
+}
+
+impl RangeBy {
+    /// The members in range, reversed if `rev` is set, after skipping and limiting them.
+    #[cfg(feature = "server")]
+    fn select(&self, set: &SortedSet, rev: bool, limit: Option<(i64, i64)>) -> Vec<(Bytes, f64)> {
+        let (offset, count) = match limit {
+            Some((offset, _)) if offset < 0 => return vec![],
+            Some((offset, count)) => (
+                offset as usize,
+                usize::try_from(count).unwrap_or(usize::MAX),
+            ),
+            None => (0, usize::MAX),
+        };
+
+        match self {
+            RangeBy::Rank(start, stop) => match list_range(*start, *stop, set.len()) {
+                // reversed ranks count from the highest score
+                Some(ranks) => pick(set.iter(), rev, *ranks.start(), ranks.count()),
+                None => vec![],
+            },
+            RangeBy::Score(min, max) => pick(set.range_by_score(*min, *max), rev, offset, count),
+            RangeBy::Lex(min, max) => match (min.as_min(), max.as_max()) {
+                (Some(min), Some(max)) => pick(set.range_by_lex(min, max), rev, offset, count),
+                _ => vec![],
+            },
+        }
+    }
+}
+
+/// Skips `offset` of `members` and takes `count` of them, walking them backwards if `rev`
+/// is set.
+#[cfg(feature = "server")]
+fn pick<'a>(
+    members: impl DoubleEndedIterator<Item = (&'a Bytes, f64)>,
+    rev: bool,
+    offset: usize,
+    count: usize,
+) -> Vec<(Bytes, f64)> {
+    let owned = |(member, score): (&Bytes, f64)| (member.clone(), score);
+
+    match rev {
+        true => members.rev().skip(offset).take(count).map(owned).collect(),
+        false => members.skip(offset).take(count).map(owned).collect(),
+    }
+}
+
+/// Reads `LIMIT offset count`, once `LIMIT` was read.
+fn parse_limit(parser: &mut Parse) -> anyhow::Result<(i64, i64)> {
+    Ok((parser.next_int()?, parser.next_int()?))
+}
+
+fn push_options(
+    frame: &mut Frame,
+    limit: Option<(i64, i64)>,
+    with_scores: bool,
+) -> Result<(), FrameError> {
+    if let Some((offset, count)) = limit {
+        frame.push_bulk(Bytes::from_static(b"LIMIT"))?;
+        frame.push_int(offset)?;
+        frame.push_int(count)?;
+    }
+
+    if with_scores {
+        frame.push_bulk(Bytes::from_static(b"WITHSCORES"))?;
+    }
+
+    Ok(())
+}
+
+impl ZRange {
+    pub(crate) fn new(
+        key: impl ToString,
+        by: RangeBy,
+        rev: bool,
+        limit: Option<(i64, i64)>,
+        with_scores: bool,
+    ) -> Self {
+        Self {
+            key: key.to_string(),
+            by,
+            rev,
+            limit,
+            with_scores,
+        }
+    }
+}
+
+impl ZRangeByScore {
+    pub(crate) fn new(
+        key: impl ToString,
+        min: Bound<f64>,
+        max: Bound<f64>,
+        limit: Option<(i64, i64)>,
+        with_scores: bool,
+    ) -> Self {
+        Self {
+            key: key.to_string(),
+            min,
+            max,
+            limit,
+            with_scores,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+async fn range(
+    key: &str,
+    by: RangeBy,
+    rev: bool,
+    limit: Option<(i64, i64)>,
+    with_scores: bool,
+    db: &Database,
+    conn: &mut Connection,
+) -> anyhow::Result<()> {
+    let members = db.read_collection(key, |set: &SortedSet| by.select(set, rev, limit));
+
+    let res = match members {
+        Ok(members) => {
+            let members = members.unwrap_or_default();
+
+            match with_scores {
+                true => scored_frame(members, conn.protocol()),
+                false => Frame::Array(members.into_iter().map(|(m, _)| Frame::Bulk(m)).collect()),
+            }
+        }
+        Err(e) => Frame::Error(e.to_string()),
+    };
+
+    conn.write_frame(&res).await?;
+    Ok(())
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for ZRange {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        range(
+            &self.key,
+            self.by,
+            self.rev,
+            self.limit,
+            self.with_scores,
+            db,
+            conn,
+        )
+        .await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for ZRangeByScore {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let by = RangeBy::Score(self.min, self.max);
+        range(&self.key, by, false, self.limit, self.with_scores, db, conn).await
+    }
+}
+
+impl Command for ZRange {
+    fn representation<'a>() -> &'a str {
+        "zrange"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let key = parser.next_string()?;
+        let (start, stop) = (parser.next_bytes()?, parser.next_bytes()?);
+
+        let (mut by_score, mut by_lex, mut rev, mut limit, mut with_scores) =
+            (false, false, false, None, false);
+
+        loop {
+            match parser.next_string() {
+                Ok(s) => match s.to_lowercase().as_str() {
+                    "byscore" => by_score = true,
+                    "bylex" => by_lex = true,
+                    "rev" => rev = true,
+                    "limit" => limit = Some(parse_limit(parser)?),
+                    "withscores" => with_scores = true,
+                    _ => return Err(syntax_error()),
+                },
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        if limit.is_some() && !by_score && !by_lex {
+            return Err(anyhow!(
+                "syntax error, LIMIT is only supported in combination with either BYSCORE or BYLEX"
+            ));
+        }
+
+        if with_scores && by_lex {
+            return Err(anyhow!(
+                "syntax error, WITHSCORES not supported in combination with BYLEX"
+            ));
+        }
+
+        // reversed score and lexicographic ranges are given highest first
+        let (low, high) = match rev && (by_score || by_lex) {
+            true => (stop, start),
+            false => (start, stop),
+        };
+
+        let by = match (by_score, by_lex) {
+            (true, true) => return Err(syntax_error()),
+            (true, false) => {
+                let parse =
+                    |arg: &Bytes| parse_score_bound(std::str::from_utf8(arg).unwrap_or_default());
+
+                RangeBy::Score(parse(&low)?, parse(&high)?)
+            }
+            (false, true) => RangeBy::Lex(LexBound::parse(low)?, LexBound::parse(high)?),
+            (false, false) => {
+                let parse = |arg: &Bytes| {
+                    std::str::from_utf8(arg)
+                        .ok()
+                        .and_then(|arg| arg.parse().ok())
+                        .ok_or_else(|| anyhow!("value is not an integer or out of range"))
+                };
+
+                RangeBy::Rank(parse(&low)?, parse(&high)?)
+            }
+        };
+
+        Ok(Self {
+            key,
+            by,
+            rev,
+            limit,
+            with_scores,
+        })
+    }
+}
+
+impl Command for ZRangeByScore {
+    fn representation<'a>() -> &'a str {
+        "zrangebyscore"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let key = parser.next_string()?;
+        let min = parse_score_bound(&parser.next_string()?)?;
+        let max = parse_score_bound(&parser.next_string()?)?;
+
+        let (mut limit, mut with_scores) = (None, false);
+
+        loop {
+            match parser.next_string() {
+                Ok(s) => match s.to_lowercase().as_str() {
+                    "limit" => limit = Some(parse_limit(parser)?),
+                    "withscores" => with_scores = true,
+                    _ => return Err(syntax_error()),
+                },
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(Self {
+            key,
+            min,
+            max,
+            limit,
+            with_scores,
+        })
+    }
+}
+
+impl TryInto<Frame> for ZRange {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+
+        let (low, high, by) = match &self.by {
+            RangeBy::Rank(start, stop) => (
+                Bytes::from(start.to_string().into_bytes()),
+                Bytes::from(stop.to_string().into_bytes()),
+                None,
+            ),
+            RangeBy::Score(min, max) => (
+                score_bound_bytes(min, "-inf"),
+                score_bound_bytes(max, "+inf"),
+                Some("BYSCORE"),
+            ),
+            RangeBy::Lex(min, max) => (min.to_bytes(), max.to_bytes(), Some("BYLEX")),
+        };
+
+        let (start, stop) = match self.rev && by.is_some() {
+            true => (high, low),
+            false => (low, high),
+        };
+
+        frame.push_bulk(start)?;
+        frame.push_bulk(stop)?;
+
+        if let Some(by) = by {
+            frame.push_bulk(Bytes::from(by.as_bytes()))?;
+        }
+
+        if self.rev {
+            frame.push_bulk(Bytes::from_static(b"REV"))?;
+        }
+
+        push_options(&mut frame, self.limit, self.with_scores)?;
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for ZRangeByScore {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_bulk(score_bound_bytes(&self.min, "-inf"))?;
+        frame.push_bulk(score_bound_bytes(&self.max, "+inf"))?;
+        push_options(&mut frame, self.limit, self.with_scores)?;
+        Ok(frame)
+    }
+}
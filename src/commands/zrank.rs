@@ -0,0 +1,317 @@
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use async_trait::async_trait;
+
+use crate::{
+    commands::{keys_frame, set::syntax_error, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::{Parse, ParseError},
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{
+        database::{database::Database, sorted_set::SortedSet},
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// Replies with the score of a member of a sorted set.
+#[derive(Debug)]
+pub(crate) struct ZScore {
+    key: String,
+    member: Bytes,
+}
+
+/// Replies with the number of members of a sorted set.
+#[derive(Debug)]
+pub(crate) struct ZCard {
+    key: String,
+}
+
+/// Replies with the rank of a member of a sorted set, counting from the lowest score, and
+/// with its score too if asked to.
+#[derive(Debug)]
+pub(crate) struct ZRank {
+    key: String,
+    member: Bytes,
+    with_score: bool,
+}
+
+/// `ZRANK`, counting from the highest score.
+#[derive(Debug)]
+pub(crate) struct ZRevRank {
+    key: String,
+    member: Bytes,
+    with_score: bool,
+}
+
+impl ZScore {
+    pub(crate) fn new(key: impl ToString, member: Bytes) -> Self {
+        Self {
+            key: key.to_string(),
+            member,
+        }
+    }
+}
+
+impl ZCard {
+    pub(crate) fn new(key: impl ToString) -> Self {
+        Self {
+            key: key.to_string(),
+        }
+    }
+}
+
+impl ZRank {
+    pub(crate) fn new(key: impl ToString, member: Bytes, with_score: bool) -> Self {
+        Self {
+            key: key.to_string(),
+            member,
+            with_score,
+        }
+    }
+}
+
+impl ZRevRank {
+    pub(crate) fn new(key: impl ToString, member: Bytes, with_score: bool) -> Self {
+        Self {
+            key: key.to_string(),
+            member,
+            with_score,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+async fn rank(
+    key: &str,
+    member: &[u8],
+    with_score: bool,
+    rev: bool,
+    db: &Database,
+    conn: &mut Connection,
+) -> anyhow::Result<()> {
+    let ranked = db.read_collection(key, |set: &SortedSet| {
+        let rank = set.rank(member)?;
+        let rank = if rev { set.len() - 1 - rank } else { rank };
+
+        Some((rank, set.score(member)?))
+    });
+
+    let res = match ranked {
+        Ok(Some(Some((rank, score)))) if with_score => {
+            Frame::Array(vec![Frame::Integer(rank as i64), Frame::Double(score)])
+        }
+        Ok(Some(Some((rank, _)))) => Frame::Integer(rank as i64),
+        Ok(_) if with_score => Frame::NullArray,
+        Ok(_) => Frame::Null,
+        Err(e) => Frame::Error(e.to_string()),
+    };
+
+    conn.write_frame(&res).await?;
+    Ok(())
+}
+
+/// Reads a key and a member, followed by an optional `WITHSCORE`.
+fn parse_rank(parser: &mut Parse) -> anyhow::Result<(String, Bytes, bool)> {
+    let key = parser.next_string()?;
+    let member = parser.next_bytes()?;
+
+    let with_score = match parser.next_string() {
+        Ok(s) if s.eq_ignore_ascii_case("withscore") => true,
+        Ok(_) => return Err(syntax_error()),
+        Err(ParseError::EndOfStream) => false,
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok((key, member, with_score))
+}
+
+fn rank_frame(
+    name: &str,
+    key: String,
+    member: Bytes,
+    with_score: bool,
+) -> Result<Frame, FrameError> {
+    let mut frame = keys_frame(name, vec![key])?;
+    frame.push_bulk(member)?;
+
+    if with_score {
+        frame.push_bulk(Bytes::from_static(b"WITHSCORE"))?;
+    }
+
+    Ok(frame)
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for ZScore {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let score = db.read_collection(&self.key, |set: &SortedSet| set.score(&self.member));
+
+        let res = match score {
+            Ok(score) => score.flatten().map_or(Frame::Null, Frame::Double),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for ZCard {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        let len = db.read_collection(&self.key, |set: &SortedSet| set.len());
+
+        let res = match len {
+            Ok(len) => Frame::Integer(len.unwrap_or(0) as i64),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for ZRank {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        rank(&self.key, &self.member, self.with_score, false, db, conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for ZRevRank {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        rank(&self.key, &self.member, self.with_score, true, db, conn).await
+    }
+}
+
+impl Command for ZScore {
+    fn representation<'a>() -> &'a str {
+        "zscore"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+            member: parser.next_bytes()?,
+        })
+    }
+}
+
+impl Command for ZCard {
+    fn representation<'a>() -> &'a str {
+        "zcard"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self {
+            key: parser.next_string()?,
+        })
+    }
+}
+
+impl Command for ZRank {
+    fn representation<'a>() -> &'a str {
+        "zrank"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let (key, member, with_score) = parse_rank(parser)?;
+
+        Ok(Self {
+            key,
+            member,
+            with_score,
+        })
+    }
+}
+
+impl Command for ZRevRank {
+    fn representation<'a>() -> &'a str {
+        "zrevrank"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        let (key, member, with_score) = parse_rank(parser)?;
+
+        Ok(Self {
+            key,
+            member,
+            with_score,
+        })
+    }
+}
+
+impl TryInto<Frame> for ZScore {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        let mut frame = keys_frame(Self::representation(), vec![self.key])?;
+        frame.push_bulk(self.member)?;
+        Ok(frame)
+    }
+}
+
+impl TryInto<Frame> for ZCard {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        keys_frame(Self::representation(), vec![self.key])
+    }
+}
+
+impl TryInto<Frame> for ZRank {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        rank_frame(
+            Self::representation(),
+            self.key,
+            self.member,
+            self.with_score,
+        )
+    }
+}
+
+impl TryInto<Frame> for ZRevRank {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        rank_frame(
+            Self::representation(),
+            self.key,
+            self.member,
+            self.with_score,
+        )
+    }
+}
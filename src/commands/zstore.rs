@@ -0,0 +1,294 @@
+use anyhow::anyhow;
+use bytes::Bytes;
+
+#[cfg(feature = "server")]
+use {async_trait::async_trait, std::collections::HashMap};
+
+use crate::{
+    commands::{set::syntax_error, zadd::parse_score, Command},
+    connection::Connection,
+    frame::{Frame, FrameError},
+    parse::{Parse, ParseError},
+};
+
+#[cfg(feature = "server")]
+use crate::{
+    commands::Execute,
+    server::{
+        database::{database::Database, sorted_set::SortedSet},
+        shutdown_listener::ShutdownListener,
+    },
+};
+
+/// How the scores a member has in several sorted sets are combined.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum Aggregate {
+    #[default]
+    Sum,
+    Min,
+    Max,
+}
+
+/// Arguments shared by ZUNIONSTORE and ZINTERSTORE.
+#[derive(Debug)]
+pub(crate) struct ZStoreArgs {
+    dest: String,
+    keys: Vec<String>,
+    /// What the scores of each sorted set are multiplied by, `1` for every set if `None`.
+    weights: Option<Vec<f64>>,
+    aggregate: Aggregate,
+}
+
+/// Stores the union of sorted sets at `dest` and replies with its size.
+#[derive(Debug)]
+pub(crate) struct ZUnionStore(ZStoreArgs);
+
+/// Stores the intersection of sorted sets at `dest` and replies with its size.
+#[derive(Debug)]
+pub(crate) struct ZInterStore(ZStoreArgs);
+
+impl Aggregate {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Aggregate::Sum => "sum",
+            Aggregate::Min => "min",
+            Aggregate::Max => "max",
+        }
+    }
+
+    #[cfg(feature = "server")]
+    fn apply(&self, a: f64, b: f64) -> f64 {
+        match self {
+            // infinities of opposite signs add up to zero rather than NaN
+            Aggregate::Sum => Some(a + b).filter(|sum| !sum.is_nan()).unwrap_or(0.0),
+            Aggregate::Min => a.min(b),
+            Aggregate::Max => a.max(b),
+        }
+    }
+}
+
+impl ZStoreArgs {
+    pub(crate) fn new(
+        dest: impl ToString,
+        keys: Vec<String>,
+        weights: Option<Vec<f64>>,
+        aggregate: Aggregate,
+    ) -> Self {
+        Self {
+            dest: dest.to_string(),
+            keys,
+            weights,
+            aggregate,
+        }
+    }
+
+    fn parse(parser: &mut Parse, name: &str) -> anyhow::Result<Self> {
+        let dest = parser.next_string()?;
+        let count = parser.next_int()?;
+
+        if count <= 0 {
+            return Err(anyhow!(
+                "at least 1 input key is needed for '{name}' command"
+            ));
+        }
+
+        let keys = (0..count)
+            .map(|_| match parser.next_string() {
+                Err(ParseError::EndOfStream) => Err(syntax_error()),
+                key => Ok(key?),
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let (mut weights, mut aggregate) = (None, Aggregate::default());
+
+        loop {
+            match parser.next_string() {
+                Ok(s) => match s.to_lowercase().as_str() {
+                    "weights" => {
+                        let parsed = keys
+                            .iter()
+                            .map(|_| {
+                                parser
+                                    .next_string()
+                                    .ok()
+                                    .and_then(|w| parse_score(&w))
+                                    .ok_or_else(|| anyhow!("weight value is not a float"))
+                            })
+                            .collect::<anyhow::Result<_>>()?;
+
+                        weights = Some(parsed);
+                    }
+                    "aggregate" => {
+                        aggregate = match parser.next_string()?.to_lowercase().as_str() {
+                            "sum" => Aggregate::Sum,
+                            "min" => Aggregate::Min,
+                            "max" => Aggregate::Max,
+                            _ => return Err(syntax_error()),
+                        }
+                    }
+                    _ => return Err(syntax_error()),
+                },
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(Self {
+            dest,
+            keys,
+            weights,
+            aggregate,
+        })
+    }
+
+    /// Combines the sorted sets, keeping members of every set if `inter` is set, and of
+    /// any set otherwise.
+    #[cfg(feature = "server")]
+    fn combine(&self, sets: Vec<Option<&SortedSet>>, inter: bool) -> SortedSet {
+        if inter && sets.iter().any(Option::is_none) {
+            return SortedSet::default();
+        }
+
+        let mut scores: HashMap<&Bytes, (f64, usize)> = HashMap::new();
+
+        for (i, set) in sets.iter().enumerate() {
+            let weight = self.weights.as_ref().map_or(1.0, |weights| weights[i]);
+
+            for (member, score) in set.iter().flat_map(|set| set.iter()) {
+                // an infinite score weighted by zero counts as zero
+                let score = Some(score * weight)
+                    .filter(|score| !score.is_nan())
+                    .unwrap_or(0.0);
+
+                scores
+                    .entry(member)
+                    .and_modify(|(acc, seen)| {
+                        *acc = self.aggregate.apply(*acc, score);
+                        *seen += 1;
+                    })
+                    .or_insert((score, 1));
+            }
+        }
+
+        let mut combined = SortedSet::default();
+
+        for (member, (score, seen)) in scores {
+            if !inter || seen == sets.len() {
+                combined.insert(member.clone(), score);
+            }
+        }
+
+        combined
+    }
+
+    #[cfg(feature = "server")]
+    async fn apply(self, inter: bool, db: &Database, conn: &mut Connection) -> anyhow::Result<()> {
+        let len = db.store_collection(&self.dest, &self.keys, |sets| self.combine(sets, inter));
+
+        let res = match len {
+            Ok(len) => Frame::Integer(len as i64),
+            Err(e) => Frame::Error(e.to_string()),
+        };
+
+        conn.write_frame(&res).await?;
+        Ok(())
+    }
+
+    fn into_frame(self, name: &str) -> Result<Frame, FrameError> {
+        let mut frame = Frame::Array(vec![]);
+
+        frame.push_bulk(Bytes::from(name.as_bytes().to_owned()))?;
+        frame.push_bulk(Bytes::from(self.dest.into_bytes()))?;
+        frame.push_int(self.keys.len() as i64)?;
+
+        for key in self.keys {
+            frame.push_bulk(Bytes::from(key.into_bytes()))?;
+        }
+
+        if let Some(weights) = self.weights {
+            frame.push_bulk(Bytes::from_static(b"WEIGHTS"))?;
+
+            for weight in weights {
+                frame.push_bulk(Bytes::from(weight.to_string().into_bytes()))?;
+            }
+        }
+
+        frame.push_bulk(Bytes::from_static(b"AGGREGATE"))?;
+        frame.push_bulk(Bytes::from(self.aggregate.as_str().as_bytes()))?;
+        Ok(frame)
+    }
+}
+
+impl ZUnionStore {
+    pub(crate) fn new(args: ZStoreArgs) -> Self {
+        Self(args)
+    }
+}
+
+impl ZInterStore {
+    pub(crate) fn new(args: ZStoreArgs) -> Self {
+        Self(args)
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for ZUnionStore {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        self.0.apply(false, db, conn).await
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl Execute for ZInterStore {
+    async fn execute(
+        self,
+        db: &Database,
+        conn: &mut Connection,
+        _: &mut ShutdownListener,
+    ) -> anyhow::Result<()> {
+        self.0.apply(true, db, conn).await
+    }
+}
+
+impl Command for ZUnionStore {
+    fn representation<'a>() -> &'a str {
+        "zunionstore"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self(ZStoreArgs::parse(parser, Self::representation())?))
+    }
+}
+
+impl Command for ZInterStore {
+    fn representation<'a>() -> &'a str {
+        "zinterstore"
+    }
+
+    fn parse_from_frame(parser: &mut Parse) -> anyhow::Result<Self> {
+        Ok(Self(ZStoreArgs::parse(parser, Self::representation())?))
+    }
+}
+
+impl TryInto<Frame> for ZUnionStore {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        self.0.into_frame(Self::representation())
+    }
+}
+
+impl TryInto<Frame> for ZInterStore {
+    type Error = FrameError;
+
+    fn try_into(self) -> Result<Frame, Self::Error> {
+        self.0.into_frame(Self::representation())
+    }
+}
@@ -1,13 +1,12 @@
-use std::io::{self, Cursor};
-
-use bytes::{Buf, BytesMut};
-use tokio::io::{AsyncReadExt, AsyncWriteExt, BufWriter};
+use bytes::BytesMut;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
+use tokio_util::codec::{Decoder, Encoder};
 
 use anyhow::anyhow;
 use thiserror::Error;
-use tracing::error;
 
+use crate::codec::{FrameCodec, Protocol, ProtocolLimits};
 use crate::error::DatabaseError;
 use crate::frame::{Frame, FrameError};
 
@@ -17,33 +16,77 @@ pub struct ConnectionError(pub anyhow::Error);
 
 #[derive(Debug)]
 pub(crate) struct Connection {
-    stream: BufWriter<TcpStream>,
+    stream: TcpStream,
     buffer: BytesMut,
+    /// Encoded replies waiting to be written to the socket.
+    write_buffer: BytesMut,
+    codec: FrameCodec,
+    name: Option<String>,
+    /// Set once the peer sent more than could be buffered while its requests were not read.
+    overflowed: bool,
 }
 
 impl Connection {
     pub(crate) fn new(socket: TcpStream) -> Self {
-        Self {
-            stream: BufWriter::new(socket),
-            buffer: BytesMut::with_capacity(4 * 1024),
-        }
+        Self::with_capacity(socket, 4 * 1024)
     }
 
     pub fn with_capacity(socket: TcpStream, buf_capacity: usize) -> Self {
         Self {
-            stream: BufWriter::new(socket),
+            stream: socket,
             buffer: BytesMut::with_capacity(buf_capacity),
+            write_buffer: BytesMut::with_capacity(buf_capacity),
+            codec: FrameCodec::default(),
+            name: None,
+            overflowed: false,
         }
     }
 
+    /// Creates a connection whose peer is held to the given protocol limits. Frames exceeding
+    /// them fail `read_frame`, after which the connection should be closed.
+    pub(crate) fn with_limits(socket: TcpStream, limits: ProtocolLimits) -> Self {
+        Self {
+            codec: FrameCodec::with_limits(limits),
+            ..Self::new(socket)
+        }
+    }
+
+    pub(crate) fn protocol(&self) -> Protocol {
+        self.codec.protocol()
+    }
+
+    pub(crate) fn set_protocol(&mut self, protocol: Protocol) {
+        self.codec.set_protocol(protocol);
+    }
+
+    pub(crate) fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub(crate) fn set_name(&mut self, name: String) {
+        self.name = Some(name);
+    }
+
     pub(crate) async fn read_frame(&mut self) -> Result<Option<Frame>, DatabaseError> {
+        if self.overflowed {
+            return Err(FrameError::LimitExceeded(
+                "too much data sent while the connection was blocked".to_string(),
+            )
+            .into());
+        }
+
         loop {
-            if let Some(frame) = self.parse_frame()? {
+            if let Some(frame) = self.codec.decode(&mut self.buffer)? {
                 return Ok(Some(frame));
             }
 
+            // bulk payloads are split off the buffer, so its spare capacity must be topped up
+            if self.buffer.capacity() - self.buffer.len() < 1024 {
+                self.buffer.reserve(4 * 1024);
+            }
+
             if self.stream.read_buf(&mut self.buffer).await? == 0 {
-                if self.buffer.is_empty() {
+                if self.buffer.is_empty() && !self.codec.has_partial_frame() {
                     return Ok(None);
                 } else {
                     // close the connection as it is stale
@@ -55,90 +98,33 @@ impl Connection {
         }
     }
 
-    fn parse_frame(&mut self) -> Result<Option<Frame>, DatabaseError> {
-        let mut buf = Cursor::new(&self.buffer[..]);
+    /// Waits for the peer to close the connection. Requests sent in the meantime are kept
+    /// for `read_frame`, up to the size of the largest request the limits allow. Past that,
+    /// the peer is considered gone and `read_frame` fails. Cancel safe.
+    pub(crate) async fn closed(&mut self) {
+        let limits = self.codec.limits();
+        let max_pending = limits.max_bulk_len.saturating_add(limits.max_line_len);
 
-        match Frame::validate(&mut buf) {
-            Ok(_) => {
-                let len = buf.position() as usize;
-                buf.set_position(0);
-                let frame = Frame::parse(&mut buf)?;
-                self.buffer.advance(len);
-                Ok(Some(frame))
+        loop {
+            if self.buffer.len() > max_pending {
+                self.overflowed = true;
+                return;
             }
-            Err(e) => Err(e.into()),
-        }
-    }
-
-    #[async_recursion::async_recursion]
-    pub(crate) async fn write_frame(&mut self, frame: &Frame) -> Result<(), DatabaseError> {
-        // recursive data structures?
-        // async recursion not natively supported in rust
-        match frame {
-            Frame::Array(f) => {
-                self.stream.write_u8(b'*').await?;
-
-                // write num elements in Frame
-                self.write_decimal(f.len() as u64).await?;
-
-                for sub_f in f.iter() {
-                    self.write_frame(sub_f).await?;
-                }
 
-                Ok(())
+            if self.buffer.capacity() - self.buffer.len() < 1024 {
+                self.buffer.reserve(4 * 1024);
             }
-            _ => self.write_value(frame).await,
-        }
-    }
 
-    async fn write_value(&mut self, frame: &Frame) -> Result<(), DatabaseError> {
-        match frame {
-            Frame::Simple(s) => {
-                self.stream.write_u8(b'+').await?;
-                self.stream.write_all(s.as_bytes()).await
-                // .map_err(|e| DatabaseError::from(e))
-            }
-            Frame::Error(e) => {
-                self.stream.write_u8(b'-').await?;
-                self.stream.write_all(e.as_bytes()).await
-                // .map_err(|e| DatabaseError::from(e))
-            }
-            Frame::Integer(i) => {
-                self.write_decimal(*i).await
-                // .map_err(|e| DatabaseError::from(e))
+            match self.stream.read_buf(&mut self.buffer).await {
+                Ok(0) | Err(_) => return,
+                Ok(_) => {}
             }
-            Frame::Bulk(bs) => {
-                self.stream.write_u8(b'$').await?;
-                self.write_decimal(bs.len() as u64).await?;
-                self.stream.write_all(b"\r\n").await
-                // .map_err(|e| DatabaseError::from(e))
-            }
-            Frame::Null => {
-                self.stream.write_u8(b'_').await
-                // .map_err(|e| DatabaseError::from(e))
-            }
-            f => {
-                error!(frame = %f, "unmatched frame");
-                return Err(
-                    FrameError::ProtocolError("Unexpected frame encountered".to_string()).into(),
-                );
-            }
-        };
-
-        self.stream.write_all(b"\r\n").await;
-        Ok(())
+        }
     }
 
-    async fn write_decimal(&mut self, value: u64) -> Result<(), io::Error> {
-        use std::io::Write;
-
-        let mut buf = [0u8; 8];
-        let mut buf = Cursor::new(&mut buf[..]);
-        write!(&mut buf, "{}", value)?;
-
-        let pos = buf.position() as usize;
-        self.stream.write_all(&buf.get_ref()[..pos]).await?;
-
+    pub(crate) async fn write_frame(&mut self, frame: &Frame) -> Result<(), DatabaseError> {
+        self.codec.encode(frame, &mut self.write_buffer)?;
+        self.stream.write_all_buf(&mut self.write_buffer).await?;
         Ok(())
     }
 }
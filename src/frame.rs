@@ -1,9 +1,6 @@
-use std::{
-    fmt::{Debug, Display},
-    io::Cursor,
-};
+use std::fmt::{Debug, Display};
 
-use bytes::{Buf, Bytes};
+use bytes::Bytes;
 use thiserror::Error;
 use tracing::warn;
 
@@ -17,22 +14,43 @@ pub enum FrameError {
 
     #[error("Protocol Error: {0}")]
     ProtocolError(String),
+
+    /// The peer sent a frame exceeding one of the configured [`ProtocolLimits`].
+    ///
+    /// [`ProtocolLimits`]: crate::codec::ProtocolLimits
+    #[error("Limit Exceeded: {0}")]
+    LimitExceeded(String),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Frame {
     Simple(String),
     Error(String),
-    Integer(u64),
+    Integer(i64),
     Bulk(Bytes),
     Null,
+    /// A null standing for a missing array. RESP3 has a single null, but RESP2 encodes this
+    /// one as `*-1` rather than `$-1`.
+    NullArray,
     Array(Vec<Frame>),
+    Boolean(bool),
+    Double(f64),
+    /// Integers outside of the 64-bit range, kept in their decimal representation.
+    BigNumber(String),
+    BulkError(Bytes),
+    /// A bulk string paired with its three character format hint (e.g. `txt` or `mkd`).
+    Verbatim(String, Bytes),
+    Map(Vec<(Frame, Frame)>),
+    Set(Vec<Frame>),
+    /// Out-of-band metadata followed by the frame it annotates.
+    Attribute(Vec<(Frame, Frame)>, Box<Frame>),
+    Push(Vec<Frame>),
 }
 
 impl Frame {
     pub(crate) fn push_bulk(&mut self, bytes: Bytes) -> Result<(), FrameError> {
         match self {
-            Frame::Array(v) => {
+            Frame::Array(v) | Frame::Set(v) | Frame::Push(v) => {
                 v.push(Frame::Bulk(bytes));
                 Ok(())
             }
@@ -42,9 +60,9 @@ impl Frame {
         }
     }
 
-    pub(crate) fn push_int(&mut self, value: u64) -> Result<(), FrameError> {
+    pub(crate) fn push_int(&mut self, value: i64) -> Result<(), FrameError> {
         match self {
-            Frame::Array(v) => {
+            Frame::Array(v) | Frame::Set(v) | Frame::Push(v) => {
                 v.push(Frame::Integer(value));
                 Ok(())
             }
@@ -54,120 +72,26 @@ impl Frame {
         }
     }
 
-    pub(crate) fn validate(cursor: &mut Cursor<&[u8]>) -> Result<(), FrameError> {
-        match get_next(cursor)? {
-            b'+' => {
-                // simple string
-                get_line(cursor)?;
-                Ok(())
-            }
-            b'-' => {
-                // simple error
-                get_line(cursor)?.to_vec();
-                Ok(())
-            }
-            b':' => {
-                // integer
-                get_decimal(cursor)?;
-                Ok(())
-            }
-            b'_' => {
-                // null (RESP3 encoding)
-                let l = get_line(cursor)?;
-                if l == b"_\r\n" {
-                    Ok(())
-                } else {
-                    Err(FrameError::ProtocolError(format!(
-                        "Invalid frame type. Bytes: {l:?}"
-                    )))
-                }
-            }
-            b'$' => {
-                //bulk string
-                let len = get_decimal(cursor)?;
-                advance(cursor, (len as usize) + 2)
-            }
-            b'*' => {
-                // simple array
-                let len = get_decimal(cursor)?;
-
-                for _ in 0..(len as usize) {
-                    Frame::validate(cursor)?;
-                }
-                Ok(())
-            }
-            unsupported => Err(FrameError::ProtocolError(format!(
-                "Invalid frame type. Byte: {unsupported:?}"
-            ))),
-        }
-    }
-
-    pub(crate) fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Frame, FrameError> {
-        match get_next(cursor)? {
-            b'+' => {
-                let l = get_line(cursor)?.to_vec();
-                String::from_utf8(l)
-                    .map_err(|e| {
-                        warn!(error = %e, "converting bytes to string failed");
-                        FrameError::ParsingError(format!("Byte to string coversion failed.",))
-                    })
-                    .map(|v| Frame::Simple(v))
-            }
-            b'-' => {
-                let l = get_line(cursor)?.to_vec();
-                String::from_utf8(l)
-                    .map_err(|e| {
-                        warn!(error = %e, "converting bytes to string failed");
-                        FrameError::ParsingError(format!("Byte to string coversion failed."))
-                    })
-                    .map(|v| Frame::Error(v))
-            }
-            b':' => {
-                let d = get_decimal(cursor)?;
-                Ok(Frame::Integer(d))
-            }
-            b'_' => {
-                let l = get_line(cursor)?;
-                if l == b"_\r\n" {
-                    advance(cursor, 3)?;
-                    Ok(Frame::Null)
-                } else {
-                    Err(FrameError::ProtocolError(format!(
-                        "Invalid frame type. Bytes: {l:?}"
-                    )))
-                }
-            }
-            b'$' => {
-                // read bulk string
-                let len = get_decimal(cursor)? as usize;
-                let n = len + 2;
-
-                if cursor.remaining() < n {
-                    Err(FrameError::ParsingError(
-                        "Bulk string length exceeds buffer".to_string(),
-                    ))
-                } else if cursor.chunk()[len..n] != b"\r\n"[..] {
-                    Err(FrameError::ParsingError("Invalid terminator.".to_string()))
-                } else {
-                    let bulk = Bytes::copy_from_slice(&cursor.chunk()[..len]);
-                    advance(cursor, n)?;
-                    Ok(Frame::Bulk(bulk))
-                }
-            }
-            b'*' => {
-                let len = get_decimal(cursor)?;
-                let mut v = Vec::with_capacity(len as usize);
-                for _ in 0..len {
-                    v.push(Frame::parse(cursor)?);
-                }
-
-                Ok(Frame::Array(v))
-            }
-            unsupported => Err(FrameError::ProtocolError(format!(
-                "Invalid frame type. Byte {}",
-                unsupported
-            ))),
-        }
+    /// Whether `byte` starts a RESP frame. Requests starting with any other byte are treated
+    /// as inline commands.
+    pub(crate) fn is_type_byte(byte: u8) -> bool {
+        matches!(
+            byte,
+            b'+' | b'-'
+                | b':'
+                | b'_'
+                | b'#'
+                | b','
+                | b'('
+                | b'$'
+                | b'!'
+                | b'='
+                | b'*'
+                | b'~'
+                | b'>'
+                | b'%'
+                | b'|'
+        )
     }
 }
 
@@ -177,13 +101,15 @@ impl PartialEq<&str> for Frame {
             Frame::Simple(v) => v.eq(other),
             Frame::Bulk(v) => v.eq(other),
             Frame::Error(v) => v.eq(other),
+            Frame::BulkError(v) => v.eq(other),
+            Frame::Verbatim(_, v) => v.eq(other),
             _ => false,
         }
     }
 }
 
-impl PartialEq<u64> for Frame {
-    fn eq(&self, other: &u64) -> bool {
+impl PartialEq<i64> for Frame {
+    fn eq(&self, other: &i64) -> bool {
         match self {
             Frame::Integer(v) => *v == *other,
             _ => false,
@@ -197,74 +123,30 @@ impl Display for Frame {
             Frame::Simple(s) => Display::fmt(s, f),
             Frame::Error(e) => Display::fmt(e, f),
             Frame::Integer(i) => Display::fmt(i, f),
-            Frame::Bulk(b) => match std::str::from_utf8(b) {
-                Ok(s) => Display::fmt(&s, f),
-                Err(e) => {
-                    warn!(error = %e, "bulk string to utf8 failed");
-                    write!(f, "{:?}", e)
+            Frame::Bulk(b) | Frame::BulkError(b) | Frame::Verbatim(_, b) => {
+                match std::str::from_utf8(b) {
+                    Ok(s) => Display::fmt(&s, f),
+                    Err(e) => {
+                        warn!(error = %e, "bulk string to utf8 failed");
+                        write!(f, "{:?}", e)
+                    }
                 }
-            },
-            Frame::Null => Display::fmt("(null)", f),
-            Frame::Array(v) => v.iter().try_for_each(|frame| {
+            }
+            Frame::Null | Frame::NullArray => Display::fmt("(null)", f),
+            Frame::Boolean(b) => Display::fmt(b, f),
+            Frame::Double(d) => Display::fmt(d, f),
+            Frame::BigNumber(n) => Display::fmt(n, f),
+            Frame::Array(v) | Frame::Set(v) | Frame::Push(v) => v.iter().try_for_each(|frame| {
                 Display::fmt(frame, f)?;
                 write!(f, " ")
             }),
-            // For future data types
-            _ => todo!(),
-        }
-    }
-}
-
-fn advance(source: &mut Cursor<&[u8]>, n: usize) -> Result<(), FrameError> {
-    if source.remaining() < n {
-        Err(FrameError::ParsingError(format!(
-            "Advance failed. Advance of {n} bytes while {} bytes remaining.",
-            source.remaining()
-        )))
-    } else {
-        source.advance(n);
-        Ok(())
-    }
-}
-
-fn peek_next(source: &mut Cursor<&[u8]>) -> Result<u8, FrameError> {
-    if !source.has_remaining() {
-        Err(FrameError::ParsingError(
-            "Peek failed. Buffer exhausted.".to_string(),
-        ))
-    } else {
-        Ok(source.chunk()[0])
-    }
-}
-
-fn get_next(source: &mut Cursor<&[u8]>) -> Result<u8, FrameError> {
-    if !source.has_remaining() {
-        Err(FrameError::ParsingError(
-            "Get failed. Buffer exhausted.".to_string(),
-        ))
-    } else {
-        Ok(source.get_u8())
-    }
-}
-
-fn get_line<'a>(source: &mut Cursor<&'a [u8]>) -> Result<&'a [u8], FrameError> {
-    let start = source.position() as usize;
-    let end = source.get_ref().len() - 1;
-
-    for i in start..end {
-        if source.get_ref()[i] == b'\r' && source.get_ref()[i + 1] == b'\n' {
-            source.set_position((i + 2) as u64);
-            return Ok(&source.get_ref()[start..i]);
+            Frame::Map(m) => m.iter().try_for_each(|(k, v)| {
+                Display::fmt(k, f)?;
+                write!(f, ": ")?;
+                Display::fmt(v, f)?;
+                write!(f, " ")
+            }),
+            Frame::Attribute(_, frame) => Display::fmt(frame, f),
         }
     }
-
-    Err(FrameError::ParsingError(
-        "Get line failed. Buffer exhausted.".to_string(),
-    ))
-}
-
-fn get_decimal(source: &mut Cursor<&[u8]>) -> Result<u64, FrameError> {
-    let l = get_line(source)?;
-    atoi::atoi::<u64>(l)
-        .ok_or_else(|| FrameError::ProtocolError("Invalid frame format".to_string()))
 }
@@ -1,5 +1,6 @@
 pub(crate) mod error;
 
+pub(crate) mod codec;
 pub(crate) mod commands;
 pub(crate) mod connection;
 pub(crate) mod frame;
@@ -14,5 +15,47 @@ pub(crate) mod server;
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "server")]
 #[tokio::main]
-async fn main() {}
+async fn main() -> std::process::ExitCode {
+    use std::process::ExitCode;
+
+    use server::config::{Config, USAGE};
+    use tracing::error;
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if args.iter().any(|a| a == "-h" || a == "--help") {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+
+    if args.iter().any(|a| a == "-V" || a == "--version") {
+        println!("insomnia-server {}", env!("CARGO_PKG_VERSION"));
+        return ExitCode::SUCCESS;
+    }
+
+    // logging is configured by the config itself, so failures are reported on stderr
+    let config = match Config::from_args(args) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{e:#}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // the level was validated along with the rest of the config
+    let level = config.level().unwrap_or(tracing::Level::INFO);
+    tracing_subscriber::fmt().with_max_level(level).init();
+
+    match server::run(config, server::shutdown_signal()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            error!(error = %e, "server failed");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(not(feature = "server"))]
+fn main() {}
@@ -72,22 +72,39 @@ impl Parse {
         }
     }
 
-    pub(crate) fn next_int(&mut self) -> Result<u64, ParseError> {
-        let err_msg = "Invalid integer.";
-
+    pub(crate) fn next_int(&mut self) -> Result<i64, ParseError> {
         match self.next()? {
             Frame::Integer(i) => Ok(i),
-            Frame::Simple(s) => atoi::atoi::<u64>(s.as_bytes())
-                .ok_or_else(|| ParseError::ProtocolError(err_msg.to_string())),
-            Frame::Bulk(bs) => {
-                atoi::atoi::<u64>(&bs).ok_or_else(|| ParseError::ProtocolError(err_msg.to_string()))
-            }
+            Frame::Simple(s) => parse_int(s.as_bytes()),
+            Frame::Bulk(bs) => parse_int(&bs),
             frame => Err(ParseError::ProtocolError(format!(
                 "Expected a integer, simple, or bulk frame containing an integer. Found: {frame}"
             ))),
         }
     }
 
+    /// Consumes the remaining parts as strings.
+    pub(crate) fn remaining_strings(&mut self) -> Result<Vec<String>, ParseError> {
+        let mut strings = vec![];
+
+        while !self.parts.as_slice().is_empty() {
+            strings.push(self.next_string()?);
+        }
+
+        Ok(strings)
+    }
+
+    /// Consumes the remaining parts as bytes.
+    pub(crate) fn remaining_bytes(&mut self) -> Result<Vec<Bytes>, ParseError> {
+        let mut parts = vec![];
+
+        while !self.parts.as_slice().is_empty() {
+            parts.push(self.next_bytes()?);
+        }
+
+        Ok(parts)
+    }
+
     pub(crate) fn finish(&mut self) -> Result<(), ParseError> {
         if self.parts.next().is_none() {
             Ok(())
@@ -98,3 +115,15 @@ impl Parse {
         }
     }
 }
+
+/// Parses the whole buffer as a signed integer, rejecting trailing bytes and overflow.
+fn parse_int(buf: &[u8]) -> Result<i64, ParseError> {
+    use atoi::FromRadix10SignedChecked;
+
+    match i64::from_radix_10_signed_checked(buf) {
+        (Some(i), used) if used == buf.len() && buf.iter().any(u8::is_ascii_digit) => Ok(i),
+        _ => Err(ParseError::ProtocolError(
+            "value is not an integer or out of range".to_string(),
+        )),
+    }
+}
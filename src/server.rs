@@ -1,6 +1,107 @@
+use std::future::Future;
+
+use anyhow::anyhow;
+use tokio::{
+    net::TcpListener,
+    signal,
+    sync::{broadcast, mpsc},
+};
+use tracing::{error, info};
+
+use crate::{
+    connection::ConnectionError,
+    error::{DatabaseError, StartupError},
+};
+
+use self::{config::Config, database::database_guard::DatabaseGuard, listener::Listener};
+
+pub(crate) mod config;
 pub(crate) mod database;
+pub(crate) mod glob;
 pub(crate) mod jobs;
 pub(crate) mod shutdown_listener;
 
-mod handler;
+pub(crate) mod handler;
 mod listener;
+
+/// Binds the configured address and serves clients until `shutdown` completes or the
+/// listener fails.
+pub(crate) async fn run(config: Config, shutdown: impl Future) -> Result<(), DatabaseError> {
+    let addr = format!("{}:{}", config.bind, config.port);
+
+    let tcp_listener = TcpListener::bind(&addr).await.map_err(|e| {
+        DatabaseError::StartupError(StartupError(anyhow!("Failed to bind {addr}: {e}")))
+    })?;
+
+    info!(%addr, "listening");
+
+    serve(tcp_listener, &config, shutdown).await
+}
+
+/// Serves clients on an already bound listener.
+///
+/// Once `shutdown` completes, no more connections are accepted and every connection is told
+/// to close after its in-flight command. The background tasks are halted once all of them
+/// have closed, or once the configured shutdown timeout has passed.
+pub(crate) async fn serve(
+    tcp_listener: TcpListener,
+    config: &Config,
+    shutdown: impl Future,
+) -> Result<(), DatabaseError> {
+    let (shutdown_notifier, _) = broadcast::channel(1);
+    let (shutdown_complete_tx, shutdown_complete_rx) = mpsc::channel(1);
+
+    let mut listener = Listener::new(
+        DatabaseGuard::new(config),
+        tcp_listener,
+        config.max_connections,
+        config.protocol_limits(),
+        shutdown_notifier,
+        shutdown_complete_tx,
+    );
+
+    let res = tokio::select! {
+        res = listener.run() => res.map_err(|e| ConnectionError(e).into()),
+        _ = shutdown => {
+            info!("shutting down");
+            Ok(())
+        }
+    };
+
+    listener
+        .shutdown(shutdown_complete_rx, config.shutdown_timeout())
+        .await;
+
+    res
+}
+
+/// Completes when the process is asked to terminate, by ctrl-c or, on unix, by SIGTERM.
+pub(crate) async fn shutdown_signal() {
+    let interrupt = async {
+        if let Err(e) = signal::ctrl_c().await {
+            error!(error = %e, "failed to listen for ctrl-c");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match signal::unix::signal(signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                error!(error = %e, "failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = interrupt => {},
+        _ = terminate => {},
+    }
+}
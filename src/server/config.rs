@@ -0,0 +1,221 @@
+use std::{fs, path::Path, str::FromStr, time::Duration};
+
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+use tracing::Level;
+
+use crate::{codec::ProtocolLimits, error::StartupError};
+
+pub(crate) const USAGE: &str = "\
+Usage: insomnia-server [--config <path>] [--<option> <value>]...
+
+Options may also be given as --<option>=<value>. Any option of the config file can be set
+from the command line, where it takes precedence over the file.
+
+  --config <path>             TOML (*.toml) or redis.conf-style config file
+  --bind <address>            address to listen on [default: 127.0.0.1]
+  --port <port>               port to listen on [default: 6379]
+  --max-connections <n>       maximum number of concurrent clients [default: 10000]
+  --log-level <level>         trace, debug, info, warn or error [default: info]
+  --proto-max-bulk-len <n>    maximum bulk string length in bytes [default: 536870912]
+  --shutdown-timeout <secs>   how long to wait for clients to finish on shutdown [default: 10]
+  -h, --help                  print this message
+  -V, --version               print the version";
+
+/// Server settings, resolved from defaults, an optional config file and command line flags,
+/// in increasing order of precedence.
+///
+/// Keys are spelled in kebab-case. The redis names of the common settings (`maxclients`,
+/// `loglevel`) are accepted too, see [`normalize_key`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub(crate) struct Config {
+    pub(crate) bind: String,
+    pub(crate) port: u16,
+    pub(crate) max_connections: usize,
+    pub(crate) log_level: String,
+    pub(crate) proto_max_bulk_len: usize,
+    pub(crate) proto_max_multibulk_len: usize,
+    pub(crate) proto_max_nesting_depth: usize,
+    pub(crate) proto_max_inline_len: usize,
+    /// Seconds to wait on shutdown for connections to drain before exiting regardless.
+    pub(crate) shutdown_timeout: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let limits = ProtocolLimits::default();
+
+        Self {
+            bind: "127.0.0.1".to_string(),
+            port: 6379,
+            max_connections: 10_000,
+            log_level: "info".to_string(),
+            proto_max_bulk_len: limits.max_bulk_len,
+            proto_max_multibulk_len: limits.max_elements,
+            proto_max_nesting_depth: limits.max_depth,
+            proto_max_inline_len: limits.max_line_len,
+            shutdown_timeout: 10,
+        }
+    }
+}
+
+impl Config {
+    /// Builds the configuration from command line arguments, excluding the program name.
+    pub(crate) fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, StartupError> {
+        let mut file = toml::Table::new();
+        let mut overrides = toml::Table::new();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let Some(flag) = arg.strip_prefix("--") else {
+                return Err(StartupError(anyhow!(
+                    "Unexpected argument '{arg}'.\n\n{USAGE}"
+                )));
+            };
+
+            let (key, value) = match flag.split_once('=') {
+                Some((key, value)) => (key.to_string(), value.to_string()),
+                None => {
+                    let value = args.next().ok_or_else(|| {
+                        StartupError(anyhow!("Missing value for '--{flag}'.\n\n{USAGE}"))
+                    })?;
+                    (flag.to_string(), value)
+                }
+            };
+
+            if key == "config" {
+                file = load_file(Path::new(&value))?;
+            } else {
+                let key = normalize_key(&key);
+                let value = typed_value(&key, &value);
+                overrides.insert(key, value);
+            }
+        }
+
+        file.extend(overrides);
+        Self::from_table(file)
+    }
+
+    fn from_table(table: toml::Table) -> Result<Self, StartupError> {
+        let config: Config = toml::Value::Table(table)
+            .try_into()
+            .map_err(|e| StartupError(anyhow!("Invalid configuration: {e}")))?;
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), StartupError> {
+        let counts = [
+            ("max-connections", self.max_connections),
+            ("proto-max-bulk-len", self.proto_max_bulk_len),
+            ("proto-max-multibulk-len", self.proto_max_multibulk_len),
+            ("proto-max-nesting-depth", self.proto_max_nesting_depth),
+            ("proto-max-inline-len", self.proto_max_inline_len),
+        ];
+
+        // a limit of zero would have every request rejected
+        if let Some((name, _)) = counts.iter().find(|(_, count)| *count == 0) {
+            return Err(StartupError(anyhow!("{name} must be greater than 0")));
+        }
+
+        self.level()?;
+        Ok(())
+    }
+
+    /// The maximum level of events to log. The redis log levels are mapped to their closest
+    /// `tracing` counterpart.
+    pub(crate) fn level(&self) -> Result<Level, StartupError> {
+        match self.log_level.to_lowercase().as_str() {
+            "verbose" => Ok(Level::DEBUG),
+            "notice" => Ok(Level::INFO),
+            "warning" => Ok(Level::WARN),
+            level => Level::from_str(level)
+                .map_err(|_| StartupError(anyhow!("Invalid log level '{}'", self.log_level))),
+        }
+    }
+
+    pub(crate) fn shutdown_timeout(&self) -> Duration {
+        Duration::from_secs(self.shutdown_timeout)
+    }
+
+    pub(crate) fn protocol_limits(&self) -> ProtocolLimits {
+        ProtocolLimits {
+            max_bulk_len: self.proto_max_bulk_len,
+            max_elements: self.proto_max_multibulk_len,
+            max_depth: self.proto_max_nesting_depth,
+            max_line_len: self.proto_max_inline_len,
+        }
+    }
+}
+
+/// Reads a TOML file if its extension is `.toml` and a redis.conf-style file of
+/// `<key> <value>` lines otherwise.
+fn load_file(path: &Path) -> Result<toml::Table, StartupError> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file {}", path.display()))
+        .map_err(StartupError)?;
+
+    if path.extension().is_some_and(|ext| ext == "toml") {
+        return contents
+            .parse::<toml::Table>()
+            .map(|table| {
+                table
+                    .into_iter()
+                    .map(|(k, v)| (normalize_key(&k), v))
+                    .collect()
+            })
+            .with_context(|| format!("Failed to parse config file {}", path.display()))
+            .map_err(StartupError);
+    }
+
+    parse_conf(&contents)
+        .with_context(|| format!("Failed to parse config file {}", path.display()))
+        .map_err(StartupError)
+}
+
+/// Parses redis.conf-style directives. Blank lines and lines starting with `#` are ignored.
+pub(crate) fn parse_conf(contents: &str) -> anyhow::Result<toml::Table> {
+    let mut table = toml::Table::new();
+
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| anyhow!("line {}: expected '<key> <value>'", i + 1))?;
+
+        let key = normalize_key(key);
+        let value = typed_value(&key, value.trim().trim_matches('"'));
+        table.insert(key, value);
+    }
+
+    Ok(table)
+}
+
+/// Maps a setting to its kebab-case name, so the same setting given under different names
+/// in the config file and on the command line is recognised as such.
+fn normalize_key(key: &str) -> String {
+    match key.to_lowercase().replace('_', "-").as_str() {
+        "maxclients" => "max-connections".to_string(),
+        "loglevel" => "log-level".to_string(),
+        key => key.to_string(),
+    }
+}
+
+/// Values given on the command line or in redis.conf files are untyped, so they are given the
+/// type of the setting they are for, as found in the defaults. Values that do not fit are
+/// kept as strings and rejected along with the rest of the config.
+fn typed_value(key: &str, value: &str) -> toml::Value {
+    let defaults = toml::Value::try_from(Config::default()).expect("the defaults serialize");
+
+    match (defaults.get(key), value.parse::<i64>()) {
+        (Some(toml::Value::Integer(_)), Ok(i)) => toml::Value::Integer(i),
+        _ => toml::Value::String(value.to_string()),
+    }
+}
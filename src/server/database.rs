@@ -1,6 +1,11 @@
+pub(crate) mod blocking;
 pub(crate) mod database;
 pub(crate) mod database_guard;
 
-mod entry;
+pub(crate) mod entry;
+pub(crate) mod expiry;
+pub(crate) mod hash;
 pub(crate) mod shared_state;
+pub(crate) mod sorted_set;
 mod state;
+pub(crate) mod stream;
@@ -0,0 +1,188 @@
+use super::{
+    database::ListEnd,
+    entry::WrongType,
+    shared_state::SharedState,
+    stream::{GroupRead, StreamEntry, StreamId},
+};
+
+use bytes::Bytes;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+};
+use tokio::sync::oneshot;
+
+/// What a blocked client is served with: the key it popped from and what it popped.
+pub(crate) type Served<T> = Result<(String, T), WrongType>;
+
+/// A member popped from a sorted set, along with its score.
+pub(crate) type Scored = (Bytes, f64);
+
+/// A client blocked until one of the keys it waits on has an element for it.
+#[derive(Debug)]
+pub(super) struct Waiter {
+    pub(super) keys: Vec<String>,
+    pub(super) pop: Pop,
+}
+
+/// What a blocked client pops, and where it is sent.
+#[derive(Debug)]
+pub(super) enum Pop {
+    List {
+        from: ListEnd,
+        /// The list the popped element is pushed to, for `BLMOVE`.
+        to: Option<(String, ListEnd)>,
+        sender: oneshot::Sender<Served<Bytes>>,
+    },
+    /// Pops the member with the lowest score, or the highest if `max` is set, along with
+    /// its score.
+    SortedSet {
+        max: bool,
+        sender: oneshot::Sender<Served<Scored>>,
+    },
+    /// Reads at most `count` entries of a stream. Unlike elements of lists, entries are only
+    /// consumed by the clients reading for a consumer group.
+    Stream {
+        read: StreamRead,
+        count: Option<usize>,
+        sender: oneshot::Sender<Served<Vec<StreamEntry>>>,
+    },
+}
+
+/// What a client blocked on streams reads.
+#[derive(Debug)]
+pub(super) enum StreamRead {
+    /// The entries added after the ID given for each key.
+    After(HashMap<String, StreamId>),
+    /// The entries never delivered to a consumer group.
+    Group(GroupRead),
+}
+
+impl Pop {
+    /// The type of the values the client pops from, as reported by `TYPE`.
+    pub(super) fn type_name(&self) -> &'static str {
+        match self {
+            Pop::List { .. } => "list",
+            Pop::SortedSet { .. } => "zset",
+            Pop::Stream { .. } => "stream",
+        }
+    }
+}
+
+/// The clients blocked on lists, sorted sets and streams. Every key has a queue of the clients
+/// waiting on it, in the order they blocked, so that they are served first come first
+/// served.
+#[derive(Debug, Default)]
+pub(super) struct Blocked {
+    next_id: u64,
+    queues: HashMap<String, VecDeque<u64>>,
+    waiters: HashMap<u64, Waiter>,
+}
+
+impl Blocked {
+    /// Queues the waiter on each of its keys.
+    pub(super) fn block(&mut self, waiter: Waiter) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        for key in &waiter.keys {
+            self.queues.entry(key.clone()).or_default().push_back(id);
+        }
+
+        self.waiters.insert(id, waiter);
+        id
+    }
+
+    /// Removes the waiter from every queue. Returns it if it was still waiting.
+    pub(super) fn unblock(&mut self, id: u64) -> Option<Waiter> {
+        let waiter = self.waiters.remove(&id)?;
+
+        for key in &waiter.keys {
+            let Some(queue) = self.queues.get_mut(key) else {
+                continue;
+            };
+
+            queue.retain(|other| *other != id);
+
+            if queue.is_empty() {
+                self.queues.remove(key);
+            }
+        }
+
+        Some(waiter)
+    }
+
+    /// The first client waiting on `key` for a value of the type named `type_name`.
+    pub(super) fn front(&self, key: &str, type_name: &str) -> Option<u64> {
+        self.queued(key, type_name).next()
+    }
+
+    /// The clients waiting on `key` for a value of the type named `type_name`, in the order
+    /// they blocked.
+    pub(super) fn queued<'a>(
+        &'a self,
+        key: &str,
+        type_name: &'a str,
+    ) -> impl Iterator<Item = u64> + 'a {
+        self.queues
+            .get(key)
+            .into_iter()
+            .flatten()
+            .filter(move |id| self.waiters[id].pop.type_name() == type_name)
+            .copied()
+    }
+
+    pub(super) fn get(&self, id: u64) -> Option<&Waiter> {
+        self.waiters.get(&id)
+    }
+}
+
+/// A client's place in the queues of the keys it waits on. The client leaves the queues when
+/// it is dropped.
+#[derive(Debug)]
+pub(crate) struct BlockedPop<T> {
+    id: u64,
+    receiver: oneshot::Receiver<Served<T>>,
+    shared_state: Arc<SharedState>,
+}
+
+impl<T> BlockedPop<T> {
+    pub(super) fn new(
+        id: u64,
+        receiver: oneshot::Receiver<Served<T>>,
+        shared_state: Arc<SharedState>,
+    ) -> Self {
+        Self {
+            id,
+            receiver,
+            shared_state,
+        }
+    }
+
+    /// Waits until the client is served. Cancel safe.
+    pub(crate) async fn served(&mut self) -> Served<T> {
+        match (&mut self.receiver).await {
+            Ok(served) => served,
+            // the sender is never dropped unused while the client is queued
+            Err(_) => std::future::pending().await,
+        }
+    }
+
+    /// Stops waiting. Returns what the client was served with, if it was served before it
+    /// could leave the queues.
+    pub(crate) fn cancel(mut self) -> Option<Served<T>> {
+        self.leave();
+        self.receiver.try_recv().ok()
+    }
+
+    fn leave(&mut self) {
+        let mut state = self.shared_state.state.lock().unwrap();
+        state.blocked.unblock(self.id);
+    }
+}
+
+impl<T> Drop for BlockedPop<T> {
+    fn drop(&mut self) {
+        self.leave();
+    }
+}
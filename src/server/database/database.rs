@@ -1,16 +1,79 @@
-use super::{entry, shared_state::SharedState, state::State};
-use crate::server::jobs::job_queue::{self, JobQueue};
+use super::{
+    blocking::{BlockedPop, Pop, Scored, StreamRead, Waiter},
+    entry::{self, Buffer, Collection, Value, WrongType},
+    expiry::{self, ExpiryStats},
+    shared_state::SharedState,
+    sorted_set::SortedSet,
+    state::State,
+    stream::{GroupRead, Stream, StreamEntry, StreamId},
+};
+use crate::server::{
+    config::Config,
+    glob,
+    jobs::job_queue::{self, JobQueue},
+};
 
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use std::{
-    collections::{BTreeMap, BTreeSet, HashMap},
-    sync::{Arc, Mutex},
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
 };
+use thiserror::Error;
 use tokio::{
-    sync::{broadcast, Notify},
-    time::{Duration, Instant},
+    sync::{broadcast, oneshot, Notify},
+    time::Instant,
 };
-use tracing::instrument;
+
+/// The expiration of a value stored by [`Database::set`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum SetExpiration {
+    /// The value never expires.
+    Persist,
+    /// The value takes over the expiration of the value it replaces.
+    Keep,
+    At(Instant),
+}
+
+/// An end of a list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ListEnd {
+    Left,
+    Right,
+}
+
+impl ListEnd {
+    pub(crate) fn pop(&self, list: &mut VecDeque<Bytes>) -> Option<Bytes> {
+        match self {
+            ListEnd::Left => list.pop_front(),
+            ListEnd::Right => list.pop_back(),
+        }
+    }
+
+    pub(crate) fn push(&self, list: &mut VecDeque<Bytes>, value: Bytes) {
+        match self {
+            ListEnd::Left => list.push_front(value),
+            ListEnd::Right => list.push_back(value),
+        }
+    }
+}
+
+/// The entries read from each stream, or the caller's place in the queues of the streams
+/// until there are entries to read.
+pub(crate) type StreamReads = Result<Vec<(String, Vec<StreamEntry>)>, BlockedPop<Vec<StreamEntry>>>;
+
+/// Raised by reads of streams for a consumer group.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub(crate) enum GroupError {
+    #[error(transparent)]
+    WrongType(#[from] WrongType),
+
+    /// The stream at the key, or its group, does not exist.
+    #[error("NOGROUP No such key '{0}' or consumer group '{1}' in XREADGROUP with GROUP option")]
+    NoGroup(String, String),
+}
 
 #[derive(Clone, Debug)]
 pub(crate) struct Database {
@@ -19,22 +82,20 @@ pub(crate) struct Database {
 }
 
 impl Database {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(config: &Config) -> Self {
         let shared_state = Arc::new(SharedState {
-            state: Mutex::new(State {
-                data: BTreeMap::new(),
-                pub_sub_map: HashMap::new(),
-                expiration_set: BTreeSet::new(),
-                active: true,
-            }),
+            state: Mutex::new(State::new()),
             expiration_task: Notify::new(),
             job_queue_task: Notify::new(),
+            connected_clients: AtomicUsize::new(0),
+            max_clients: config.max_connections,
+            max_string_len: config.proto_max_bulk_len,
         });
 
         let job_queue = Arc::new(tokio::sync::Mutex::new(JobQueue::new()));
 
         //background task for cleaning expired data
-        tokio::spawn(purge_expired(shared_state.clone()));
+        tokio::spawn(expiry::purge_expired(shared_state.clone()));
 
         // background task for job queue
         // tokio::spawn(async move {
@@ -50,24 +111,129 @@ impl Database {
         }
     }
 
-    pub(crate) fn get(&self, key: &str) -> Option<Bytes> {
-        let state = self.shared_state.state.lock().unwrap();
-        state.data.get(key).map(|v| v.buf.clone())
+    pub(crate) fn get(&self, key: &str) -> Result<Option<Bytes>, WrongType> {
+        let mut state = self.shared_state.state.lock().unwrap();
+
+        state
+            .get_entry(key)
+            .map(|v| v.value.as_string().map(Buffer::to_bytes))
+            .transpose()
     }
 
-    pub(crate) fn set(&self, key: String, val: Bytes, expiration: Option<Duration>) {
+    /// Looks up every key at once. Missing keys, and keys holding other types than strings,
+    /// are `None`.
+    pub(crate) fn mget(&self, keys: &[String]) -> Vec<Option<Bytes>> {
         let mut state = self.shared_state.state.lock().unwrap();
 
-        // should notify the task
-        let mut should_notify = false;
+        keys.iter()
+            .map(|key| {
+                let buf = state.get_entry(key)?.value.as_string().ok()?;
+                Some(buf.to_bytes())
+            })
+            .collect()
+    }
 
-        let expiration = expiration.map(|dur| {
-            let time = Instant::now() + dur;
+    /// Removes the key and returns its value.
+    pub(crate) fn get_del(&self, key: &str) -> Result<Option<Bytes>, WrongType> {
+        let mut state = self.shared_state.state.lock().unwrap();
 
-            should_notify = state.get_expired().map(|next| next > time).unwrap_or(true);
+        match state.get_entry(key) {
+            Some(entry) => entry.value.as_string()?,
+            None => return Ok(None),
+        };
 
-            time
-        });
+        let entry = state.remove_entry(key);
+        Ok(entry.and_then(|entry| match entry.value {
+            Value::String(buf) => Some(buf.into_bytes()),
+            _ => None,
+        }))
+    }
+
+    /// Returns the value of the key and changes its expiration. An expiration that has
+    /// already passed removes the key.
+    pub(crate) fn get_and_expire(
+        &self,
+        key: &str,
+        expiration: SetExpiration,
+    ) -> Result<Option<Bytes>, WrongType> {
+        let mut state = self.shared_state.state.lock().unwrap();
+
+        let buf = match state.get_entry(key) {
+            Some(entry) => entry.value.as_string()?.to_bytes(),
+            None => return Ok(None),
+        };
+
+        let earliest = match expiration {
+            SetExpiration::Keep => false,
+            SetExpiration::Persist => state.set_expiration(key, None),
+            SetExpiration::At(time) if time <= Instant::now() => {
+                state.remove_entry(key);
+                false
+            }
+            SetExpiration::At(time) => state.set_expiration(key, Some(time)),
+        };
+
+        drop(state);
+
+        self.notify_expiration_task(earliest);
+        Ok(Some(buf))
+    }
+
+    /// Stores every pair, without an expiration. If `only_new` is set, nothing is stored
+    /// unless all keys are missing. Returns whether the values were stored.
+    pub(crate) fn mset(&self, pairs: Vec<(String, Bytes)>, only_new: bool) -> bool {
+        let mut state = self.shared_state.state.lock().unwrap();
+
+        if only_new && pairs.iter().any(|(key, _)| state.contains_key(key)) {
+            return false;
+        }
+
+        for (key, val) in pairs {
+            let new_entry = entry::Entry::builder()
+                .with_bytes(val)
+                .build_consume()
+                .unwrap();
+
+            state.insert_entry(key, new_entry);
+        }
+
+        true
+    }
+
+    /// Stores `val` at `key`, provided `allow` accepts, being told whether the key exists.
+    /// Values of any type are replaced.
+    ///
+    /// Returns the previous value if `get` is set, and whether `val` was stored. With `get`,
+    /// nothing is stored if the key holds another type than a string.
+    pub(crate) fn set(
+        &self,
+        key: String,
+        val: Bytes,
+        expiration: SetExpiration,
+        get: bool,
+        allow: impl FnOnce(bool) -> bool,
+    ) -> Result<(Option<Bytes>, bool), WrongType> {
+        let mut state = self.shared_state.state.lock().unwrap();
+
+        let (exists, previous_expiration, previous) = match state.get_entry(&key) {
+            Some(entry) if get => (
+                true,
+                entry.expiration,
+                Some(entry.value.as_string()?.to_bytes()),
+            ),
+            Some(entry) => (true, entry.expiration, None),
+            None => (false, None, None),
+        };
+
+        if !allow(exists) {
+            return Ok((previous, false));
+        }
+
+        let expiration = match expiration {
+            SetExpiration::Persist => None,
+            SetExpiration::Keep => previous_expiration,
+            SetExpiration::At(time) => Some(time),
+        };
 
         let new_entry = entry::Entry::builder()
             .with_bytes(val)
@@ -75,25 +241,546 @@ impl Database {
             .build_consume()
             .unwrap();
 
-        // need to store, to ensure that if value is replaced the corresponding
-        // expiration data in the expiration_set is cleared.
-        let replaced = state.data.insert(key.clone(), new_entry);
+        let earliest = state.insert_entry(key, new_entry);
+        drop(state);
+
+        self.notify_expiration_task(earliest);
+        Ok((previous, true))
+    }
+
+    /// Replaces the string stored at `key` with the one computed by `update` from the
+    /// current value, or from `None` if the key does not exist. The key keeps its expiration.
+    ///
+    /// Nothing is stored if `update` fails.
+    pub(crate) fn update<T, E>(
+        &self,
+        key: &str,
+        update: impl FnOnce(Option<&[u8]>) -> Result<(Bytes, T), E>,
+    ) -> Result<Result<T, E>, WrongType> {
+        let mut state = self.shared_state.state.lock().unwrap();
 
-        if let Some(val) = replaced {
-            if let Some(expiration) = val.expiration {
-                state.expiration_set.remove(&(expiration, key.clone()));
+        let current = match state.get_entry(key) {
+            Some(entry) => Some(entry.value.as_string()?.as_slice()),
+            None => None,
+        };
+
+        let (buf, res) = match update(current) {
+            Ok(update) => update,
+            Err(e) => return Ok(Err(e)),
+        };
+
+        match state.data.get_mut(key) {
+            Some(entry) => entry.value = Value::String(Buffer::Shared(buf)),
+            None => {
+                let new_entry = entry::Entry::builder()
+                    .with_bytes(buf)
+                    .build_consume()
+                    .unwrap();
+
+                state.insert_entry(key.to_string(), new_entry);
             }
         }
 
-        if let Some(exp) = expiration {
-            state.expiration_set.insert((exp, key.clone()));
+        Ok(Ok(res))
+    }
+
+    /// Calls `read` with the string stored at `key`, without copying it.
+    pub(crate) fn read<T>(
+        &self,
+        key: &str,
+        read: impl FnOnce(&[u8]) -> T,
+    ) -> Result<Option<T>, WrongType> {
+        let mut state = self.shared_state.state.lock().unwrap();
+
+        match state.get_entry(key) {
+            Some(entry) => Ok(Some(read(entry.value.as_string()?.as_slice()))),
+            None => Ok(None),
+        }
+    }
+
+    /// Edits the string stored at `key` in place, keeping its expiration. A missing key is
+    /// edited as an empty string, and only stored if `edit` leaves it non-empty.
+    pub(crate) fn edit<T>(
+        &self,
+        key: &str,
+        edit: impl FnOnce(&mut BytesMut) -> T,
+    ) -> Result<T, WrongType> {
+        let mut state = self.shared_state.state.lock().unwrap();
+
+        state.evict_if_expired(key);
+
+        if let Some(entry) = state.data.get_mut(key) {
+            return Ok(edit(entry.value.as_string_mut()?.make_mut()));
+        }
+
+        let mut buf = BytesMut::new();
+        let res = edit(&mut buf);
+
+        if !buf.is_empty() {
+            let new_entry = entry::Entry::builder()
+                .with_bytes_mut(buf)
+                .build_consume()
+                .unwrap();
+
+            state.insert_entry(key.to_string(), new_entry);
+        }
+
+        Ok(res)
+    }
+
+    /// Stores the string `combine` computes from the strings at `keys` at `dest`, without an
+    /// expiration. `dest` is removed instead if the string is empty. Returns the length of
+    /// the string.
+    pub(crate) fn combine(
+        &self,
+        dest: &str,
+        keys: &[String],
+        combine: impl FnOnce(Vec<Option<&[u8]>>) -> Bytes,
+    ) -> Result<usize, WrongType> {
+        let mut state = self.shared_state.state.lock().unwrap();
+
+        for key in keys {
+            state.evict_if_expired(key);
         }
 
+        let values = keys
+            .iter()
+            .map(|key| match state.data.get(key) {
+                Some(entry) => entry.value.as_string().map(|buf| Some(buf.as_slice())),
+                None => Ok(None),
+            })
+            .collect::<Result<_, _>>()?;
+
+        let buf = combine(values);
+        let len = buf.len();
+
+        if buf.is_empty() {
+            state.remove_entry(dest);
+        } else {
+            let new_entry = entry::Entry::builder()
+                .with_bytes(buf)
+                .build_consume()
+                .unwrap();
+
+            state.insert_entry(dest.to_string(), new_entry);
+        }
+
+        Ok(len)
+    }
+
+    /// Calls `read` with the collection stored at `key`.
+    pub(crate) fn read_collection<C: Collection, T>(
+        &self,
+        key: &str,
+        read: impl FnOnce(&C) -> T,
+    ) -> Result<Option<T>, WrongType> {
+        let mut state = self.shared_state.state.lock().unwrap();
+
+        match state.get_entry(key) {
+            Some(entry) => Ok(Some(read(C::from_value(&entry.value)?))),
+            None => Ok(None),
+        }
+    }
+
+    /// Edits the collection stored at `key` in place, keeping its expiration. A missing key
+    /// is edited as an empty collection if `create` is set, and is otherwise left missing,
+    /// `None` being returned. Collections left empty are removed.
+    pub(crate) fn edit_collection<C: Collection, T>(
+        &self,
+        key: &str,
+        create: bool,
+        edit: impl FnOnce(&mut C) -> T,
+    ) -> Result<Option<T>, WrongType> {
+        let mut state = self.shared_state.state.lock().unwrap();
+        let next_expiration = state.get_expired();
+        let res = state.edit_collection(key, create, edit);
+
+        // entries may be added to streams that already exist
+        state.serve_blocked(key);
+
+        // fields of a hash may have been given an expiration
+        let earliest = state.get_expired() != next_expiration;
         drop(state);
 
-        if should_notify {
-            self.shared_state.expiration_task.notify_one();
+        self.notify_expiration_task(earliest);
+        res
+    }
+
+    /// Calls `read` with the collections stored at `keys`, `None` standing for missing keys.
+    pub(crate) fn read_collections<C: Collection, T>(
+        &self,
+        keys: &[String],
+        read: impl FnOnce(Vec<Option<&C>>) -> T,
+    ) -> Result<T, WrongType> {
+        let mut state = self.shared_state.state.lock().unwrap();
+        Ok(read(state.collections(keys)?))
+    }
+
+    /// Stores the collection `combine` computes from the collections at `keys` at `dest`,
+    /// without an expiration. `dest` is removed instead if the collection is empty. Returns
+    /// the size of the collection.
+    pub(crate) fn store_collection<C: Collection>(
+        &self,
+        dest: &str,
+        keys: &[String],
+        combine: impl FnOnce(Vec<Option<&C>>) -> C,
+    ) -> Result<usize, WrongType> {
+        let mut state = self.shared_state.state.lock().unwrap();
+        let collection = combine(state.collections(keys)?);
+        let len = collection.len();
+
+        if collection.is_empty() {
+            state.remove_entry(dest);
+        } else {
+            let new_entry = entry::Entry::builder()
+                .with_value(collection.into_value())
+                .build_consume()
+                .unwrap();
+
+            state.insert_entry(dest.to_string(), new_entry);
+            state.serve_blocked(dest);
+        }
+
+        Ok(len)
+    }
+
+    /// Pops an element from the `from` end of the list at `src` and pushes it to the `to`
+    /// end of the list at `dst`, which is created if needed. Returns the element, `None` if
+    /// `src` does not exist.
+    pub(crate) fn list_move(
+        &self,
+        src: &str,
+        dst: &str,
+        from: ListEnd,
+        to: ListEnd,
+    ) -> Result<Option<Bytes>, WrongType> {
+        let mut state = self.shared_state.state.lock().unwrap();
+        state.list_move(src, dst, from, to)
+    }
+
+    /// Pops an element from the `from` end of the first of the lists at `keys` that exists,
+    /// pushing it to the `to` list if one is given. Replies with the key popped from and the
+    /// element.
+    ///
+    /// If none of the lists exists, the caller is queued on all of them instead, and is
+    /// served by the first push to any of them, after the clients queued before it.
+    pub(crate) fn pop_or_block(
+        &self,
+        keys: Vec<String>,
+        from: ListEnd,
+        to: Option<(String, ListEnd)>,
+    ) -> Result<Result<(String, Bytes), BlockedPop<Bytes>>, WrongType> {
+        let mut state = self.shared_state.state.lock().unwrap();
+
+        for key in &keys {
+            match state.get_entry(key) {
+                Some(entry) => VecDeque::from_value(&entry.value)?,
+                None => continue,
+            };
+
+            let popped = match &to {
+                Some((dst, to)) => state.list_move(key, dst, from, *to)?,
+                None => state
+                    .edit_collection(key, false, |list| from.pop(list))?
+                    .flatten(),
+            };
+
+            // lists are never stored empty
+            return Ok(Ok((key.clone(), popped.unwrap())));
+        }
+
+        let (sender, receiver) = oneshot::channel();
+        let id = state.blocked.block(Waiter {
+            keys,
+            pop: Pop::List { from, to, sender },
+        });
+
+        Ok(Err(BlockedPop::new(
+            id,
+            receiver,
+            self.shared_state.clone(),
+        )))
+    }
+
+    /// Pops the member with the lowest score, or the highest if `max` is set, from the first
+    /// of the sorted sets at `keys` that exists. Replies with the key popped from, the member
+    /// and its score.
+    ///
+    /// If none of the sorted sets exists, the caller is queued on all of them instead, as by
+    /// `pop_or_block`.
+    pub(crate) fn pop_member_or_block(
+        &self,
+        keys: Vec<String>,
+        max: bool,
+    ) -> Result<Result<(String, Scored), BlockedPop<Scored>>, WrongType> {
+        let mut state = self.shared_state.state.lock().unwrap();
+
+        for key in &keys {
+            let popped = state.edit_collection(key, false, |set: &mut SortedSet| set.pop(max))?;
+
+            // sorted sets are never stored empty
+            if let Some(popped) = popped {
+                return Ok(Ok((key.clone(), popped.unwrap())));
+            }
+        }
+
+        let (sender, receiver) = oneshot::channel();
+        let id = state.blocked.block(Waiter {
+            keys,
+            pop: Pop::SortedSet { max, sender },
+        });
+
+        Ok(Err(BlockedPop::new(
+            id,
+            receiver,
+            self.shared_state.clone(),
+        )))
+    }
+
+    /// Reads at most `count` entries of each of the streams at `keys`, after the ID given for
+    /// each of them, `None` standing for the stream's last ID. Replies with the entries of
+    /// the streams that have any to read.
+    ///
+    /// If none has any and `block` is set, the caller is queued on all of them instead, until
+    /// entries are added to one of them.
+    pub(crate) fn read_streams_or_block(
+        &self,
+        keys: Vec<String>,
+        ids: Vec<Option<StreamId>>,
+        count: Option<usize>,
+        block: bool,
+    ) -> Result<StreamReads, WrongType> {
+        let mut state = self.shared_state.state.lock().unwrap();
+        let streams = state.collections::<Stream>(&keys)?;
+        let (mut after, mut read) = (HashMap::new(), vec![]);
+
+        for ((key, id), stream) in keys.iter().zip(ids).zip(streams) {
+            let id = id.unwrap_or_else(|| stream.map_or_else(StreamId::default, Stream::last_id));
+            let entries = stream.map(|stream| stream.read_after(id, count));
+
+            match entries {
+                Some(entries) if !entries.is_empty() => read.push((key.clone(), entries)),
+                _ => {
+                    after.insert(key.clone(), id);
+                }
+            }
+        }
+
+        if !read.is_empty() || !block {
+            return Ok(Ok(read));
+        }
+
+        Ok(Err(self.block_on_streams(
+            &mut state,
+            keys,
+            StreamRead::After(after),
+            count,
+        )))
+    }
+
+    /// Reads at most `count` entries of each of the streams at `keys` for a consumer of a
+    /// group, as [`Stream::read_group`] does with the ID given for each stream. Replies with
+    /// the entries of the streams that have any to read, and with the pending entries read
+    /// back, even if there are none.
+    ///
+    /// If there are none at all, only new entries are read and `block` is set, the caller is
+    /// queued on all the streams instead, until entries are added to one of them. Fails with
+    /// the first key whose stream or group does not exist.
+    pub(crate) fn read_group_or_block(
+        &self,
+        keys: Vec<String>,
+        ids: Vec<Option<StreamId>>,
+        read: GroupRead,
+        count: Option<usize>,
+        block: bool,
+    ) -> Result<StreamReads, GroupError> {
+        let mut state = self.shared_state.state.lock().unwrap();
+
+        // every group is checked before anything is delivered
+        for key in &keys {
+            let has_group = match state.get_entry(key) {
+                Some(entry) => Stream::from_value(&entry.value)?
+                    .group(&read.group)
+                    .is_some(),
+                None => false,
+            };
+
+            if !has_group {
+                return Err(GroupError::NoGroup(key.clone(), read.group));
+            }
+        }
+
+        let mut entries_read = vec![];
+        let only_new = ids.iter().all(Option::is_none);
+
+        for (key, id) in keys.iter().zip(ids) {
+            let entries = state
+                .edit_collection(key, false, |stream: &mut Stream| {
+                    stream.read_group(&read, id, count, Instant::now())
+                })?
+                .flatten()
+                .unwrap_or_default();
+
+            if id.is_some() || !entries.is_empty() {
+                entries_read.push((key.clone(), entries));
+            }
+        }
+
+        if !entries_read.is_empty() || !only_new || !block {
+            return Ok(Ok(entries_read));
+        }
+
+        Ok(Err(self.block_on_streams(
+            &mut state,
+            keys,
+            StreamRead::Group(read),
+            count,
+        )))
+    }
+
+    fn block_on_streams(
+        &self,
+        state: &mut State,
+        keys: Vec<String>,
+        read: StreamRead,
+        count: Option<usize>,
+    ) -> BlockedPop<Vec<StreamEntry>> {
+        let (sender, receiver) = oneshot::channel();
+        let id = state.blocked.block(Waiter {
+            keys,
+            pop: Pop::Stream {
+                read,
+                count,
+                sender,
+            },
+        });
+
+        BlockedPop::new(id, receiver, self.shared_state.clone())
+    }
+
+    /// Removes the given keys. Returns how many of them existed.
+    pub(crate) fn del(&self, keys: &[String]) -> usize {
+        let mut state = self.shared_state.state.lock().unwrap();
+
+        keys.iter()
+            .filter(|key| {
+                state.evict_if_expired(key);
+                state.remove_entry(key).is_some()
+            })
+            .count()
+    }
+
+    /// Counts how many of the given keys exist. Keys given more than once are counted as
+    /// many times.
+    pub(crate) fn exists(&self, keys: &[String]) -> usize {
+        let mut state = self.shared_state.state.lock().unwrap();
+        keys.iter().filter(|key| state.contains_key(key)).count()
+    }
+
+    /// The expiration of the key, `None` if the key does not exist and `Some(None)` if it
+    /// never expires.
+    pub(crate) fn expiration(&self, key: &str) -> Option<Option<Instant>> {
+        let mut state = self.shared_state.state.lock().unwrap();
+        state.get_entry(key).map(|entry| entry.expiration)
+    }
+
+    /// Makes the key expire at `time`, provided it exists and `allow` accepts its current
+    /// expiration. A key whose new expiration has already passed is removed. Returns whether
+    /// the expiration was changed.
+    pub(crate) fn expire(
+        &self,
+        key: &str,
+        time: Instant,
+        allow: impl FnOnce(Option<Instant>) -> bool,
+    ) -> bool {
+        let mut state = self.shared_state.state.lock().unwrap();
+
+        match state.get_entry(key) {
+            Some(entry) if allow(entry.expiration) => {}
+            _ => return false,
+        }
+
+        if time <= Instant::now() {
+            state.remove_entry(key);
+            return true;
+        }
+
+        let earliest = state.set_expiration(key, Some(time));
+        drop(state);
+
+        self.notify_expiration_task(earliest);
+        true
+    }
+
+    /// Removes the key's expiration. Returns whether it had one.
+    pub(crate) fn persist(&self, key: &str) -> bool {
+        let mut state = self.shared_state.state.lock().unwrap();
+
+        if state
+            .get_entry(key)
+            .is_none_or(|entry| entry.expiration.is_none())
+        {
+            return false;
+        }
+
+        state.set_expiration(key, None);
+        true
+    }
+
+    /// The name of the type of the value stored at `key`, if there is one.
+    pub(crate) fn key_type(&self, key: &str) -> Option<&'static str> {
+        let mut state = self.shared_state.state.lock().unwrap();
+        state.get_entry(key).map(|entry| entry.value.type_name())
+    }
+
+    /// The name of the encoding of the value stored at `key`, if there is one.
+    pub(crate) fn encoding(&self, key: &str) -> Option<&'static str> {
+        let mut state = self.shared_state.state.lock().unwrap();
+        state.get_entry(key).map(|entry| entry.value.encoding())
+    }
+
+    /// Moves the value stored at `src`, along with its expiration, to `dst`. An existing
+    /// value at `dst` is only replaced if `overwrite` is set.
+    ///
+    /// Returns `None` if `src` does not exist and otherwise whether the value was moved.
+    pub(crate) fn rename(&self, src: &str, dst: &str, overwrite: bool) -> Option<bool> {
+        let mut state = self.shared_state.state.lock().unwrap();
+
+        if !state.contains_key(src) {
+            return None;
+        }
+
+        if src == dst {
+            return Some(overwrite);
         }
+
+        if !overwrite && state.contains_key(dst) {
+            return Some(false);
+        }
+
+        let entry = state.remove_entry(src)?;
+        state.insert_entry(dst.to_string(), entry);
+        state.serve_blocked(dst);
+        Some(true)
+    }
+
+    /// Copies the value stored at `src`, along with its expiration, to `dst`. An existing
+    /// value at `dst` is only replaced if `replace` is set. Returns whether the value was
+    /// copied.
+    pub(crate) fn copy(&self, src: &str, dst: &str, replace: bool) -> bool {
+        let mut state = self.shared_state.state.lock().unwrap();
+
+        let Some(entry) = state.get_entry(src).cloned() else {
+            return false;
+        };
+
+        if !replace && state.contains_key(dst) {
+            return false;
+        }
+
+        state.insert_entry(dst.to_string(), entry);
+        state.serve_blocked(dst);
+        true
     }
 
     /// Request a reciever for a requested channel identified by its key.
@@ -119,15 +806,86 @@ impl Database {
         }
     }
 
-    /// Publish a message to a channel. Returns the number of subscribed listeners.
+    /// Request a receiver for the messages published to channels matching a glob-style
+    /// pattern, along with their channel.
+    pub(crate) fn psubscribe(&self, pattern: String) -> broadcast::Receiver<(String, Bytes)> {
+        let mut state = self.shared_state.state.lock().unwrap();
+
+        match state.pattern_subs.get(&pattern) {
+            Some(tx) => tx.subscribe(),
+            None => {
+                // the same capacity as channels
+                let (tx, rx) = broadcast::channel(1024);
+                state.pattern_subs.insert(pattern, tx);
+                rx
+            }
+        }
+    }
+
+    /// Publish a message to a channel. Returns the number of subscribed listeners, counting
+    /// those of every pattern the channel matches.
     pub(crate) fn publish(&self, key: &str, val: Bytes) -> usize {
-        let state = self.shared_state.state.lock().unwrap();
+        let mut state = self.shared_state.state.lock().unwrap();
 
-        state
+        let subs = state
             .pub_sub_map
             .get(key)
-            .map(|tx| tx.send(val).unwrap_or(0))
-            .unwrap_or(0)
+            .map(|tx| tx.send(val.clone()).unwrap_or(0))
+            .unwrap_or(0);
+
+        // every pattern is matched against the channel, so patterns nobody listens to any
+        // more are dropped first
+        state.pattern_subs.retain(|_, tx| tx.receiver_count() > 0);
+
+        let pattern_subs: usize = state
+            .pattern_subs
+            .iter()
+            .filter(|(pattern, _)| glob::matches(pattern.as_bytes(), key.as_bytes()))
+            .map(|(_, tx)| tx.send((key.to_string(), val.clone())).unwrap_or(0))
+            .sum();
+
+        subs + pattern_subs
+    }
+
+    pub(crate) fn client_connected(&self) {
+        self.shared_state
+            .connected_clients
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn client_disconnected(&self) {
+        self.shared_state
+            .connected_clients
+            .fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn connected_clients(&self) -> usize {
+        self.shared_state.connected_clients.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn max_clients(&self) -> usize {
+        self.shared_state.max_clients
+    }
+
+    pub(crate) fn max_string_len(&self) -> usize {
+        self.shared_state.max_string_len
+    }
+
+    pub(crate) fn expiry_stats(&self) -> ExpiryStats {
+        let state = self.shared_state.state.lock().unwrap();
+
+        ExpiryStats {
+            expired_keys: state.expired_keys,
+            stale_ratio: state.stale_ratio,
+            volatile_keys: state.expiration_set.len(),
+        }
+    }
+
+    /// Wakes the purge task when a key now expires before the one it is waiting on.
+    fn notify_expiration_task(&self, earliest: bool) {
+        if earliest {
+            self.shared_state.expiration_task.notify_one();
+        }
     }
 
     pub(super) fn halt_background_tasks(&self) {
@@ -138,17 +896,3 @@ impl Database {
         self.shared_state.job_queue_task.notify_one();
     }
 }
-
-#[instrument(name = "purge_expired")]
-async fn purge_expired(shared: Arc<SharedState>) {
-    while shared.has_shutdown() {
-        if let Some(time) = shared.purge_expired() {
-            tokio::select! {
-                _ = tokio::time::sleep_until(time) => {},
-                _ = shared.expiration_task.notified() => {}
-            }
-        } else {
-            shared.expiration_task.notified().await;
-        }
-    }
-}
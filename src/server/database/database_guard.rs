@@ -1,3 +1,5 @@
+use crate::server::config::Config;
+
 use super::database::Database;
 
 #[derive(Clone, Debug)]
@@ -6,9 +8,9 @@ pub(crate) struct DatabaseGuard {
 }
 
 impl DatabaseGuard {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(config: &Config) -> Self {
         Self {
-            db: Database::new(),
+            db: Database::new(config),
         }
     }
 
@@ -1,4 +1,7 @@
-use bytes::Bytes;
+use super::{hash::Hash, sorted_set::SortedSet, stream::Stream};
+
+use bytes::{Bytes, BytesMut};
+use std::collections::{HashSet, VecDeque};
 use thiserror::Error;
 use tokio::time::Instant;
 
@@ -6,21 +9,45 @@ use tokio::time::Instant;
 #[error("[BuilderError] {0}")]
 pub(crate) struct BuilderError(#[from] anyhow::Error);
 
-#[derive(Debug)]
+/// Raised by operations on a key holding a value of another type than they work on.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("WRONGTYPE Operation against a key holding the wrong kind of value")]
+pub(crate) struct WrongType;
+
+#[derive(Debug, Clone)]
 pub(crate) struct Entry {
-    pub(super) buf: Bytes,
+    pub(super) value: Value,
     pub(super) expiration: Option<Instant>,
 }
 
+#[derive(Debug, Clone)]
+pub(crate) enum Value {
+    String(Buffer),
+    List(VecDeque<Bytes>),
+    Hash(Hash),
+    Set(HashSet<Bytes>),
+    SortedSet(SortedSet),
+    Stream(Stream),
+}
+
+/// The bytes of a value. Values that are edited in place are moved into a `BytesMut`, so
+/// that repeated edits do not copy the whole value.
+#[derive(Debug, Clone)]
+pub(crate) enum Buffer {
+    Shared(Bytes),
+    Owned(BytesMut),
+}
+
 pub(crate) struct Builder {
-    buf: Option<Bytes>,
+    value: Option<Value>,
     expiration: Option<Instant>,
 }
 
 impl Entry {
+    /// An entry expires at its expiration instant, not after it.
     pub(crate) fn has_expired(&self) -> bool {
         match self.expiration {
-            Some(expiry) => expiry < Instant::now(),
+            Some(expiry) => expiry <= Instant::now(),
             None => false,
         }
     }
@@ -30,16 +57,281 @@ impl Entry {
     }
 }
 
+impl Value {
+    /// The name reported by `TYPE`.
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            Value::String(_) => "string",
+            Value::List(_) => "list",
+            Value::Hash(_) => "hash",
+            Value::Set(_) => "set",
+            Value::SortedSet(_) => "zset",
+            Value::Stream(_) => "stream",
+        }
+    }
+
+    /// The name reported by `OBJECT ENCODING`. Strings are reported as Redis would store
+    /// them, and other types by the Redis encoding closest to their representation.
+    pub(crate) fn encoding(&self) -> &'static str {
+        match self {
+            Value::String(buf) => buf.encoding(),
+            Value::List(_) => "quicklist",
+            Value::Hash(_) | Value::Set(_) => "hashtable",
+            Value::SortedSet(_) => "skiplist",
+            Value::Stream(_) => "stream",
+        }
+    }
+
+    /// The instant the next field of a hash expires at.
+    pub(crate) fn next_field_expiration(&self) -> Option<Instant> {
+        match self {
+            Value::Hash(hash) => hash.next_expiration(),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_string(&self) -> Result<&Buffer, WrongType> {
+        match self {
+            Value::String(buf) => Ok(buf),
+            _ => Err(WrongType),
+        }
+    }
+
+    pub(crate) fn as_string_mut(&mut self) -> Result<&mut Buffer, WrongType> {
+        match self {
+            Value::String(buf) => Ok(buf),
+            _ => Err(WrongType),
+        }
+    }
+}
+
+/// A collection type held by a [`Value`]. Collections are never stored empty, except for
+/// streams.
+pub(crate) trait Collection: Default + Sized {
+    fn from_value(value: &Value) -> Result<&Self, WrongType>;
+
+    fn from_value_mut(value: &mut Value) -> Result<&mut Self, WrongType>;
+
+    fn into_value(self) -> Value;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool;
+}
+
+impl Collection for VecDeque<Bytes> {
+    fn from_value(value: &Value) -> Result<&Self, WrongType> {
+        match value {
+            Value::List(list) => Ok(list),
+            _ => Err(WrongType),
+        }
+    }
+
+    fn from_value_mut(value: &mut Value) -> Result<&mut Self, WrongType> {
+        match value {
+            Value::List(list) => Ok(list),
+            _ => Err(WrongType),
+        }
+    }
+
+    fn into_value(self) -> Value {
+        Value::List(self)
+    }
+
+    fn len(&self) -> usize {
+        VecDeque::len(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        VecDeque::is_empty(self)
+    }
+}
+
+impl Collection for Hash {
+    fn from_value(value: &Value) -> Result<&Self, WrongType> {
+        match value {
+            Value::Hash(hash) => Ok(hash),
+            _ => Err(WrongType),
+        }
+    }
+
+    fn from_value_mut(value: &mut Value) -> Result<&mut Self, WrongType> {
+        match value {
+            Value::Hash(hash) => Ok(hash),
+            _ => Err(WrongType),
+        }
+    }
+
+    fn into_value(self) -> Value {
+        Value::Hash(self)
+    }
+
+    fn len(&self) -> usize {
+        Hash::len(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        Hash::is_empty(self)
+    }
+}
+
+impl Collection for HashSet<Bytes> {
+    fn from_value(value: &Value) -> Result<&Self, WrongType> {
+        match value {
+            Value::Set(set) => Ok(set),
+            _ => Err(WrongType),
+        }
+    }
+
+    fn from_value_mut(value: &mut Value) -> Result<&mut Self, WrongType> {
+        match value {
+            Value::Set(set) => Ok(set),
+            _ => Err(WrongType),
+        }
+    }
+
+    fn into_value(self) -> Value {
+        Value::Set(self)
+    }
+
+    fn len(&self) -> usize {
+        HashSet::len(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        HashSet::is_empty(self)
+    }
+}
+
+impl Collection for SortedSet {
+    fn from_value(value: &Value) -> Result<&Self, WrongType> {
+        match value {
+            Value::SortedSet(set) => Ok(set),
+            _ => Err(WrongType),
+        }
+    }
+
+    fn from_value_mut(value: &mut Value) -> Result<&mut Self, WrongType> {
+        match value {
+            Value::SortedSet(set) => Ok(set),
+            _ => Err(WrongType),
+        }
+    }
+
+    fn into_value(self) -> Value {
+        Value::SortedSet(self)
+    }
+
+    fn len(&self) -> usize {
+        SortedSet::len(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        SortedSet::is_empty(self)
+    }
+}
+
+impl Collection for Stream {
+    fn from_value(value: &Value) -> Result<&Self, WrongType> {
+        match value {
+            Value::Stream(stream) => Ok(stream),
+            _ => Err(WrongType),
+        }
+    }
+
+    fn from_value_mut(value: &mut Value) -> Result<&mut Self, WrongType> {
+        match value {
+            Value::Stream(stream) => Ok(stream),
+            _ => Err(WrongType),
+        }
+    }
+
+    fn into_value(self) -> Value {
+        Value::Stream(self)
+    }
+
+    fn len(&self) -> usize {
+        Stream::len(self)
+    }
+
+    /// Streams are kept once emptied, along with their groups and last ID.
+    fn is_empty(&self) -> bool {
+        false
+    }
+}
+
+impl Buffer {
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        match self {
+            Buffer::Shared(buf) => buf,
+            Buffer::Owned(buf) => buf,
+        }
+    }
+
+    /// Copies the value if it is being edited in place.
+    pub(crate) fn to_bytes(&self) -> Bytes {
+        match self {
+            Buffer::Shared(buf) => buf.clone(),
+            Buffer::Owned(buf) => Bytes::copy_from_slice(buf),
+        }
+    }
+
+    pub(crate) fn into_bytes(self) -> Bytes {
+        match self {
+            Buffer::Shared(buf) => buf,
+            Buffer::Owned(buf) => buf.freeze(),
+        }
+    }
+
+    fn encoding(&self) -> &'static str {
+        let buf = self.as_slice();
+
+        let is_int = buf.len() <= 20
+            && std::str::from_utf8(buf)
+                .ok()
+                .and_then(|s| s.parse::<i64>().ok())
+                .is_some_and(|i| i.to_string().as_bytes() == buf);
+
+        match self {
+            _ if is_int => "int",
+            Buffer::Shared(buf) if buf.len() <= 44 => "embstr",
+            _ => "raw",
+        }
+    }
+
+    /// Returns the value for editing, copying it out of a shared buffer the first time.
+    pub(crate) fn make_mut(&mut self) -> &mut BytesMut {
+        if let Buffer::Shared(buf) = self {
+            *self = Buffer::Owned(BytesMut::from(&buf[..]));
+        }
+
+        match self {
+            Buffer::Owned(buf) => buf,
+            Buffer::Shared(_) => unreachable!(),
+        }
+    }
+}
+
 impl Builder {
     pub(crate) fn new() -> Self {
         Self {
-            buf: None,
+            value: None,
             expiration: None,
         }
     }
 
     pub(crate) fn with_bytes(mut self, buffer: Bytes) -> Self {
-        self.buf = Some(buffer);
+        self.value = Some(Value::String(Buffer::Shared(buffer)));
+        self
+    }
+
+    pub(crate) fn with_bytes_mut(mut self, buffer: BytesMut) -> Self {
+        self.value = Some(Value::String(Buffer::Owned(buffer)));
+        self
+    }
+
+    pub(crate) fn with_value(mut self, value: Value) -> Self {
+        self.value = Some(value);
         self
     }
 
@@ -49,23 +341,23 @@ impl Builder {
     }
 
     pub(crate) fn build(self) -> Result<Entry, BuilderError> {
-        if self.buf.is_none() {
-            return Err(anyhow::anyhow!("Buffer is empty").into());
+        if self.value.is_none() {
+            return Err(anyhow::anyhow!("Value is empty").into());
         }
 
         Ok(Entry {
-            buf: self.buf.clone().unwrap(),
+            value: self.value.clone().unwrap(),
             expiration: self.expiration.clone(),
         })
     }
 
     pub(crate) fn build_consume(mut self) -> Result<Entry, BuilderError> {
-        if self.buf.is_none() {
-            return Err(anyhow::anyhow!("Buffer is empty").into());
+        if self.value.is_none() {
+            return Err(anyhow::anyhow!("Value is empty").into());
         }
 
         Ok(Entry {
-            buf: self.buf.unwrap(),
+            value: self.value.unwrap(),
             expiration: self.expiration,
         })
     }
@@ -74,7 +366,7 @@ impl Builder {
 impl Default for Builder {
     fn default() -> Self {
         Self {
-            buf: None,
+            value: None,
             expiration: None,
         }
     }
@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use tokio::time::{Duration, Instant};
+use tracing::{debug, instrument};
+
+use super::shared_state::SharedState;
+
+/// Keys removed per acquisition of the state lock.
+const KEYS_PER_BATCH: usize = 20;
+
+/// Batches run per tick at the lowest effort. Each effort level adds as many again.
+const BATCHES_PER_TICK: usize = 10;
+
+/// Upper bound on the effort, and so on the work done by a single tick.
+const MAX_EFFORT: usize = 8;
+
+/// Pause between ticks while due keys are left over, giving clients a turn at the lock.
+const BACKLOG_TICK_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Expiration statistics, as reported by `INFO`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct ExpiryStats {
+    /// Keys removed because their expiration passed.
+    pub(crate) expired_keys: u64,
+    /// Share of the keys with an expiration that the last purge tick found expired.
+    pub(crate) stale_ratio: f64,
+    /// Keys that currently have an expiration.
+    pub(crate) volatile_keys: usize,
+}
+
+/// The outcome of a purge tick.
+enum Tick {
+    /// The work bound was hit with due keys left over.
+    Backlog,
+    /// Every due key was removed. Holds the instant the next key expires at, if any.
+    Done(Option<Instant>),
+}
+
+/// Background task removing expired keys, so that keys which are never read again do not
+/// linger.
+///
+/// Work is done in ticks of bounded batches, and the state lock is released between batches.
+/// When a tick leaves due keys behind the effort is doubled for the next one, and it is reset
+/// once the purge has caught up.
+#[instrument(name = "purge_expired", skip(shared))]
+pub(super) async fn purge_expired(shared: Arc<SharedState>) {
+    let mut effort = 1;
+
+    while !shared.has_shutdown() {
+        let next = match purge_tick(&shared, effort) {
+            Tick::Backlog => {
+                effort = (effort * 2).min(MAX_EFFORT);
+                Some(Instant::now() + BACKLOG_TICK_INTERVAL)
+            }
+            Tick::Done(next) => {
+                effort = 1;
+                next
+            }
+        };
+
+        match next {
+            Some(time) => tokio::select! {
+                _ = tokio::time::sleep_until(time) => {},
+                _ = shared.expiration_task.notified() => {}
+            },
+            None => shared.expiration_task.notified().await,
+        }
+    }
+}
+
+fn purge_tick(shared: &SharedState, effort: usize) -> Tick {
+    let mut expired = 0;
+    let mut volatile = None;
+
+    for _ in 0..BATCHES_PER_TICK * effort {
+        let mut state = shared.state.lock().unwrap();
+
+        if !state.active {
+            return Tick::Done(None);
+        }
+
+        let volatile = *volatile.get_or_insert(state.expiration_set.len());
+        let removed = state.expire_due(Instant::now(), KEYS_PER_BATCH);
+        expired += removed;
+
+        if removed < KEYS_PER_BATCH {
+            state.stale_ratio = ratio(expired, volatile);
+
+            if expired > 0 {
+                debug!(expired, "purged expired keys");
+            }
+
+            return Tick::Done(state.get_expired());
+        }
+    }
+
+    let mut state = shared.state.lock().unwrap();
+    state.stale_ratio = ratio(expired, volatile.unwrap_or(0));
+
+    debug!(expired, effort, "purge tick left due keys behind");
+    Tick::Backlog
+}
+
+fn ratio(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
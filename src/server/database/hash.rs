@@ -0,0 +1,159 @@
+use bytes::Bytes;
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeSet, HashMap},
+    hash::{Hash as _, Hasher},
+};
+use tokio::time::Instant;
+
+/// A hash value. Fields may expire on their own, ahead of the key.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Hash {
+    fields: HashMap<Bytes, Field>,
+    /// The fields that have an expiration, soonest first.
+    expirations: BTreeSet<(Instant, Bytes)>,
+    /// Every field by its scan position, the order `scan` walks them in.
+    positions: BTreeSet<(u64, Bytes)>,
+}
+
+#[derive(Debug, Clone)]
+struct Field {
+    value: Bytes,
+    expiration: Option<Instant>,
+}
+
+impl Hash {
+    pub(crate) fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub(crate) fn get(&self, field: &[u8]) -> Option<&Bytes> {
+        self.fields.get(field).map(|f| &f.value)
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&Bytes, &Bytes)> {
+        self.fields.iter().map(|(name, f)| (name, &f.value))
+    }
+
+    /// Stores `value` at `field`, removing any expiration of the field. Returns whether the
+    /// field is new.
+    pub(crate) fn insert(&mut self, field: Bytes, value: Bytes) -> bool {
+        let previous = self.fields.insert(
+            field.clone(),
+            Field {
+                value,
+                expiration: None,
+            },
+        );
+
+        match previous {
+            Some(Field {
+                expiration: Some(time),
+                ..
+            }) => {
+                self.expirations.remove(&(time, field));
+                false
+            }
+            Some(_) => false,
+            None => {
+                self.positions.insert((scan_position(&field), field));
+                true
+            }
+        }
+    }
+
+    /// The value of `field` for editing in place. The field keeps its expiration.
+    pub(crate) fn get_mut(&mut self, field: &[u8]) -> Option<&mut Bytes> {
+        self.fields.get_mut(field).map(|f| &mut f.value)
+    }
+
+    /// Removes `field`. Returns whether it existed.
+    pub(crate) fn remove(&mut self, field: &[u8]) -> bool {
+        let Some((name, removed)) = self.fields.remove_entry(field) else {
+            return false;
+        };
+
+        if let Some(time) = removed.expiration {
+            self.expirations.remove(&(time, name.clone()));
+        }
+
+        self.positions.remove(&(scan_position(&name), name));
+        true
+    }
+
+    /// The expiration of `field`, `None` if it does not exist and `Some(None)` if it never
+    /// expires.
+    pub(crate) fn expiration(&self, field: &[u8]) -> Option<Option<Instant>> {
+        self.fields.get(field).map(|f| f.expiration)
+    }
+
+    /// Changes the expiration of `field`. Returns whether the field exists.
+    pub(crate) fn set_expiration(&mut self, field: &[u8], expiration: Option<Instant>) -> bool {
+        let Some((name, f)) = self.fields.get_key_value(field) else {
+            return false;
+        };
+
+        let name = name.clone();
+
+        if let Some(time) = f.expiration {
+            self.expirations.remove(&(time, name.clone()));
+        }
+
+        if let Some(time) = expiration {
+            self.expirations.insert((time, name.clone()));
+        }
+
+        self.fields.get_mut(&name).unwrap().expiration = expiration;
+        true
+    }
+
+    /// The instant the next field expires at, if any field has an expiration.
+    pub(crate) fn next_expiration(&self) -> Option<Instant> {
+        self.expirations.first().map(|(time, _)| *time)
+    }
+
+    /// Removes the fields due to expire by `now`. Returns how many were removed.
+    pub(crate) fn expire_due(&mut self, now: Instant) -> usize {
+        let mut expired = 0;
+
+        while self.expirations.first().is_some_and(|(t, _)| *t <= now) {
+            let (_, field) = self.expirations.pop_first().unwrap();
+            self.fields.remove(&field);
+            self.positions.remove(&(scan_position(&field), field));
+            expired += 1;
+        }
+
+        expired
+    }
+
+    /// Returns about `count` fields, starting at `cursor`, along with the cursor to continue
+    /// from, zero once every field has been returned.
+    ///
+    /// Fields are walked by a position derived from their name only, so that a field present
+    /// for a whole scan is returned exactly once, however the hash changes meanwhile.
+    pub(crate) fn scan(&self, cursor: u64, count: usize) -> (u64, Vec<(&Bytes, &Bytes)>) {
+        let mut positions = self.positions.range((cursor, Bytes::new())..).peekable();
+        let mut fields = Vec::new();
+        let mut last = None;
+
+        // fields sharing a position are returned together, a cursor cannot split them
+        while let Some((position, name)) = positions
+            .next_if(|(position, _)| fields.len() < count.max(1) || last == Some(*position))
+        {
+            last = Some(*position);
+            fields.push((name, &self.fields[name].value));
+        }
+
+        let next = positions.peek().map_or(0, |(position, _)| *position);
+        (next, fields)
+    }
+}
+
+fn scan_position(field: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    field.hash(&mut hasher);
+    hasher.finish()
+}
@@ -1,7 +1,6 @@
-use std::sync::Mutex;
+use std::sync::{atomic::AtomicUsize, Mutex};
 
-use tokio::{sync::Notify, time::Instant};
-use tracing::info;
+use tokio::sync::Notify;
 
 use super::state::State;
 
@@ -10,32 +9,15 @@ pub(crate) struct SharedState {
     pub(crate) state: Mutex<State>,
     pub(crate) expiration_task: Notify,
     pub(crate) job_queue_task: Notify,
+    /// Number of clients currently connected, kept up to date by the listener.
+    pub(crate) connected_clients: AtomicUsize,
+    /// The most clients the listener serves at once.
+    pub(crate) max_clients: usize,
+    /// The largest a string value may grow to, in bytes (`proto-max-bulk-len`).
+    pub(crate) max_string_len: usize,
 }
 
 impl SharedState {
-    pub(super) fn purge_expired(&self) -> Option<Instant> {
-        let mut state = self.state.lock().unwrap();
-
-        if !state.active {
-            return None;
-        }
-
-        let now = Instant::now();
-        let state = &mut *state;
-
-        while let Some(&(time, ref key)) = state.expiration_set.iter().next() {
-            if time > now {
-                return Some(time);
-            }
-
-            info!(key = key.clone(), "purging key");
-            state.data.remove(key);
-            state.expiration_set.remove(&(time, key.clone()));
-        }
-
-        None
-    }
-
     pub(crate) fn has_shutdown(&self) -> bool {
         !self.state.lock().unwrap().active
     }
@@ -0,0 +1,140 @@
+use bytes::Bytes;
+use std::{
+    cmp::Ordering,
+    collections::{BTreeSet, HashMap},
+    ops::{Bound, RangeBounds},
+};
+
+/// The score of a sorted set member, totally ordered so that it can key a `BTreeSet`. Scores
+/// are never NaN.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Score(pub(crate) f64);
+
+impl PartialEq for Score {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Score {}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Score {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Members ordered by score, members with equal scores being ordered lexicographically.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SortedSet {
+    scores: HashMap<Bytes, Score>,
+    ordered: BTreeSet<(Score, Bytes)>,
+}
+
+impl SortedSet {
+    pub(crate) fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    pub(crate) fn score(&self, member: &[u8]) -> Option<f64> {
+        self.scores.get(member).map(|score| score.0)
+    }
+
+    /// Sets the score of `member`. Returns its previous score if it was a member.
+    pub(crate) fn insert(&mut self, member: Bytes, score: f64) -> Option<f64> {
+        // zero is given a single sign, as the total order tells them apart
+        let score = Score(score + 0.0);
+        let previous = self.scores.insert(member.clone(), score);
+
+        if let Some(previous) = previous {
+            self.ordered.remove(&(previous, member.clone()));
+        }
+
+        self.ordered.insert((score, member));
+        previous.map(|score| score.0)
+    }
+
+    /// Removes `member`. Returns its score if it was a member.
+    pub(crate) fn remove(&mut self, member: &[u8]) -> Option<f64> {
+        let (member, score) = self.scores.remove_entry(member)?;
+        self.ordered.remove(&(score, member));
+        Some(score.0)
+    }
+
+    /// The number of members ordered before `member`.
+    pub(crate) fn rank(&self, member: &[u8]) -> Option<usize> {
+        let (member, score) = self.scores.get_key_value(member)?;
+        Some(self.ordered.range(..(*score, member.clone())).count())
+    }
+
+    /// The members in order, along with their scores.
+    pub(crate) fn iter(&self) -> impl DoubleEndedIterator<Item = (&Bytes, f64)> {
+        self.ordered.iter().map(|(score, member)| (member, score.0))
+    }
+
+    /// The members whose score is within `min` and `max`, in order.
+    pub(crate) fn range_by_score(
+        &self,
+        min: Bound<f64>,
+        max: Bound<f64>,
+    ) -> impl DoubleEndedIterator<Item = (&Bytes, f64)> {
+        // members are keyed by score first, so the empty member is the first of a score
+        let first_of = |score: f64| (Score(score + 0.0), Bytes::new());
+        let next_up = |score: f64| (score < f64::INFINITY).then(|| first_of(score.next_up()));
+
+        let start = match min {
+            Bound::Included(min) => Some(first_of(min)),
+            Bound::Excluded(min) => next_up(min),
+            Bound::Unbounded => Some(first_of(f64::NEG_INFINITY)),
+        };
+
+        let end = match max {
+            Bound::Included(max) => next_up(max).map_or(Bound::Unbounded, Bound::Excluded),
+            Bound::Excluded(max) => Bound::Excluded(first_of(max)),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+
+        let range = match (start, end) {
+            (Some(start), Bound::Excluded(end)) if start < end => self
+                .ordered
+                .range((Bound::Included(start), Bound::Excluded(end))),
+            (Some(start), Bound::Unbounded) => self.ordered.range(start..),
+            // ranges must not end before they start
+            _ => self.ordered.range(..first_of(f64::NEG_INFINITY)),
+        };
+
+        range.map(|(score, member)| (member, score.0))
+    }
+
+    /// The members within `min` and `max`, compared byte by byte, in order. Only meaningful
+    /// when every member has the same score.
+    pub(crate) fn range_by_lex<'a>(
+        &'a self,
+        min: Bound<&'a [u8]>,
+        max: Bound<&'a [u8]>,
+    ) -> impl DoubleEndedIterator<Item = (&'a Bytes, f64)> {
+        self.iter()
+            .filter(move |(member, _)| RangeBounds::<[u8]>::contains(&(min, max), member.as_ref()))
+    }
+
+    /// Removes the member with the lowest score, or the highest if `max` is set.
+    pub(crate) fn pop(&mut self, max: bool) -> Option<(Bytes, f64)> {
+        let (score, member) = match max {
+            true => self.ordered.pop_last()?,
+            false => self.ordered.pop_first()?,
+        };
+
+        self.scores.remove(&member);
+        Some((member, score.0))
+    }
+}
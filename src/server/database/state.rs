@@ -1,18 +1,468 @@
-use super::entry::Entry;
+use super::{
+    blocking::{Blocked, Pop, Scored, Served, StreamRead, Waiter},
+    database::ListEnd,
+    entry::{Collection, Entry, Value, WrongType},
+    sorted_set::SortedSet,
+    stream::Stream,
+};
 use bytes::Bytes;
-use std::collections::{BTreeMap, BTreeSet, HashMap};
-use tokio::{sync::broadcast, time::Instant};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use tokio::{
+    sync::{broadcast, oneshot},
+    time::Instant,
+};
 
 #[derive(Debug)]
 pub(crate) struct State {
     pub(super) data: BTreeMap<String, Entry>,
     pub(super) pub_sub_map: HashMap<String, broadcast::Sender<Bytes>>,
+    /// Pattern subscriptions, by pattern. Messages are sent along with their channel.
+    pub(super) pattern_subs: HashMap<String, broadcast::Sender<(String, Bytes)>>,
     pub(super) expiration_set: BTreeSet<(Instant, String)>,
+    /// Hashes with expiring fields, by the instant their next field expires at.
+    pub(super) field_expiration_set: BTreeSet<(Instant, String)>,
+    /// Clients waiting for an element to be added to a list, sorted set or stream.
+    pub(super) blocked: Blocked,
     pub(crate) active: bool,
+    /// Number of keys removed because their expiration passed, whether on access or by the
+    /// background purge.
+    pub(super) expired_keys: u64,
+    /// Share of the keys with an expiration that the last purge tick found expired.
+    pub(super) stale_ratio: f64,
 }
 
 impl State {
+    pub(super) fn new() -> Self {
+        Self {
+            data: BTreeMap::new(),
+            pub_sub_map: HashMap::new(),
+            pattern_subs: HashMap::new(),
+            expiration_set: BTreeSet::new(),
+            field_expiration_set: BTreeSet::new(),
+            blocked: Blocked::default(),
+            active: true,
+            expired_keys: 0,
+            stale_ratio: 0.0,
+        }
+    }
+
+    /// The instant the next key, or field of a hash, expires at.
     pub(super) fn get_expired(&self) -> Option<Instant> {
-        self.expiration_set.iter().next().map(|k| k.0)
+        let key = self.expiration_set.first().map(|k| k.0);
+        let field = self.field_expiration_set.first().map(|k| k.0);
+
+        key.into_iter().chain(field).min()
+    }
+
+    /// Looks up the entry stored at `key`. Entries past their expiration are removed on access,
+    /// so they are never observed, however late the background purge runs.
+    pub(super) fn get_entry(&mut self, key: &str) -> Option<&Entry> {
+        self.evict_if_expired(key);
+        self.data.get(key)
+    }
+
+    /// Whether a live entry is stored at `key`.
+    pub(super) fn contains_key(&mut self, key: &str) -> bool {
+        self.get_entry(key).is_some()
+    }
+
+    /// Stores `entry` at `key`, replacing any previous entry along with its expiration data.
+    /// Returns whether the entry now expires before any other.
+    pub(super) fn insert_entry(&mut self, key: String, entry: Entry) -> bool {
+        self.remove_entry(&key);
+        let earliest = self.get_expired();
+        self.track_expiration(&key, entry.expiration);
+
+        if let Some(time) = entry.value.next_field_expiration() {
+            self.field_expiration_set.insert((time, key.clone()));
+        }
+
+        self.data.insert(key, entry);
+        self.get_expired() != earliest
+    }
+
+    /// Changes the expiration of the entry stored at `key`, if there is one. Returns whether
+    /// the entry now expires before any other.
+    pub(super) fn set_expiration(&mut self, key: &str, expiration: Option<Instant>) -> bool {
+        let Some(entry) = self.data.get_mut(key) else {
+            return false;
+        };
+
+        if let Some(previous) = std::mem::replace(&mut entry.expiration, expiration) {
+            self.expiration_set.remove(&(previous, key.to_string()));
+        }
+
+        self.track_expiration(key, expiration)
+    }
+
+    fn track_expiration(&mut self, key: &str, expiration: Option<Instant>) -> bool {
+        let Some(time) = expiration else {
+            return false;
+        };
+
+        let earliest = self.get_expired().is_none_or(|next| time < next);
+        self.expiration_set.insert((time, key.to_string()));
+        earliest
+    }
+
+    /// Removes the entry stored at `key` along with its expiration data.
+    pub(super) fn remove_entry(&mut self, key: &str) -> Option<Entry> {
+        let entry = self.data.remove(key)?;
+
+        if let Some(expiration) = entry.expiration {
+            self.expiration_set.remove(&(expiration, key.to_string()));
+        }
+
+        if let Some(time) = entry.value.next_field_expiration() {
+            self.field_expiration_set.remove(&(time, key.to_string()));
+        }
+
+        Some(entry)
+    }
+
+    /// Moves the hash at `key` to its place in the field expiration set, after its next field
+    /// expiration changed from `previous`.
+    fn track_field_expiration(&mut self, key: &str, previous: Option<Instant>) {
+        let next = self
+            .data
+            .get(key)
+            .and_then(|entry| entry.value.next_field_expiration());
+
+        if next == previous {
+            return;
+        }
+
+        if let Some(time) = previous {
+            self.field_expiration_set.remove(&(time, key.to_string()));
+        }
+
+        if let Some(time) = next {
+            self.field_expiration_set.insert((time, key.to_string()));
+        }
+    }
+
+    /// Edits the collection stored at `key` in place, keeping its expiration. A missing key
+    /// is edited as an empty collection if `create` is set, and is otherwise left missing,
+    /// `None` being returned. Collections left empty are removed.
+    pub(super) fn edit_collection<C: Collection, T>(
+        &mut self,
+        key: &str,
+        create: bool,
+        edit: impl FnOnce(&mut C) -> T,
+    ) -> Result<Option<T>, WrongType> {
+        self.evict_if_expired(key);
+
+        let Some(entry) = self.data.get_mut(key) else {
+            if !create {
+                return Ok(None);
+            }
+
+            let mut collection = C::default();
+            let res = edit(&mut collection);
+
+            if !collection.is_empty() {
+                let entry = Entry::builder()
+                    .with_value(collection.into_value())
+                    .build_consume()
+                    .unwrap();
+
+                self.insert_entry(key.to_string(), entry);
+            }
+
+            return Ok(Some(res));
+        };
+
+        let previous = entry.value.next_field_expiration();
+        let collection = C::from_value_mut(&mut entry.value)?;
+        let res = edit(collection);
+        let is_empty = collection.is_empty();
+
+        self.track_field_expiration(key, previous);
+
+        if is_empty {
+            self.remove_entry(key);
+        }
+
+        Ok(Some(res))
+    }
+
+    /// The collections stored at `keys`, `None` standing for missing keys.
+    pub(super) fn collections<C: Collection>(
+        &mut self,
+        keys: &[String],
+    ) -> Result<Vec<Option<&C>>, WrongType> {
+        for key in keys {
+            self.evict_if_expired(key);
+        }
+
+        keys.iter()
+            .map(|key| match self.data.get(key) {
+                Some(entry) => C::from_value(&entry.value).map(Some),
+                None => Ok(None),
+            })
+            .collect()
+    }
+
+    /// Pops an element from the `from` end of the list at `src` and pushes it to the `to`
+    /// end of the list at `dst`, which is created if needed. Returns the element, `None` if
+    /// `src` does not exist.
+    pub(super) fn list_move(
+        &mut self,
+        src: &str,
+        dst: &str,
+        from: ListEnd,
+        to: ListEnd,
+    ) -> Result<Option<Bytes>, WrongType> {
+        // both keys are checked before anything is popped
+        match self.get_entry(src) {
+            Some(entry) => VecDeque::from_value(&entry.value)?,
+            None => return Ok(None),
+        };
+
+        if let Some(entry) = self.get_entry(dst) {
+            VecDeque::from_value(&entry.value)?;
+        }
+
+        if src == dst {
+            let rotated = self.edit_collection(src, false, |list: &mut VecDeque<Bytes>| {
+                let value = from.pop(list)?;
+                to.push(list, value.clone());
+                Some(value)
+            });
+
+            return rotated.map(Option::flatten);
+        }
+
+        let popped = self.edit_collection(src, false, |list| from.pop(list))?;
+        let Some(value) = popped.flatten() else {
+            return Ok(None);
+        };
+
+        self.edit_collection(dst, true, |list| to.push(list, value.clone()))?;
+        self.serve_blocked(dst);
+        Ok(Some(value))
+    }
+
+    /// Hands elements of the list, sorted set or stream at `key` to the clients blocked on
+    /// it, first come first served. The lists `BLMOVE` pushes the elements to are served in
+    /// turn.
+    pub(super) fn serve_blocked(&mut self, key: &str) {
+        let mut ready = vec![key.to_string()];
+
+        while let Some(key) = ready.pop() {
+            let is_stream = self
+                .get_entry(&key)
+                .is_some_and(|entry| Stream::from_value(&entry.value).is_ok());
+
+            if is_stream {
+                self.serve_stream_readers(&key);
+                continue;
+            }
+
+            while let Some(entry) = self.get_entry(&key) {
+                let type_name = entry.value.type_name();
+                let Some(id) = self.blocked.front(&key, type_name) else {
+                    break;
+                };
+
+                match self.blocked.unblock(id).unwrap().pop {
+                    Pop::List { from, to, sender } => {
+                        ready.extend(self.serve_list(&key, from, to, sender));
+                    }
+                    Pop::SortedSet { max, sender } => self.serve_sorted_set(&key, max, sender),
+                    Pop::Stream { .. } => unreachable!("streams are served in place"),
+                }
+            }
+        }
+    }
+
+    /// Pops an element of the list at `key` for a blocked client, and pushes it to the `to`
+    /// list for `BLMOVE`. Returns the list pushed to.
+    fn serve_list(
+        &mut self,
+        key: &str,
+        from: ListEnd,
+        to: Option<(String, ListEnd)>,
+        sender: oneshot::Sender<Served<Bytes>>,
+    ) -> Option<String> {
+        if let Some((dst, _)) = &to {
+            let dst_is_list = self
+                .get_entry(dst)
+                .is_none_or(|entry| VecDeque::from_value(&entry.value).is_ok());
+
+            if !dst_is_list {
+                let _ = sender.send(Err(WrongType));
+                return None;
+            }
+        }
+
+        let popped = self.edit_collection(key, false, |list: &mut VecDeque<Bytes>| {
+            let value = from.pop(list)?;
+
+            // the client may have gone while it was queued
+            match sender.send(Ok((key.to_string(), value.clone()))) {
+                Ok(()) => Some(value),
+                Err(_) => {
+                    from.push(list, value);
+                    None
+                }
+            }
+        });
+
+        let (Ok(Some(Some(value))), Some((dst, to))) = (popped, to) else {
+            return None;
+        };
+
+        let _ = self.edit_collection(&dst, true, |list| to.push(list, value));
+        Some(dst)
+    }
+
+    /// Pops the member with the lowest score of the sorted set at `key` for a blocked client,
+    /// or the highest if `max` is set.
+    fn serve_sorted_set(&mut self, key: &str, max: bool, sender: oneshot::Sender<Served<Scored>>) {
+        let _ = self.edit_collection(key, false, |set: &mut SortedSet| {
+            let (member, score) = set.pop(max)?;
+
+            // the client may have gone while it was queued
+            if sender
+                .send(Ok((key.to_string(), (member.clone(), score))))
+                .is_err()
+            {
+                set.insert(member, score);
+            }
+
+            Some(())
+        });
+    }
+
+    /// Hands entries of the stream at `key` to the clients blocked on it that have entries
+    /// to read. Clients reading for the same consumer group are served first come first
+    /// served, each reading the entries the previous ones left.
+    fn serve_stream_readers(&mut self, key: &str) {
+        let Some(Value::Stream(stream)) = self.data.get_mut(key).map(|entry| &mut entry.value)
+        else {
+            return;
+        };
+
+        let queued: Vec<_> = self.blocked.queued(key, "stream").collect();
+
+        for id in queued {
+            let Some(Waiter {
+                pop:
+                    Pop::Stream {
+                        read,
+                        count,
+                        sender,
+                    },
+                ..
+            }) = self.blocked.get(id)
+            else {
+                continue;
+            };
+
+            // entries read for a group must not be delivered to a client that has gone
+            if sender.is_closed() {
+                continue;
+            }
+
+            let entries = match read {
+                StreamRead::After(ids) => stream.read_after(ids[key], *count),
+                StreamRead::Group(read) => stream
+                    .read_group(read, None, *count, Instant::now())
+                    .unwrap_or_default(),
+            };
+
+            if entries.is_empty() {
+                continue;
+            }
+
+            if let Some(Pop::Stream { sender, .. }) = self.blocked.unblock(id).map(|w| w.pop) {
+                let _ = sender.send(Ok((key.to_string(), entries)));
+            }
+        }
+    }
+
+    /// Removes the entry stored at `key` if it has expired, and otherwise the fields of a hash
+    /// that have. Returns whether the entry was removed.
+    pub(super) fn evict_if_expired(&mut self, key: &str) -> bool {
+        let Some(entry) = self.data.get(key) else {
+            return false;
+        };
+
+        if entry.has_expired() {
+            self.remove_entry(key);
+            self.expired_keys += 1;
+            return true;
+        }
+
+        let now = Instant::now();
+
+        if entry
+            .value
+            .next_field_expiration()
+            .is_some_and(|t| t <= now)
+        {
+            return self.expire_fields(key, now);
+        }
+
+        false
+    }
+
+    /// Removes the fields of the hash at `key` due to expire by `now`, and the hash if no
+    /// field is left. Returns whether the hash was removed.
+    fn expire_fields(&mut self, key: &str, now: Instant) -> bool {
+        let Some(entry) = self.data.get_mut(key) else {
+            return false;
+        };
+
+        let previous = entry.value.next_field_expiration();
+
+        let Value::Hash(hash) = &mut entry.value else {
+            return false;
+        };
+
+        hash.expire_due(now);
+        let is_empty = hash.is_empty();
+
+        self.track_field_expiration(key, previous);
+
+        if is_empty {
+            self.remove_entry(key);
+        }
+
+        is_empty
+    }
+
+    /// Removes at most `limit` of the entries due to expire by `now`, soonest first. Hashes
+    /// with fields due to expire have these fields removed, each hash counting as one entry.
+    /// Returns how many entries were handled.
+    pub(super) fn expire_due(&mut self, now: Instant, limit: usize) -> usize {
+        let mut handled = 0;
+        let mut expired = 0;
+
+        while handled < limit {
+            let key_due = self.expiration_set.first().filter(|(t, _)| *t <= now);
+            let field_due = self.field_expiration_set.first().filter(|(t, _)| *t <= now);
+
+            match (key_due, field_due) {
+                (Some((key_time, _)), field) if field.is_none_or(|(t, _)| key_time <= t) => {
+                    let (_, key) = self.expiration_set.pop_first().unwrap();
+                    self.remove_entry(&key);
+                    expired += 1;
+                }
+                (_, Some(_)) => {
+                    // the hash's next deadline, if any, is tracked again once its fields are
+                    // expired, so a stale entry cannot stay due forever
+                    let (_, key) = self.field_expiration_set.pop_first().unwrap();
+                    self.expire_fields(&key, now);
+                }
+                _ => break,
+            }
+
+            handled += 1;
+        }
+
+        self.expired_keys += expired as u64;
+        handled
     }
 }
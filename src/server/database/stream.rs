@@ -0,0 +1,468 @@
+use bytes::Bytes;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+    ops::Bound,
+    time::Duration,
+};
+use tokio::time::Instant;
+
+/// The ID of a stream entry: a unix time in milliseconds, and a sequence number for entries
+/// added within the same millisecond.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct StreamId {
+    pub(crate) ms: u64,
+    pub(crate) seq: u64,
+}
+
+/// The field-value pairs of a stream entry.
+pub(crate) type Fields = Vec<(Bytes, Bytes)>;
+
+/// A stream entry, along with its ID.
+pub(crate) type StreamEntry = (StreamId, Fields);
+
+/// The ID a new entry is added with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NewId {
+    /// Generated from the current time, `*`.
+    Auto,
+    /// The given time with a generated sequence number, `<ms>-*`.
+    AutoSeq(u64),
+    Exact(StreamId),
+}
+
+/// How the oldest entries of a stream are evicted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Trim {
+    /// Keeps at most this many entries.
+    MaxLen(usize),
+    /// Evicts the entries with a lower ID.
+    MinId(StreamId),
+}
+
+/// A consumer of a group reading a stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct GroupRead {
+    pub(crate) group: String,
+    pub(crate) consumer: String,
+    /// Entries read are not added to the pending entries list.
+    pub(crate) noack: bool,
+}
+
+/// An entry delivered to a consumer of a group, not acknowledged yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Pending {
+    pub(crate) consumer: String,
+    pub(crate) delivered: Instant,
+    pub(crate) deliveries: u64,
+}
+
+/// How `XCLAIM` claims entries, beyond handing them to the new consumer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct ClaimOptions {
+    /// Only claims entries idle for at least this long.
+    pub(crate) min_idle: Duration,
+    /// How long ago the entries count as delivered, now if `None`.
+    pub(crate) idle: Option<Duration>,
+    /// The delivery count the entries are given, instead of incrementing it.
+    pub(crate) retry_count: Option<u64>,
+    /// Claims entries that are not pending yet, as long as they are in the stream.
+    pub(crate) force: bool,
+    /// Leaves the delivery count as is.
+    pub(crate) just_id: bool,
+}
+
+/// The state of a consumer group: the last entry it was delivered, and the entries its
+/// consumers have yet to acknowledge.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Group {
+    last_delivered: StreamId,
+    pending: BTreeMap<StreamId, Pending>,
+    consumers: HashSet<String>,
+}
+
+/// An append-only log of entries, each made of field-value pairs.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Stream {
+    entries: BTreeMap<StreamId, Fields>,
+    /// The greatest ID ever added. New IDs must exceed it, even once its entry is trimmed.
+    last_id: StreamId,
+    groups: HashMap<String, Group>,
+}
+
+impl fmt::Display for StreamId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.ms, self.seq)
+    }
+}
+
+impl StreamId {
+    pub(crate) const MAX: StreamId = StreamId {
+        ms: u64::MAX,
+        seq: u64::MAX,
+    };
+
+    pub(crate) fn new(ms: u64, seq: u64) -> Self {
+        Self { ms, seq }
+    }
+}
+
+/// The items of `map` keyed between `start` and `end`. Ranges ending before they start are
+/// empty.
+fn range<V>(
+    map: &BTreeMap<StreamId, V>,
+    start: Bound<StreamId>,
+    end: Bound<StreamId>,
+) -> impl DoubleEndedIterator<Item = (&StreamId, &V)> {
+    let empty = match (start, end) {
+        (Bound::Excluded(start), Bound::Excluded(end)) => start >= end,
+        (
+            Bound::Included(start) | Bound::Excluded(start),
+            Bound::Included(end) | Bound::Excluded(end),
+        ) => start > end,
+        _ => false,
+    };
+
+    // `BTreeMap::range` panics on such ranges
+    let (start, end) = match empty {
+        true => (Bound::Excluded(StreamId::MAX), Bound::Unbounded),
+        false => (start, end),
+    };
+
+    map.range((start, end))
+}
+
+fn owned((id, fields): (&StreamId, &Fields)) -> StreamEntry {
+    (*id, fields.clone())
+}
+
+impl Group {
+    pub(crate) fn set_last_delivered(&mut self, id: StreamId) {
+        self.last_delivered = id;
+    }
+
+    /// Adds a consumer. Returns whether it is new.
+    pub(crate) fn create_consumer(&mut self, consumer: &str) -> bool {
+        self.consumers.insert(consumer.to_string())
+    }
+
+    /// Removes a consumer along with its pending entries. Returns how many entries it had
+    /// pending, `None` if it did not exist.
+    pub(crate) fn delete_consumer(&mut self, consumer: &str) -> Option<usize> {
+        if !self.consumers.remove(consumer) {
+            return None;
+        }
+
+        let before = self.pending.len();
+        self.pending
+            .retain(|_, pending| pending.consumer != consumer);
+        Some(before - self.pending.len())
+    }
+
+    /// Acknowledges entries, removing them from the pending entries list. Returns how many
+    /// were pending.
+    pub(crate) fn ack(&mut self, ids: &[StreamId]) -> usize {
+        ids.iter()
+            .filter(|id| self.pending.remove(id).is_some())
+            .count()
+    }
+
+    /// The pending entries between `start` and `end`, in order.
+    pub(crate) fn pending(
+        &self,
+        start: Bound<StreamId>,
+        end: Bound<StreamId>,
+    ) -> impl DoubleEndedIterator<Item = (&StreamId, &Pending)> {
+        range(&self.pending, start, end)
+    }
+
+    /// The number of pending entries of each consumer that has any.
+    pub(crate) fn pending_by_consumer(&self) -> BTreeMap<&str, usize> {
+        let mut counts = BTreeMap::new();
+
+        for pending in self.pending.values() {
+            *counts.entry(pending.consumer.as_str()).or_default() += 1;
+        }
+
+        counts
+    }
+
+    pub(crate) fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+impl Stream {
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub(crate) fn last_id(&self) -> StreamId {
+        self.last_id
+    }
+
+    /// Adds an entry. Returns its ID, `None` if it would not exceed the last ID.
+    pub(crate) fn add(&mut self, id: NewId, fields: Fields, now_ms: u64) -> Option<StreamId> {
+        let last = self.last_id;
+        let next_seq = || {
+            last.seq
+                .checked_add(1)
+                .map(|seq| StreamId::new(last.ms, seq))
+        };
+
+        let id = match id {
+            NewId::Auto if now_ms > last.ms => StreamId::new(now_ms, 0),
+            // the clock went back, or several entries are added within a millisecond
+            NewId::Auto => {
+                next_seq().or_else(|| Some(StreamId::new(last.ms.checked_add(1)?, 0)))?
+            }
+            NewId::AutoSeq(ms) if ms > last.ms => StreamId::new(ms, 0),
+            NewId::AutoSeq(ms) if ms == last.ms => next_seq()?,
+            NewId::AutoSeq(_) => return None,
+            NewId::Exact(id) => id,
+        };
+
+        if id <= last {
+            return None;
+        }
+
+        self.entries.insert(id, fields);
+        self.last_id = id;
+        Some(id)
+    }
+
+    /// Evicts the oldest entries as `trim` describes, at most `limit` of them. Returns how
+    /// many were evicted.
+    pub(crate) fn trim(&mut self, trim: Trim, limit: Option<usize>) -> usize {
+        let mut evicted = 0;
+
+        while evicted < limit.unwrap_or(usize::MAX) {
+            let excess = match trim {
+                Trim::MaxLen(len) => self.entries.len() > len,
+                Trim::MinId(id) => self.entries.first_key_value().is_some_and(|(f, _)| *f < id),
+            };
+
+            if !excess {
+                break;
+            }
+
+            self.entries.pop_first();
+            evicted += 1;
+        }
+
+        evicted
+    }
+
+    /// The entries between `start` and `end`, in order.
+    pub(crate) fn range(
+        &self,
+        start: Bound<StreamId>,
+        end: Bound<StreamId>,
+    ) -> impl DoubleEndedIterator<Item = (&StreamId, &Fields)> {
+        range(&self.entries, start, end)
+    }
+
+    /// At most `count` of the entries added after `id`.
+    pub(crate) fn read_after(&self, id: StreamId, count: Option<usize>) -> Vec<StreamEntry> {
+        self.range(Bound::Excluded(id), Bound::Unbounded)
+            .take(count.unwrap_or(usize::MAX))
+            .map(owned)
+            .collect()
+    }
+
+    pub(crate) fn group(&self, group: &str) -> Option<&Group> {
+        self.groups.get(group)
+    }
+
+    pub(crate) fn group_mut(&mut self, group: &str) -> Option<&mut Group> {
+        self.groups.get_mut(group)
+    }
+
+    /// Creates a group that is delivered the entries after `last_delivered`. Returns whether
+    /// the group is new.
+    pub(crate) fn create_group(&mut self, group: &str, last_delivered: StreamId) -> bool {
+        if self.groups.contains_key(group) {
+            return false;
+        }
+
+        let state = Group {
+            last_delivered,
+            ..Default::default()
+        };
+
+        self.groups.insert(group.to_string(), state);
+        true
+    }
+
+    /// Removes a group. Returns whether it existed.
+    pub(crate) fn destroy_group(&mut self, group: &str) -> bool {
+        self.groups.remove(group).is_some()
+    }
+
+    /// Reads at most `count` entries for a consumer of a group, which is created if needed.
+    /// Without an `after` ID, the entries never delivered to the group are delivered to the
+    /// consumer. Otherwise the consumer reads back its own pending entries after that ID,
+    /// entries trimmed from the stream meanwhile being dropped from the pending list.
+    ///
+    /// Returns `None` if the group does not exist.
+    pub(crate) fn read_group(
+        &mut self,
+        read: &GroupRead,
+        after: Option<StreamId>,
+        count: Option<usize>,
+        now: Instant,
+    ) -> Option<Vec<StreamEntry>> {
+        let group = self.groups.get_mut(&read.group)?;
+        let count = count.unwrap_or(usize::MAX);
+        group.create_consumer(&read.consumer);
+
+        let Some(after) = after else {
+            let entries: Vec<_> = range(
+                &self.entries,
+                Bound::Excluded(group.last_delivered),
+                Bound::Unbounded,
+            )
+            .take(count)
+            .map(owned)
+            .collect();
+
+            if let Some((last, _)) = entries.last() {
+                group.last_delivered = *last;
+            }
+
+            if read.noack {
+                return Some(entries);
+            }
+
+            for (id, _) in &entries {
+                let deliveries = group.pending.get(id).map_or(0, |p| p.deliveries);
+
+                group.pending.insert(
+                    *id,
+                    Pending {
+                        consumer: read.consumer.clone(),
+                        delivered: now,
+                        deliveries: deliveries + 1,
+                    },
+                );
+            }
+
+            return Some(entries);
+        };
+
+        let ids: Vec<_> = group
+            .pending
+            .range((Bound::Excluded(after), Bound::Unbounded))
+            .filter(|(_, pending)| pending.consumer == read.consumer)
+            .map(|(id, _)| *id)
+            .collect();
+
+        let mut entries = vec![];
+
+        for id in ids {
+            if entries.len() == count {
+                break;
+            }
+
+            match self.entries.get(&id) {
+                Some(fields) => entries.push((id, fields.clone())),
+                None => {
+                    group.pending.remove(&id);
+                }
+            }
+        }
+
+        Some(entries)
+    }
+
+    /// Hands the pending entries at `ids` that have been idle for long enough to
+    /// `consumer`, which is created if needed. Entries trimmed from the stream are dropped
+    /// from the pending list instead. Returns the entries claimed, `None` if the group does
+    /// not exist.
+    pub(crate) fn claim(
+        &mut self,
+        group: &str,
+        consumer: &str,
+        ids: &[StreamId],
+        options: ClaimOptions,
+        now: Instant,
+    ) -> Option<Vec<StreamEntry>> {
+        let group = self.groups.get_mut(group)?;
+        group.create_consumer(consumer);
+
+        let delivered = now
+            .checked_sub(options.idle.unwrap_or_default())
+            .unwrap_or(now);
+        let mut claimed = vec![];
+
+        for id in ids {
+            let Some(fields) = self.entries.get(id) else {
+                group.pending.remove(id);
+                continue;
+            };
+
+            let pending = match group.pending.get_mut(id) {
+                Some(pending) if now.duration_since(pending.delivered) < options.min_idle => {
+                    continue
+                }
+                Some(pending) => pending,
+                None if options.force => group.pending.entry(*id).or_insert(Pending {
+                    consumer: consumer.to_string(),
+                    delivered,
+                    deliveries: 0,
+                }),
+                None => continue,
+            };
+
+            pending.consumer = consumer.to_string();
+            pending.delivered = delivered;
+
+            match options.retry_count {
+                Some(count) => pending.deliveries = count,
+                None if !options.just_id => pending.deliveries += 1,
+                None => {}
+            }
+
+            claimed.push((*id, fields.clone()));
+        }
+
+        Some(claimed)
+    }
+
+    /// Claims at most `count` of the pending entries from `start` on that have been idle
+    /// for long enough, as `claim` does with `options`. Returns the ID to continue from, the
+    /// entries claimed and the IDs of the entries trimmed from the stream, `None` if the
+    /// group does not exist. The ID to continue from is `0-0` once every entry was scanned.
+    pub(crate) fn auto_claim(
+        &mut self,
+        group: &str,
+        consumer: &str,
+        start: StreamId,
+        count: usize,
+        options: ClaimOptions,
+        now: Instant,
+    ) -> Option<(StreamId, Vec<StreamEntry>, Vec<StreamId>)> {
+        let pending = &mut self.groups.get_mut(group)?.pending;
+        let (mut ids, mut deleted, mut next) = (vec![], vec![], StreamId::default());
+
+        for (id, entry) in pending.range(start..) {
+            if ids.len() == count {
+                next = *id;
+                break;
+            }
+
+            if !self.entries.contains_key(id) {
+                deleted.push(*id);
+            } else if now.duration_since(entry.delivered) >= options.min_idle {
+                ids.push(*id);
+            }
+        }
+
+        for id in &deleted {
+            pending.remove(id);
+        }
+
+        let claimed = self.claim(group, consumer, &ids, options, now)?;
+        Some((next, claimed, deleted))
+    }
+}
@@ -0,0 +1,81 @@
+/// Whether `text` matches the glob-style `pattern`, as Redis matches keys and channels.
+///
+/// `*` matches any sequence, `?` any single byte and `[...]` any byte of a class, which may
+/// hold ranges such as `a-z` and is negated by a leading `^`. A backslash matches the byte it
+/// precedes literally.
+pub(crate) fn matches(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // where the last star was followed in the pattern, and the text it has consumed up to
+    let mut star = None;
+
+    while t < text.len() {
+        if pattern.get(p) == Some(&b'*') {
+            p += 1;
+            star = Some((p, t));
+            continue;
+        }
+
+        if let Some(next) = match_one(pattern, p, text[t]) {
+            p = next;
+            t += 1;
+            continue;
+        }
+
+        // the last star takes one more byte, and matching resumes after it
+        let Some((star_p, star_t)) = star else {
+            return false;
+        };
+
+        p = star_p;
+        t = star_t + 1;
+        star = Some((star_p, t));
+    }
+
+    pattern[p..].iter().all(|c| *c == b'*')
+}
+
+/// Matches `c` against the part of `pattern` at `p` matching a single byte. Returns where the
+/// pattern continues if it matches.
+fn match_one(pattern: &[u8], p: usize, c: u8) -> Option<usize> {
+    match *pattern.get(p)? {
+        b'?' => Some(p + 1),
+        b'\\' if p + 1 < pattern.len() => (pattern[p + 1] == c).then_some(p + 2),
+        b'[' => match_class(pattern, p + 1, c),
+        other => (other == c).then_some(p + 1),
+    }
+}
+
+/// Matches `c` against the class opened just before `p`. An unterminated class ends with the
+/// pattern.
+fn match_class(pattern: &[u8], mut p: usize, c: u8) -> Option<usize> {
+    let negate = pattern.get(p) == Some(&b'^');
+    let mut matched = false;
+
+    if negate {
+        p += 1;
+    }
+
+    while let Some(&first) = pattern.get(p) {
+        match first {
+            b']' => {
+                p += 1;
+                break;
+            }
+            b'\\' if p + 1 < pattern.len() => {
+                matched |= pattern[p + 1] == c;
+                p += 2;
+            }
+            _ if pattern.get(p + 1) == Some(&b'-') && p + 2 < pattern.len() => {
+                let (low, high) = (first.min(pattern[p + 2]), first.max(pattern[p + 2]));
+                matched |= (low..=high).contains(&c);
+                p += 3;
+            }
+            _ => {
+                matched |= first == c;
+                p += 1;
+            }
+        }
+    }
+
+    (matched != negate).then_some(p)
+}
@@ -1,4 +1,12 @@
-use crate::connection::Connection;
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+use crate::{
+    commands::{self, Execute},
+    connection::Connection,
+    error::DatabaseError,
+    frame::Frame,
+};
 
 use super::{database::database::Database, shutdown_listener::ShutdownListener};
 
@@ -8,29 +16,71 @@ pub(super) struct Handler {
     /// Tcp connection with encoding/decoding capabilities
     pub(super) connection: Connection,
 
-    ///
+    /// Notified when the server shuts down, after which the connection is closed
     pub(super) shutdown_listener: ShutdownListener,
+
+    /// Never used to send. The server knows every connection has closed once all senders
+    /// have been dropped.
+    pub(super) _shutdown_complete: mpsc::Sender<()>,
 }
 
 impl Handler {
     pub(super) async fn run(&mut self) -> anyhow::Result<()> {
         while !self.shutdown_listener.has_shutdown() {
             let frame_opt = tokio::select! {
-                fr = self.connection.read_frame() => fr?,
+                fr = self.connection.read_frame() => reject_malformed(&mut self.connection, fr).await?,
                 _ = self.shutdown_listener.subscribe() => {
                     return Ok(());
                 }
             };
 
-            let frame = if let Some(frame) = frame_opt {
-                frame
-            } else {
-                return Ok(());
+            let frame = match frame_opt {
+                // empty requests are ignored
+                Some(Frame::Array(args)) if args.is_empty() => continue,
+                Some(frame) => frame,
+                None => return Ok(()),
+            };
+
+            debug!(%frame, client = self.connection.name(), "received request");
+
+            let cmd = match commands::from_frame(frame) {
+                Ok(cmd) => cmd,
+                Err(e) => {
+                    debug!(error = %e, "rejected request");
+                    self.connection
+                        .write_frame(&Frame::Error(e.to_string()))
+                        .await?;
+                    continue;
+                }
             };
 
-            todo!("Finish command struct")
+            cmd.execute(
+                &self.database,
+                &mut self.connection,
+                &mut self.shutdown_listener,
+            )
+            .await?;
         }
 
         Ok(())
     }
 }
+
+/// Malformed frames leave the read buffer in an unknown state, so the client is told why
+/// before the connection is closed.
+pub(crate) async fn reject_malformed(
+    conn: &mut Connection,
+    frame: Result<Option<Frame>, DatabaseError>,
+) -> anyhow::Result<Option<Frame>> {
+    match frame {
+        Err(DatabaseError::FrameError(e)) => {
+            warn!(error = %e, "closing connection after a protocol error");
+
+            let res = Frame::Error(format!("ERR {e}"));
+            let _ = conn.write_frame(&res).await;
+
+            Err(e.into())
+        }
+        frame => Ok(frame?),
+    }
+}
@@ -4,17 +4,16 @@ use tokio::{
     net::{TcpListener, TcpStream},
     sync::{broadcast, mpsc, Semaphore},
 };
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
-use crate::connection::Connection;
+use crate::{codec::ProtocolLimits, connection::Connection, frame::Frame};
 
 use super::{
     database::database_guard::DatabaseGuard, handler::Handler, shutdown_listener::ShutdownListener,
 };
 
-#[allow(unused)]
 #[derive(Debug)]
-struct Listener {
+pub(super) struct Listener {
     /// Shared database handle
     db_owner: DatabaseGuard,
 
@@ -23,7 +22,7 @@ struct Listener {
 
     /// Mechanism to limit number of concurrent connections
     ///
-    /// When a processing a connection is complete, a permit is returned to the semaphore
+    /// Every connection holds a permit until it is closed, when it is returned to the semaphore
     connection_limit: Arc<Semaphore>,
 
     /// Server shutdown broadcast channel
@@ -31,31 +30,98 @@ struct Listener {
 
     /// Channel need to ensure all connections are processed before server shutdown
     shutdown_complete_channel: mpsc::Sender<()>,
+
+    /// Limits every client connection is held to
+    protocol_limits: ProtocolLimits,
 }
 
 impl Listener {
+    pub(super) fn new(
+        db_owner: DatabaseGuard,
+        listener: TcpListener,
+        max_connections: usize,
+        protocol_limits: ProtocolLimits,
+        shutdown_notifier: broadcast::Sender<()>,
+        shutdown_complete_channel: mpsc::Sender<()>,
+    ) -> Self {
+        Self {
+            db_owner,
+            listener,
+            connection_limit: Arc::new(Semaphore::new(max_connections)),
+            shutdown_notifier,
+            shutdown_complete_channel,
+            protocol_limits,
+        }
+    }
+
     pub(super) async fn run(&mut self) -> anyhow::Result<()> {
         info!("Server is live. Awaiting inbound connections.");
 
         loop {
-            let permit = self.connection_limit.clone().acquire_owned().await.unwrap();
-
             let socket = self.accept().await?;
 
+            // clients over the cap are turned away rather than left waiting for a free slot
+            let Ok(permit) = self.connection_limit.clone().try_acquire_owned() else {
+                warn!("rejecting client, max number of clients reached");
+                tokio::spawn(async move {
+                    let res = Frame::Error("ERR max number of clients reached".to_string());
+                    let _ = Connection::new(socket).write_frame(&res).await;
+                });
+                continue;
+            };
+
+            let database = self.db_owner.inner();
+            database.client_connected();
+
             let mut handler = Handler {
-                database: self.db_owner.inner(),
-                connection: Connection::new(socket),
+                database,
+                connection: Connection::with_limits(socket, self.protocol_limits),
                 shutdown_listener: ShutdownListener::new(self.shutdown_notifier.subscribe()),
+                _shutdown_complete: self.shutdown_complete_channel.clone(),
             };
 
             tokio::spawn(async move {
                 if let Err(e) = handler.run().await {
                     error!(error = ?e, "connection handler failed")
                 }
+
+                // the slot is only given back once the connection is done with
+                handler.database.client_disconnected();
+                drop(permit);
             });
+        }
+    }
 
-            drop(permit)
+    /// Tells every connection to close and waits, at most `deadline`, for their handlers to
+    /// finish. The database's background tasks are halted afterwards, when the guard is dropped.
+    pub(super) async fn shutdown(
+        self,
+        mut shutdown_complete_rx: mpsc::Receiver<()>,
+        deadline: Duration,
+    ) {
+        let Self {
+            db_owner,
+            shutdown_notifier,
+            shutdown_complete_channel,
+            ..
+        } = self;
+
+        // closing the broadcast channel notifies every `ShutdownListener`
+        drop(shutdown_notifier);
+        drop(shutdown_complete_channel);
+
+        // `recv` returns once the last handler has dropped its sender
+        if tokio::time::timeout(deadline, shutdown_complete_rx.recv())
+            .await
+            .is_err()
+        {
+            warn!(
+                ?deadline,
+                "connections still open after the shutdown timeout"
+            );
         }
+
+        drop(db_owner);
     }
 
     async fn accept(&mut self) -> anyhow::Result<TcpStream> {
@@ -1 +1,12 @@
+pub(crate) mod codec;
+#[cfg(feature = "server")]
+pub(crate) mod commands;
+#[cfg(feature = "server")]
+pub(crate) mod config;
+#[cfg(feature = "server")]
+pub(crate) mod database;
 pub(crate) mod frame;
+#[cfg(feature = "server")]
+pub(crate) mod glob;
+#[cfg(feature = "server")]
+pub(crate) mod server;
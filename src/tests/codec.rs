@@ -0,0 +1,195 @@
+use crate::{
+    codec::{FrameCodec, Protocol, ProtocolLimits},
+    frame::{Frame, FrameError},
+};
+use bytes::{Bytes, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+fn encode(protocol: Protocol, frame: &Frame) -> BytesMut {
+    let mut codec = FrameCodec::default();
+    codec.set_protocol(protocol);
+
+    let mut dst = BytesMut::new();
+    codec.encode(frame, &mut dst).unwrap();
+    dst
+}
+
+fn sample() -> Frame {
+    Frame::Push(vec![
+        Frame::Simple("OK".to_string()),
+        Frame::Integer(-7),
+        Frame::Bulk(Bytes::from_static(b"bulk")),
+        Frame::Null,
+        Frame::Boolean(true),
+        Frame::Double(1.5),
+        Frame::BigNumber("123456789012345678901234567890".to_string()),
+        Frame::BulkError(Bytes::from_static(b"ERR some\r\nerror")),
+        Frame::Verbatim("txt".to_string(), Bytes::from_static(b"text")),
+        Frame::Map(vec![(
+            Frame::Bulk(Bytes::from_static(b"key")),
+            Frame::Set(vec![Frame::Integer(1)]),
+        )]),
+        Frame::Attribute(
+            vec![(Frame::Simple("ttl".to_string()), Frame::Integer(10))],
+            Box::new(Frame::Array(vec![])),
+        ),
+    ])
+}
+
+#[test]
+fn encode_resp3() {
+    assert_eq!(
+        &encode(Protocol::Resp3, &sample())[..],
+        &b">11\r\n+OK\r\n:-7\r\n$4\r\nbulk\r\n_\r\n#t\r\n,1.5\r\n\
+        (123456789012345678901234567890\r\n!15\r\nERR some\r\nerror\r\n=8\r\ntxt:text\r\n\
+        %1\r\n$3\r\nkey\r\n~1\r\n:1\r\n|1\r\n+ttl\r\n:10\r\n*0\r\n"[..]
+    );
+    assert_eq!(
+        &encode(Protocol::Resp3, &Frame::Double(f64::NEG_INFINITY))[..],
+        &b",-inf\r\n"[..]
+    );
+    assert_eq!(
+        &encode(Protocol::Resp3, &Frame::NullArray)[..],
+        &b"_\r\n"[..]
+    );
+}
+
+#[test]
+fn encode_resp2_down_converts() {
+    assert_eq!(
+        &encode(Protocol::Resp2, &sample())[..],
+        &b"*11\r\n+OK\r\n:-7\r\n$4\r\nbulk\r\n$-1\r\n:1\r\n$3\r\n1.5\r\n\
+        $30\r\n123456789012345678901234567890\r\n-ERR some  error\r\n$4\r\ntext\r\n\
+        *2\r\n$3\r\nkey\r\n*1\r\n:1\r\n*0\r\n"[..]
+    );
+
+    // a missing array keeps its own null
+    assert_eq!(
+        &encode(Protocol::Resp2, &Frame::NullArray)[..],
+        &b"*-1\r\n"[..]
+    );
+}
+
+#[test]
+fn round_trip() {
+    let frame = sample();
+    let mut buf = encode(Protocol::Resp3, &frame);
+
+    assert_eq!(FrameCodec::default().decode(&mut buf).unwrap(), Some(frame));
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn decode_incrementally() {
+    let frame = sample();
+    let encoded = encode(Protocol::Resp3, &frame);
+
+    let mut codec = FrameCodec::default();
+    let mut buf = BytesMut::new();
+
+    // feed the frame one byte at a time, everything but the last byte is incomplete
+    for (i, b) in encoded.iter().enumerate() {
+        buf.extend_from_slice(&[*b]);
+        let decoded = codec.decode_frame(&mut buf).unwrap();
+
+        if i + 1 < encoded.len() {
+            assert_eq!(decoded, None);
+        } else {
+            assert_eq!(decoded, Some(frame.clone()));
+        }
+    }
+
+    assert!(!codec.has_partial_frame());
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn decode_pipelined() {
+    let mut codec = FrameCodec::default();
+    let mut buf = BytesMut::from(&b"*1\r\n$4\r\nPING\r\nPING\r\n*2\r\n$3\r\nGET"[..]);
+
+    let ping = Frame::Array(vec![Frame::Bulk(Bytes::from_static(b"PING"))]);
+
+    assert_eq!(codec.decode_frame(&mut buf), Ok(Some(ping.clone())));
+    assert_eq!(codec.decode_frame(&mut buf), Ok(Some(ping)));
+    assert_eq!(codec.decode_frame(&mut buf), Ok(None));
+    assert!(codec.has_partial_frame());
+
+    buf.extend_from_slice(b"\r\n$1\r\nk\r\n");
+    assert_eq!(
+        codec.decode_frame(&mut buf),
+        Ok(Some(Frame::Array(vec![
+            Frame::Bulk(Bytes::from_static(b"GET")),
+            Frame::Bulk(Bytes::from_static(b"k")),
+        ])))
+    );
+}
+
+fn limited() -> FrameCodec {
+    FrameCodec::with_limits(ProtocolLimits {
+        max_bulk_len: 8,
+        max_elements: 4,
+        max_depth: 2,
+        max_line_len: 16,
+    })
+}
+
+fn exceeds_limit(result: Result<Option<Frame>, FrameError>) -> bool {
+    matches!(result, Err(FrameError::LimitExceeded(_)))
+}
+
+#[test]
+fn limit_bulk_length() {
+    let mut buf = BytesMut::from(&b"$8\r\n12345678\r\n"[..]);
+    assert!(limited().decode_frame(&mut buf).unwrap().is_some());
+
+    // rejected from the header alone, before the payload is buffered
+    let mut buf = BytesMut::from(&b"$999999999999\r\n"[..]);
+    assert!(exceeds_limit(limited().decode_frame(&mut buf)));
+    assert!(buf.capacity() < 1024);
+
+    // headers within the limits only reserve a bounded part of the payload up front
+    let mut codec = FrameCodec::default();
+    let mut buf = BytesMut::from(&b"$536870911\r\n"[..]);
+    assert_eq!(codec.decode_frame(&mut buf), Ok(None));
+    assert!(buf.capacity() <= 128 * 1024);
+}
+
+#[test]
+fn limit_elements() {
+    let mut buf = BytesMut::from(&b"*4\r\n:1\r\n:2\r\n:3\r\n:4\r\n"[..]);
+    assert!(limited().decode_frame(&mut buf).unwrap().is_some());
+
+    let mut buf = BytesMut::from(&b"*5\r\n"[..]);
+    assert!(exceeds_limit(limited().decode_frame(&mut buf)));
+
+    let mut buf = BytesMut::from(&b"%3\r\n"[..]);
+    assert!(exceeds_limit(limited().decode_frame(&mut buf)));
+}
+
+#[test]
+fn limit_depth() {
+    let mut buf = BytesMut::from(&b"*1\r\n*1\r\n:1\r\n"[..]);
+    assert!(limited().decode_frame(&mut buf).unwrap().is_some());
+
+    let mut buf = BytesMut::from(&b"*1\r\n*1\r\n*1\r\n:1\r\n"[..]);
+    assert!(exceeds_limit(limited().decode_frame(&mut buf)));
+
+    // the default limits also stop pathological nesting
+    let mut buf = BytesMut::from("*1\r\n".repeat(100_000).as_bytes());
+    assert!(exceeds_limit(FrameCodec::default().decode_frame(&mut buf)));
+}
+
+#[test]
+fn limit_line_length() {
+    let mut buf = BytesMut::from(&b"+0123456789abcdef\r\n"[..]);
+    assert!(exceeds_limit(limited().decode_frame(&mut buf)));
+
+    // unterminated lines are rejected once they outgrow the limit
+    let mut codec = limited();
+    let mut buf = BytesMut::from(&b"PING PING PING"[..]);
+    assert_eq!(codec.decode_frame(&mut buf), Ok(None));
+
+    buf.extend_from_slice(b" PING");
+    assert!(exceeds_limit(codec.decode_frame(&mut buf)));
+}
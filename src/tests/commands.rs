@@ -0,0 +1,473 @@
+use crate::{
+    commands::{from_frame, CommandError, SupportedCommand},
+    frame::Frame,
+};
+use bytes::Bytes;
+
+fn request(args: &[&str]) -> Frame {
+    Frame::Array(
+        args.iter()
+            .map(|a| Frame::Bulk(Bytes::from(a.to_string())))
+            .collect(),
+    )
+}
+
+fn reply(args: &[&str]) -> String {
+    from_frame(request(args))
+        .err()
+        .map(|e| e.to_string())
+        .unwrap_or_default()
+}
+
+#[test]
+fn dispatch_is_case_insensitive() {
+    assert!(matches!(
+        from_frame(request(&["PiNg"])),
+        Ok(SupportedCommand::Ping(_))
+    ));
+    assert!(matches!(
+        from_frame(request(&["GET", "key"])),
+        Ok(SupportedCommand::Get(_))
+    ));
+}
+
+#[test]
+fn unknown_command() {
+    assert!(matches!(
+        from_frame(request(&["foo", "a", "b"])),
+        Err(CommandError::UnknownCommand(..))
+    ));
+    assert_eq!(
+        reply(&["foo", "a", "b"]),
+        "ERR unknown command 'foo', with args beginning with: 'a' 'b' "
+    );
+}
+
+#[test]
+fn wrong_arity() {
+    assert_eq!(
+        reply(&["get"]),
+        "ERR wrong number of arguments for 'get' command"
+    );
+    assert_eq!(
+        reply(&["GET", "a", "b"]),
+        "ERR wrong number of arguments for 'get' command"
+    );
+    assert_eq!(
+        reply(&["publish", "channel"]),
+        "ERR wrong number of arguments for 'publish' command"
+    );
+}
+
+#[test]
+fn invalid_argument() {
+    assert_eq!(
+        reply(&["set", "key", "value", "ex", "ten"]),
+        "ERR value is not an integer or out of range"
+    );
+    assert!(matches!(
+        from_frame(Frame::Simple("PING".to_string())),
+        Err(CommandError::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn expire_options() {
+    assert!(matches!(
+        from_frame(request(&["expire", "key", "10", "xx", "gt"])),
+        Ok(SupportedCommand::Expire(_))
+    ));
+    assert_eq!(
+        reply(&["pexpire", "key", "10", "nx", "xx"]),
+        "ERR NX and XX, GT or LT options at the same time are not compatible"
+    );
+    assert_eq!(
+        reply(&["expireat", "key", "10", "gt", "lt"]),
+        "ERR GT and LT options at the same time are not compatible"
+    );
+    assert_eq!(
+        reply(&["expire", "key", "10", "ever"]),
+        "ERR Unsupported option ever"
+    );
+    assert_eq!(reply(&["copy", "a", "b", "db", "1"]), "ERR syntax error");
+    assert_eq!(
+        reply(&["del"]),
+        "ERR wrong number of arguments for 'del' command"
+    );
+}
+
+#[test]
+fn set_options() {
+    for args in [
+        &["set", "k", "v", "nx", "px", "30000"][..],
+        &["set", "k", "v", "px", "30000", "nx", "get"],
+        &["set", "k", "v", "get", "xx", "keepttl"],
+        &["set", "k", "v", "exat", "1700000000"],
+    ] {
+        assert!(matches!(
+            from_frame(request(args)),
+            Ok(SupportedCommand::Set(_))
+        ));
+    }
+
+    assert_eq!(reply(&["set", "k", "v", "nx", "xx"]), "ERR syntax error");
+    assert_eq!(
+        reply(&["set", "k", "v", "ex", "10", "keepttl"]),
+        "ERR syntax error"
+    );
+    assert_eq!(
+        reply(&["set", "k", "v", "ex", "10", "px", "10"]),
+        "ERR syntax error"
+    );
+    assert_eq!(reply(&["set", "k", "v", "ttl", "10"]), "ERR syntax error");
+    assert_eq!(
+        reply(&["set", "k", "v", "px", "0"]),
+        "ERR invalid expire time in 'set' command"
+    );
+}
+
+#[test]
+fn counter_arguments() {
+    assert!(matches!(
+        from_frame(request(&["incrbyfloat", "k", "1.5e3"])),
+        Ok(SupportedCommand::IncrByFloat(_))
+    ));
+    assert_eq!(
+        reply(&["incrbyfloat", "k", "inf"]),
+        "ERR value is not a valid float"
+    );
+    assert_eq!(
+        reply(&["incrby", "k", "1.5"]),
+        "ERR value is not an integer or out of range"
+    );
+}
+
+#[test]
+fn multi_key_arguments() {
+    assert!(matches!(
+        from_frame(request(&["mset", "a", "1", "b", "2"])),
+        Ok(SupportedCommand::MSet(_))
+    ));
+    assert_eq!(
+        reply(&["msetnx", "a", "1", "b"]),
+        "ERR wrong number of arguments for 'msetnx' command"
+    );
+    assert_eq!(
+        reply(&["mget"]),
+        "ERR wrong number of arguments for 'mget' command"
+    );
+
+    for args in [
+        &["getex", "k"][..],
+        &["getex", "k", "persist"],
+        &["getex", "k", "PXAT", "1700000000000"],
+    ] {
+        assert!(matches!(
+            from_frame(request(args)),
+            Ok(SupportedCommand::GetEx(_))
+        ));
+    }
+
+    assert_eq!(reply(&["getex", "k", "keepttl"]), "ERR syntax error");
+    assert_eq!(
+        reply(&["getex", "k", "ex", "10", "persist"]),
+        "ERR syntax error"
+    );
+    assert_eq!(
+        reply(&["getex", "k", "ex", "0"]),
+        "ERR invalid expire time in 'getex' command"
+    );
+}
+
+#[test]
+fn bit_arguments() {
+    for args in [
+        &["bitcount", "k", "0", "-1", "BIT"][..],
+        &["bitpos", "k", "0", "2"],
+        &["bitop", "XOR", "dest", "a", "b"],
+        &[
+            "bitfield", "k", "get", "i64", "#2", "overflow", "fail", "incrby", "u63", "0", "1",
+        ],
+    ] {
+        assert!(from_frame(request(args)).is_ok(), "{args:?}");
+    }
+
+    assert_eq!(reply(&["bitcount", "k", "0"]), "ERR syntax error");
+    assert_eq!(
+        reply(&["bitpos", "k", "2"]),
+        "ERR The bit argument must be 1 or 0."
+    );
+    assert_eq!(
+        reply(&["setbit", "k", "-1", "1"]),
+        "ERR bit offset is not an integer or out of range"
+    );
+    assert_eq!(
+        reply(&["bitop", "not", "dest", "a", "b"]),
+        "ERR BITOP NOT must be called with a single source key."
+    );
+    assert_eq!(
+        reply(&["bitfield", "k", "get", "u64", "0"]),
+        "ERR Invalid bitfield type. Use something like i16 u8. Note that u64 is not supported \
+         but i64 is."
+    );
+    assert_eq!(
+        reply(&["setrange", "k", "-1", "v"]),
+        "ERR offset is out of range"
+    );
+}
+
+#[test]
+fn list_arguments() {
+    for args in [
+        &["rpush", "k", "a", "b"][..],
+        &["lpop", "k"],
+        &["rpop", "k", "3"],
+        &["lmove", "a", "b", "LEFT", "right"],
+        &["lrem", "k", "-2", "v"],
+        &["blpop", "a", "b", "0"],
+        &["brpop", "k", "0.5"],
+        &["blmove", "a", "b", "left", "LEFT", "1"],
+    ] {
+        assert!(from_frame(request(args)).is_ok(), "{args:?}");
+    }
+
+    assert_eq!(
+        reply(&["lpush", "k"]),
+        "ERR wrong number of arguments for 'lpush' command"
+    );
+    assert_eq!(
+        reply(&["lpop", "k", "-1"]),
+        "ERR value is out of range, must be positive"
+    );
+    assert_eq!(
+        reply(&["lmove", "a", "b", "up", "left"]),
+        "ERR syntax error"
+    );
+    assert_eq!(
+        reply(&["blpop", "k"]),
+        "ERR wrong number of arguments for 'blpop' command"
+    );
+    assert_eq!(reply(&["brpop", "k", "-1"]), "ERR timeout is negative");
+    assert_eq!(
+        reply(&["blmove", "a", "b", "left", "left", "soon"]),
+        "ERR timeout is not a float or out of range"
+    );
+}
+
+#[test]
+fn hash_arguments() {
+    for args in [
+        &["hset", "k", "f", "v", "g", "w"][..],
+        &["hmget", "k", "f", "g"],
+        &["hincrby", "k", "f", "-3"],
+        &["hscan", "k", "0", "MATCH", "f*", "COUNT", "5", "NOVALUES"],
+        &["hexpire", "k", "10", "nx", "FIELDS", "2", "f", "g"],
+        &["httl", "k", "fields", "1", "f"],
+        &["hpersist", "k", "FIELDS", "1", "f"],
+    ] {
+        assert!(from_frame(request(args)).is_ok(), "{args:?}");
+    }
+
+    assert_eq!(
+        reply(&["hset", "k", "f"]),
+        "ERR wrong number of arguments for 'hset' command"
+    );
+    assert_eq!(reply(&["hscan", "k", "x"]), "ERR invalid cursor");
+    assert_eq!(
+        reply(&["hexpire", "k", "-1", "FIELDS", "1", "f"]),
+        "ERR invalid expire time, must be >= 0"
+    );
+    assert_eq!(
+        reply(&["httl", "k", "f"]),
+        "ERR Mandatory argument FIELDS is missing or not at the right position"
+    );
+    assert_eq!(
+        reply(&["hpersist", "k", "FIELDS", "0"]),
+        "ERR Parameter `numFields` should be greater than 0"
+    );
+    assert_eq!(
+        reply(&["httl", "k", "FIELDS", "2", "f"]),
+        "ERR The `numfields` parameter must match the number of arguments"
+    );
+}
+
+#[test]
+fn set_arguments() {
+    for args in [
+        &["sadd", "k", "a", "b"][..],
+        &["srem", "k", "a"],
+        &["sismember", "k", "a"],
+        &["srandmember", "k", "-5"],
+        &["spop", "k", "2"],
+        &["sinter", "a", "b"],
+        &["sdiffstore", "dest", "a", "b"],
+    ] {
+        assert!(from_frame(request(args)).is_ok(), "{args:?}");
+    }
+
+    assert_eq!(
+        reply(&["sadd", "k"]),
+        "ERR wrong number of arguments for 'sadd' command"
+    );
+    assert_eq!(
+        reply(&["sunionstore", "dest"]),
+        "ERR wrong number of arguments for 'sunionstore' command"
+    );
+    assert_eq!(
+        reply(&["spop", "k", "-1"]),
+        "ERR value is out of range, must be positive"
+    );
+    assert_eq!(
+        reply(&["srandmember", "k", "-9223372036854775807"]),
+        "ERR value is out of range"
+    );
+}
+
+#[test]
+fn sorted_set_arguments() {
+    for args in [
+        &["zadd", "k", "nx", "ch", "1", "a", "-inf", "b"][..],
+        &["zadd", "k", "incr", "1.5", "a"],
+        &["zincrby", "k", "2", "a"],
+        &["zrange", "k", "0", "-1", "withscores"],
+        &[
+            "zrange", "k", "(5", "0", "byscore", "rev", "limit", "0", "2",
+        ],
+        &["zrange", "k", "[a", "-", "bylex", "rev"],
+        &["zrangebyscore", "k", "-inf", "+inf", "limit", "1", "1"],
+        &["zrevrank", "k", "a", "withscore"],
+        &["bzpopmin", "a", "b", "0.5"],
+        &["zunionstore", "dest", "2", "a", "b", "weights", "2", "3"],
+        &["zinterstore", "dest", "1", "a", "aggregate", "max"],
+    ] {
+        assert!(from_frame(request(args)).is_ok(), "{args:?}");
+    }
+
+    assert_eq!(
+        reply(&["zadd", "k", "nx", "xx", "1", "a"]),
+        "ERR XX and NX options at the same time are not compatible"
+    );
+    assert_eq!(reply(&["zadd", "k", "1"]), "ERR syntax error");
+    assert_eq!(
+        reply(&["zadd", "k", "nan", "a"]),
+        "ERR value is not a valid float"
+    );
+    assert_eq!(
+        reply(&["zrange", "k", "0", "1", "limit", "0", "1"]),
+        "ERR syntax error, LIMIT is only supported in combination with either BYSCORE or BYLEX"
+    );
+    assert_eq!(
+        reply(&["zrange", "k", "a", "b", "bylex"]),
+        "ERR min or max not valid string range item"
+    );
+    assert_eq!(
+        reply(&["zinterstore", "dest", "2", "a", "b", "weights", "1"]),
+        "ERR weight value is not a float"
+    );
+}
+
+#[test]
+fn stream_arguments() {
+    for args in [
+        &[
+            "xadd",
+            "k",
+            "nomkstream",
+            "maxlen",
+            "~",
+            "10",
+            "limit",
+            "5",
+            "*",
+            "f",
+            "v",
+        ][..],
+        &["xadd", "k", "minid", "5-0", "5-*", "f", "v", "g", "w"],
+        &["xtrim", "k", "maxlen", "=", "0"],
+        &["xrange", "k", "-", "+", "count", "2"],
+        &["xrevrange", "k", "+", "(5-0"],
+        &[
+            "xread", "count", "1", "block", "0", "streams", "a", "b", "$", "0",
+        ],
+        &["xgroup", "create", "k", "g", "$", "mkstream"],
+        &["xgroup", "delconsumer", "k", "g", "c"],
+        &[
+            "xreadgroup",
+            "group",
+            "g",
+            "c",
+            "noack",
+            "streams",
+            "k",
+            ">",
+        ],
+        &["xack", "k", "g", "1-0", "2"],
+        &["xpending", "k", "g", "idle", "10", "-", "+", "5", "c"],
+        &[
+            "xclaim",
+            "k",
+            "g",
+            "c",
+            "10",
+            "1-0",
+            "2-0",
+            "retrycount",
+            "3",
+            "justid",
+        ],
+        &["xautoclaim", "k", "g", "c", "10", "-", "count", "5"],
+    ] {
+        assert!(from_frame(request(args)).is_ok(), "{args:?}");
+    }
+
+    assert_eq!(
+        reply(&["xadd", "k", "*", "f"]),
+        "ERR wrong number of arguments for 'xadd' command"
+    );
+    assert_eq!(
+        reply(&["xadd", "k", "0-0", "f", "v"]),
+        "ERR The ID specified in XADD must be greater than 0-0"
+    );
+    assert_eq!(
+        reply(&["xadd", "k", "maxlen", "5", "limit", "1", "*", "f", "v"]),
+        "ERR syntax error, LIMIT cannot be used without the special ~ option"
+    );
+    assert_eq!(
+        reply(&["xrange", "k", "a", "+"]),
+        "ERR Invalid stream ID specified as stream command argument"
+    );
+    assert_eq!(
+        reply(&["xread", "streams", "a", "b", "0"]),
+        "ERR Unbalanced 'xread' list of streams: for each stream key an ID or '$' must be specified."
+    );
+    assert_eq!(
+        reply(&["xread", "streams", "a", ">"]),
+        "ERR The > ID is meaningless in the context of XREAD"
+    );
+    assert_eq!(
+        reply(&["xread", "noack", "streams", "a", "0"]),
+        "ERR syntax error"
+    );
+    assert_eq!(
+        reply(&["xautoclaim", "k", "g", "c", "10", "0", "count", "0"]),
+        "ERR COUNT must be > 0"
+    );
+}
+
+#[test]
+fn pattern_subscription_arguments() {
+    for args in [
+        &["psubscribe", "news.*", "h?llo"][..],
+        &["punsubscribe"],
+        &["punsubscribe", "news.*"],
+        &["unsubscribe"],
+        &["unsubscribe", "news", "sport"],
+    ] {
+        assert!(from_frame(request(args)).is_ok(), "{args:?}");
+    }
+
+    assert_eq!(
+        reply(&["psubscribe"]),
+        "ERR wrong number of arguments for 'psubscribe' command"
+    );
+}
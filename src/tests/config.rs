@@ -0,0 +1,92 @@
+use crate::server::config::Config;
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|a| a.to_string()).collect()
+}
+
+fn write_config(name: &str, contents: &str) -> String {
+    let path = std::env::temp_dir().join(format!("insomnia-{}-{name}", std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+#[test]
+fn defaults() {
+    assert_eq!(Config::from_args(args(&[])).unwrap(), Config::default());
+}
+
+#[test]
+fn command_line_flags() {
+    let config = Config::from_args(args(&[
+        "--port",
+        "7000",
+        "--bind=0.0.0.0",
+        "--maxclients",
+        "10",
+        "--log-level",
+        "debug",
+    ]))
+    .unwrap();
+
+    assert_eq!(config.port, 7000);
+    assert_eq!(config.bind, "0.0.0.0");
+    assert_eq!(config.max_connections, 10);
+    assert_eq!(config.level().unwrap(), tracing::Level::DEBUG);
+}
+
+#[test]
+fn invalid_flags() {
+    assert!(Config::from_args(args(&["--port"])).is_err());
+    assert!(Config::from_args(args(&["--port", "70000"])).is_err());
+    assert!(Config::from_args(args(&["--unknown", "1"])).is_err());
+    assert!(Config::from_args(args(&["port", "1"])).is_err());
+    assert!(Config::from_args(args(&["--max-connections", "0"])).is_err());
+    assert!(Config::from_args(args(&["--proto-max-multibulk-len", "0"])).is_err());
+    assert!(Config::from_args(args(&["--proto-max-nesting-depth", "0"])).is_err());
+    assert!(Config::from_args(args(&["--port", "http"])).is_err());
+    assert!(Config::from_args(args(&["--loglevel", "loud"])).is_err());
+    assert!(Config::from_args(args(&["--config", "/does/not/exist.conf"])).is_err());
+}
+
+#[test]
+fn redis_conf_file() {
+    let path = write_config(
+        "redis.conf",
+        "# comment\n\nport 7001\nmaxclients 3\nloglevel warning\nbind \"10.0.0.1\"\nshutdown-timeout 2\n",
+    );
+
+    let config = Config::from_args(args(&["--config", &path, "--port", "7002"])).unwrap();
+
+    // flags take precedence over the file
+    assert_eq!(config.port, 7002);
+    assert_eq!(config.max_connections, 3);
+    assert_eq!(config.bind, "10.0.0.1");
+    assert_eq!(config.level().unwrap(), tracing::Level::WARN);
+    assert_eq!(config.shutdown_timeout(), std::time::Duration::from_secs(2));
+}
+
+#[test]
+fn toml_file() {
+    let path = write_config(
+        "config.toml",
+        "port = 7003\nmax-connections = 4\nproto_max_bulk_len = 1024\n",
+    );
+
+    let config = Config::from_args(args(&["--config", &path, "--maxclients", "5"])).unwrap();
+
+    assert_eq!(config.port, 7003);
+    assert_eq!(config.max_connections, 5);
+    assert_eq!(config.protocol_limits().max_bulk_len, 1024);
+}
+
+#[test]
+fn values_are_typed_by_setting() {
+    // numeric spellings of string settings stay strings
+    let config = Config::from_args(args(&["--bind", "2130706433"])).unwrap();
+    assert_eq!(config.bind, "2130706433");
+
+    let path = write_config("typed.conf", "bind 0\nport 7004\n");
+    let config = Config::from_args(args(&["--config", &path])).unwrap();
+    assert_eq!(config.bind, "0");
+    assert_eq!(config.port, 7004);
+}
@@ -0,0 +1,796 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    ops::Bound,
+    time::Duration,
+};
+
+use bytes::{Bytes, BytesMut};
+
+use tokio::time::Instant;
+
+use crate::server::{
+    config::Config,
+    database::{
+        database::{Database, ListEnd, SetExpiration},
+        entry::{Buffer, Value, WrongType},
+        hash::Hash,
+        sorted_set::SortedSet,
+        stream::{ClaimOptions, GroupRead, NewId, Stream, StreamId, Trim},
+    },
+};
+
+/// Unconditionally stores `value` at `key`, expiring after `ttl` if one is given.
+fn set(db: &Database, key: String, value: Bytes, ttl: Option<Duration>) {
+    let expiration = ttl.map_or(SetExpiration::Persist, |ttl| {
+        SetExpiration::At(Instant::now() + ttl)
+    });
+
+    db.set(key, value, expiration, false, |_| true).unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn expired_keys_are_never_read() {
+    let db = Database::new(&Config::default());
+    set(
+        &db,
+        "key".to_string(),
+        Bytes::from("value"),
+        Some(Duration::from_millis(100)),
+    );
+    set(&db, "kept".to_string(), Bytes::from("value"), None);
+
+    tokio::time::advance(Duration::from_millis(99)).await;
+    assert_eq!(db.get("key"), Ok(Some(Bytes::from("value"))));
+
+    // expiry is exact, whether or not the background purge has run yet
+    tokio::time::advance(Duration::from_millis(1)).await;
+    assert_eq!(db.get("key"), Ok(None));
+    assert_eq!(db.get("kept"), Ok(Some(Bytes::from("value"))));
+}
+
+#[tokio::test(start_paused = true)]
+async fn overwriting_clears_the_expiration() {
+    let db = Database::new(&Config::default());
+    set(
+        &db,
+        "key".to_string(),
+        Bytes::from("old"),
+        Some(Duration::from_millis(100)),
+    );
+    set(&db, "key".to_string(), Bytes::from("new"), None);
+
+    tokio::time::advance(Duration::from_secs(1)).await;
+    assert_eq!(db.get("key"), Ok(Some(Bytes::from("new"))));
+}
+
+fn set_volatile(db: &Database, count: usize, ttl: Duration) {
+    for i in 0..count {
+        set(db, format!("key:{i}"), Bytes::from("value"), Some(ttl));
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn expired_keys_are_purged_without_reads() {
+    let db = Database::new(&Config::default());
+    set_volatile(&db, 50, Duration::from_millis(100));
+    set(
+        &db,
+        "kept".to_string(),
+        Bytes::from("value"),
+        Some(Duration::from_secs(60)),
+    );
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+
+    let stats = db.expiry_stats();
+    assert_eq!(stats.expired_keys, 50);
+    assert_eq!(stats.volatile_keys, 1);
+    assert!((stats.stale_ratio - 50.0 / 51.0).abs() < f64::EPSILON);
+}
+
+#[tokio::test(start_paused = true)]
+async fn purge_work_is_bounded_per_tick() {
+    let db = Database::new(&Config::default());
+    set_volatile(&db, 10_000, Duration::from_millis(100));
+
+    // lets the purge task run its first tick once the keys are due
+    tokio::time::advance(Duration::from_millis(100)).await;
+    tokio::task::yield_now().await;
+
+    let first_tick = db.expiry_stats().expired_keys;
+    assert!(first_tick > 0);
+    assert!(first_tick < 1_000, "{first_tick} keys purged at once");
+
+    // later ticks work harder until the backlog is gone
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    let stats = db.expiry_stats();
+    assert_eq!(stats.expired_keys, 10_000);
+    assert_eq!(stats.volatile_keys, 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn reads_and_purges_share_the_expired_count() {
+    let db = Database::new(&Config::default());
+    set_volatile(&db, 2, Duration::from_millis(100));
+
+    tokio::time::advance(Duration::from_millis(100)).await;
+    assert_eq!(db.get("key:0"), Ok(None));
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(db.expiry_stats().expired_keys, 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn key_lifecycle() {
+    let db = Database::new(&Config::default());
+    set(&db, "a".to_string(), Bytes::from("1"), None);
+    set(
+        &db,
+        "b".to_string(),
+        Bytes::from("2"),
+        Some(Duration::from_secs(10)),
+    );
+
+    let keys = |keys: &[&str]| keys.iter().map(|k| k.to_string()).collect::<Vec<_>>();
+    assert_eq!(db.exists(&keys(&["a", "b", "a", "c"])), 3);
+
+    // expiration conditions are checked against the current expiration
+    let later = Instant::now() + Duration::from_secs(20);
+    assert!(!db.expire("a", later, |current| current.is_some()));
+    assert!(db.expire("b", later, |current| current.is_some()));
+    assert_eq!(db.expiration("b"), Some(Some(later)));
+    assert_eq!(db.expiry_stats().volatile_keys, 1);
+
+    // renaming carries the expiration over and drops the one of the replaced key
+    assert_eq!(db.rename("b", "a", false), Some(false));
+    assert_eq!(db.rename("b", "a", true), Some(true));
+    assert_eq!(db.rename("b", "a", true), None);
+    assert_eq!(db.expiration("a"), Some(Some(later)));
+    assert_eq!(db.expiry_stats().volatile_keys, 1);
+
+    assert!(db.copy("a", "c", false));
+    assert!(!db.copy("a", "c", false));
+    assert_eq!(db.expiry_stats().volatile_keys, 2);
+
+    assert!(db.persist("c"));
+    assert!(!db.persist("c"));
+    assert_eq!(db.expiration("c"), Some(None));
+    assert_eq!(db.expiry_stats().volatile_keys, 1);
+
+    // an expiration in the past removes the key
+    assert!(db.expire("c", Instant::now(), |_| true));
+    assert_eq!(db.get("c"), Ok(None));
+
+    assert_eq!(db.del(&keys(&["a", "c"])), 1);
+    assert_eq!(db.expiry_stats().volatile_keys, 0);
+    assert_eq!(db.key_type("a"), None);
+}
+
+#[tokio::test(start_paused = true)]
+async fn conditional_set() {
+    let db = Database::new(&Config::default());
+    let missing = |exists: bool| !exists;
+
+    assert_eq!(
+        db.set(
+            "key".to_string(),
+            Bytes::from("1"),
+            SetExpiration::Persist,
+            true,
+            missing
+        ),
+        Ok((None, true))
+    );
+    assert_eq!(
+        db.set(
+            "key".to_string(),
+            Bytes::from("2"),
+            SetExpiration::Persist,
+            true,
+            missing
+        ),
+        Ok((Some(Bytes::from("1")), false))
+    );
+
+    let time = Instant::now() + Duration::from_secs(10);
+    assert_eq!(
+        db.set(
+            "key".to_string(),
+            Bytes::from("2"),
+            SetExpiration::At(time),
+            true,
+            |_| true
+        ),
+        Ok((Some(Bytes::from("1")), true))
+    );
+
+    // KEEPTTL carries the expiration over to the new value
+    db.set(
+        "key".to_string(),
+        Bytes::from("3"),
+        SetExpiration::Keep,
+        false,
+        |_| true,
+    )
+    .unwrap();
+    assert_eq!(db.expiration("key"), Some(Some(time)));
+    assert_eq!(db.expiry_stats().volatile_keys, 1);
+
+    db.set(
+        "key".to_string(),
+        Bytes::from("4"),
+        SetExpiration::Persist,
+        false,
+        |_| true,
+    )
+    .unwrap();
+    assert_eq!(db.expiration("key"), Some(None));
+    assert_eq!(db.expiry_stats().volatile_keys, 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn updates_keep_the_expiration() {
+    let db = Database::new(&Config::default());
+    set(
+        &db,
+        "n".to_string(),
+        Bytes::from("1"),
+        Some(Duration::from_secs(10)),
+    );
+    let expiration = db.expiration("n");
+
+    let incr = |current: Option<&[u8]>| -> Result<(Bytes, i64), &str> {
+        let n: i64 = match current {
+            Some(buf) => std::str::from_utf8(buf)
+                .unwrap()
+                .parse()
+                .map_err(|_| "nan")?,
+            None => 0,
+        };
+        Ok((Bytes::from((n + 1).to_string()), n + 1))
+    };
+
+    assert_eq!(db.update("n", incr), Ok(Ok(2)));
+    assert_eq!(db.expiration("n"), expiration);
+    assert_eq!(db.update("m", incr), Ok(Ok(1)));
+    assert_eq!(db.expiration("m"), Some(None));
+
+    // failed updates leave the value as it was
+    set(&db, "s".to_string(), Bytes::from("text"), None);
+    assert_eq!(db.update("s", incr), Ok(Err("nan")));
+    assert_eq!(db.get("s"), Ok(Some(Bytes::from("text"))));
+}
+
+#[tokio::test(start_paused = true)]
+async fn msetnx_is_all_or_nothing() {
+    let db = Database::new(&Config::default());
+    let pairs = |keys: &[&str]| {
+        keys.iter()
+            .map(|k| (k.to_string(), Bytes::from("value")))
+            .collect::<Vec<_>>()
+    };
+
+    assert!(db.mset(pairs(&["a", "b"]), true));
+    assert!(!db.mset(pairs(&["c", "b"]), true));
+    assert_eq!(
+        db.mget(&["a".to_string(), "b".to_string(), "c".to_string()]),
+        vec![Some(Bytes::from("value")), Some(Bytes::from("value")), None]
+    );
+
+    // plain MSET overwrites, clearing expirations
+    set(
+        &db,
+        "c".to_string(),
+        Bytes::from("old"),
+        Some(Duration::from_secs(1)),
+    );
+    assert!(db.mset(pairs(&["b", "c"]), false));
+    assert_eq!(db.expiration("c"), Some(None));
+    assert_eq!(db.get("c"), Ok(Some(Bytes::from("value"))));
+}
+
+#[tokio::test(start_paused = true)]
+async fn get_and_expire() {
+    let db = Database::new(&Config::default());
+    set(&db, "key".to_string(), Bytes::from("value"), None);
+
+    let at = Instant::now() + Duration::from_secs(10);
+    assert_eq!(
+        db.get_and_expire("key", SetExpiration::At(at)),
+        Ok(Some(Bytes::from("value")))
+    );
+    assert_eq!(db.expiration("key"), Some(Some(at)));
+
+    db.get_and_expire("key", SetExpiration::Keep).unwrap();
+    assert_eq!(db.expiration("key"), Some(Some(at)));
+
+    db.get_and_expire("key", SetExpiration::Persist).unwrap();
+    assert_eq!(db.expiration("key"), Some(None));
+
+    // an expiration in the past still replies with the value
+    assert_eq!(
+        db.get_and_expire("key", SetExpiration::At(Instant::now())),
+        Ok(Some(Bytes::from("value")))
+    );
+    assert_eq!(db.get("key"), Ok(None));
+    assert_eq!(db.get_and_expire("key", SetExpiration::Keep), Ok(None));
+
+    set(&db, "key".to_string(), Bytes::from("value"), None);
+    assert_eq!(db.get_del("key"), Ok(Some(Bytes::from("value"))));
+    assert_eq!(db.get_del("key"), Ok(None));
+}
+
+#[tokio::test(start_paused = true)]
+async fn edits_in_place() {
+    let db = Database::new(&Config::default());
+    set(
+        &db,
+        "key".to_string(),
+        Bytes::from("hello"),
+        Some(Duration::from_secs(10)),
+    );
+    let expiration = db.expiration("key");
+
+    let len = db.edit("key", |buf| {
+        buf.extend_from_slice(b" world");
+        buf.len()
+    });
+    assert_eq!(len, Ok(11));
+    assert_eq!(db.get("key"), Ok(Some(Bytes::from("hello world"))));
+    assert_eq!(db.read("key", |buf| buf.len()), Ok(Some(11)));
+    assert_eq!(db.expiration("key"), expiration);
+
+    // missing keys are only created by edits that leave a value
+    db.edit("missing", |_| ()).unwrap();
+    assert_eq!(db.read("missing", |buf| buf.len()), Ok(None));
+    db.edit("missing", |buf| buf.extend_from_slice(b"value"))
+        .unwrap();
+    assert_eq!(db.get("missing"), Ok(Some(Bytes::from("value"))));
+
+    let len = db.combine("dest", &["key".to_string(), "none".to_string()], |values| {
+        assert_eq!(values[1], None);
+        Bytes::copy_from_slice(values[0].unwrap())
+    });
+    assert_eq!(len, Ok(11));
+    assert_eq!(db.get("dest"), Ok(Some(Bytes::from("hello world"))));
+
+    db.combine("dest", &[], |_| Bytes::new()).unwrap();
+    assert_eq!(db.exists(&["dest".to_string()]), 0);
+}
+
+#[test]
+fn value_types() {
+    let list = Value::List(VecDeque::from([Bytes::from("a")]));
+    assert_eq!(list.type_name(), "list");
+    assert_eq!(list.as_string().err(), Some(WrongType));
+    assert_eq!(
+        WrongType.to_string(),
+        "WRONGTYPE Operation against a key holding the wrong kind of value"
+    );
+
+    for (value, encoding) in [
+        ("12345", "int"),
+        ("012", "embstr"),
+        ("short", "embstr"),
+        (&"x".repeat(45)[..], "raw"),
+    ] {
+        let value = Value::String(Buffer::Shared(Bytes::copy_from_slice(value.as_bytes())));
+        assert_eq!(value.type_name(), "string");
+        assert_eq!(value.encoding(), encoding);
+    }
+
+    // strings edited in place are no longer embedded
+    let value = Value::String(Buffer::Owned(BytesMut::from("short")));
+    assert_eq!(value.encoding(), "raw");
+}
+
+#[tokio::test(start_paused = true)]
+async fn encodings() {
+    let db = Database::new(&Config::default());
+    set(&db, "n".to_string(), Bytes::from("10"), None);
+    assert_eq!(db.key_type("n"), Some("string"));
+    assert_eq!(db.encoding("n"), Some("int"));
+
+    db.edit("n", |buf| buf.extend_from_slice(b"x")).unwrap();
+    assert_eq!(db.encoding("n"), Some("raw"));
+    assert_eq!(db.encoding("missing"), None);
+}
+
+#[tokio::test(start_paused = true)]
+async fn lists() {
+    let db = Database::new(&Config::default());
+    let push = |key: &str, values: &[&str]| {
+        db.edit_collection(key, true, |list: &mut VecDeque<Bytes>| {
+            list.extend(values.iter().map(|v| Bytes::copy_from_slice(v.as_bytes())));
+            list.len()
+        })
+    };
+
+    assert_eq!(push("list", &["a", "b", "c"]), Ok(Some(3)));
+    assert_eq!(db.key_type("list"), Some("list"));
+    assert_eq!(db.get("list"), Err(WrongType));
+    assert_eq!(
+        db.read_collection("list", |list: &VecDeque<Bytes>| list.len()),
+        Ok(Some(3))
+    );
+
+    set(&db, "string".to_string(), Bytes::from("value"), None);
+    assert_eq!(push("string", &["a"]), Err(WrongType));
+
+    assert_eq!(
+        db.list_move("list", "other", ListEnd::Left, ListEnd::Right),
+        Ok(Some(Bytes::from("a")))
+    );
+    assert_eq!(
+        db.list_move("list", "string", ListEnd::Left, ListEnd::Right),
+        Err(WrongType)
+    );
+    assert_eq!(
+        db.list_move("missing", "string", ListEnd::Left, ListEnd::Right),
+        Ok(None)
+    );
+
+    // lists are removed once empty, and are never created empty
+    db.edit_collection("other", false, |list: &mut VecDeque<Bytes>| list.clear())
+        .unwrap();
+    assert_eq!(db.key_type("other"), None);
+    assert_eq!(
+        db.edit_collection("other", false, |list: &mut VecDeque<Bytes>| list.len()),
+        Ok(None)
+    );
+    push("other", &[]).unwrap();
+    assert_eq!(db.key_type("other"), None);
+}
+
+#[tokio::test(start_paused = true)]
+async fn hash_fields_expire() {
+    let db = Database::new(&Config::default());
+    let field = |db: &Database, name: &str| {
+        db.read_collection("hash", |hash: &Hash| hash.get(name.as_bytes()).cloned())
+    };
+
+    db.edit_collection("hash", true, |hash: &mut Hash| {
+        hash.insert(Bytes::from("a"), Bytes::from("1"));
+        hash.insert(Bytes::from("b"), Bytes::from("2"));
+        hash.set_expiration(b"a", Some(Instant::now() + Duration::from_millis(100)))
+    })
+    .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(field(&db, "a"), Ok(Some(None)));
+    assert_eq!(field(&db, "b"), Ok(Some(Some(Bytes::from("2")))));
+
+    // overwriting a field clears its expiration
+    db.edit_collection("hash", false, |hash: &mut Hash| {
+        hash.set_expiration(b"b", Some(Instant::now() + Duration::from_millis(100)));
+        hash.insert(Bytes::from("b"), Bytes::from("3"))
+    })
+    .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(field(&db, "b"), Ok(Some(Some(Bytes::from("3")))));
+
+    // the hash goes away with its last field
+    db.edit_collection("hash", false, |hash: &mut Hash| {
+        hash.set_expiration(b"b", Some(Instant::now() + Duration::from_millis(100)))
+    })
+    .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(db.key_type("hash"), None);
+}
+
+#[test]
+fn hash_scan() {
+    let mut hash = Hash::default();
+    for i in 0..10 {
+        hash.insert(Bytes::from(format!("f{i}")), Bytes::from("v"));
+    }
+
+    let mut seen = Vec::new();
+    let mut cursor = 0;
+
+    loop {
+        let (next, fields) = hash.scan(cursor, 3);
+        assert!(fields.len() <= 3);
+        seen.extend(fields.into_iter().map(|(name, _)| name.clone()));
+
+        // fields changing mid-scan do not disturb the others
+        hash.remove(b"f0");
+        hash.insert(Bytes::from("new"), Bytes::from("v"));
+
+        cursor = next;
+        if cursor == 0 {
+            break;
+        }
+    }
+
+    for i in 1..10 {
+        let name = Bytes::from(format!("f{i}"));
+        assert_eq!(seen.iter().filter(|seen| **seen == name).count(), 1);
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn stored_collections() {
+    let db = Database::new(&Config::default());
+    let add = |key: &str, members: &[&str]| {
+        db.edit_collection(key, true, |set: &mut HashSet<Bytes>| {
+            set.extend(members.iter().map(|m| Bytes::copy_from_slice(m.as_bytes())))
+        })
+        .unwrap();
+    };
+
+    add("a", &["x", "y", "z"]);
+    add("b", &["y"]);
+    set(
+        &db,
+        "dest".to_string(),
+        Bytes::from("value"),
+        Some(Duration::from_secs(1)),
+    );
+
+    let keys = ["a".to_string(), "b".to_string(), "missing".to_string()];
+    let union = |sets: Vec<Option<&HashSet<Bytes>>>| {
+        sets.into_iter().flatten().flatten().cloned().collect()
+    };
+
+    // the destination is overwritten whatever its type, and loses its expiration
+    assert_eq!(db.store_collection("dest", &keys, union), Ok(3));
+    assert_eq!(db.key_type("dest"), Some("set"));
+    assert_eq!(db.expiration("dest"), Some(None));
+
+    assert_eq!(
+        db.store_collection("dest", &keys[2..], |_| HashSet::new()),
+        Ok(0)
+    );
+    assert_eq!(db.key_type("dest"), None);
+
+    set(&db, "string".to_string(), Bytes::from("value"), None);
+    assert_eq!(
+        db.read_collections(
+            &["a".to_string(), "string".to_string()],
+            |sets: Vec<Option<&HashSet<Bytes>>>| sets.len()
+        ),
+        Err(WrongType)
+    );
+    assert_eq!(
+        db.read_collections(&keys, |sets: Vec<Option<&HashSet<Bytes>>>| {
+            sets.iter()
+                .map(|set| set.map_or(0, HashSet::len))
+                .collect::<Vec<_>>()
+        }),
+        Ok(vec![3, 1, 0])
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn sorted_sets() {
+    let db = Database::new(&Config::default());
+    let add = |key: &str, members: &[(f64, &str)]| {
+        db.edit_collection(key, true, |set: &mut SortedSet| {
+            for (score, member) in members {
+                set.insert(Bytes::copy_from_slice(member.as_bytes()), *score);
+            }
+        })
+        .unwrap();
+    };
+    let members = |set: &SortedSet, min, max| {
+        set.range_by_score(min, max)
+            .map(|(member, _)| member.clone())
+            .collect::<Vec<_>>()
+    };
+
+    add(
+        "set",
+        &[(2.0, "c"), (1.0, "b"), (1.0, "a"), (f64::INFINITY, "d")],
+    );
+    assert_eq!(db.key_type("set"), Some("zset"));
+
+    db.read_collection("set", |set: &SortedSet| {
+        // equal scores are ordered by member
+        assert_eq!(set.rank(b"a"), Some(0));
+        assert_eq!(set.rank(b"b"), Some(1));
+        assert_eq!(set.rank(b"d"), Some(3));
+        assert_eq!(set.rank(b"e"), None);
+
+        assert_eq!(
+            members(set, Bound::Excluded(1.0), Bound::Unbounded),
+            vec![Bytes::from("c"), Bytes::from("d")]
+        );
+        assert_eq!(
+            members(set, Bound::Included(1.0), Bound::Excluded(2.0)),
+            vec![Bytes::from("a"), Bytes::from("b")]
+        );
+        assert!(members(set, Bound::Excluded(2.0), Bound::Included(1.0)).is_empty());
+        assert_eq!(
+            members(
+                set,
+                Bound::Included(f64::INFINITY),
+                Bound::Included(f64::INFINITY)
+            ),
+            vec![Bytes::from("d")]
+        );
+
+        let lex = set
+            .range_by_lex(Bound::Excluded(b"a"), Bound::Included(b"c"))
+            .map(|(member, _)| member.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(lex, vec![Bytes::from("b"), Bytes::from("c")]);
+    })
+    .unwrap();
+
+    // updating a score moves the member
+    add("set", &[(0.0, "d")]);
+    assert_eq!(
+        db.edit_collection("set", false, |set: &mut SortedSet| set.pop(false)),
+        Ok(Some(Some((Bytes::from("d"), 0.0))))
+    );
+
+    // blocked clients are served once a member is added
+    let Ok(Err(mut blocked)) = db.pop_member_or_block(vec!["empty".to_string()], true) else {
+        panic!("the sorted set is empty");
+    };
+    add("empty", &[(1.0, "x"), (3.0, "y")]);
+    assert_eq!(
+        blocked.served().await,
+        Ok(("empty".to_string(), (Bytes::from("y"), 3.0)))
+    );
+
+    set(&db, "string".to_string(), Bytes::from("value"), None);
+    assert!(matches!(
+        db.pop_member_or_block(vec!["string".to_string()], false),
+        Err(WrongType)
+    ));
+}
+
+#[tokio::test(start_paused = true)]
+async fn streams() {
+    let db = Database::new(&Config::default());
+    let add = |id: NewId, now_ms: u64| {
+        db.edit_collection("stream", true, |stream: &mut Stream| {
+            stream.add(id, vec![(Bytes::from("f"), Bytes::from("v"))], now_ms)
+        })
+        .unwrap()
+        .unwrap()
+    };
+    let ids =
+        |entries: Vec<(StreamId, _)>| entries.into_iter().map(|(id, _)| id).collect::<Vec<_>>();
+
+    // generated IDs never go back, even if the clock does
+    assert_eq!(add(NewId::Auto, 5), Some(StreamId::new(5, 0)));
+    assert_eq!(add(NewId::Auto, 5), Some(StreamId::new(5, 1)));
+    assert_eq!(add(NewId::Auto, 3), Some(StreamId::new(5, 2)));
+    assert_eq!(add(NewId::AutoSeq(7), 0), Some(StreamId::new(7, 0)));
+    assert_eq!(add(NewId::Exact(StreamId::new(7, 0)), 0), None);
+    assert_eq!(db.key_type("stream"), Some("stream"));
+
+    let read_after = |id| {
+        db.read_collection("stream", |stream: &Stream| stream.read_after(id, Some(2)))
+            .unwrap()
+            .unwrap()
+    };
+    assert_eq!(
+        ids(read_after(StreamId::new(5, 0))),
+        vec![StreamId::new(5, 1), StreamId::new(5, 2)]
+    );
+    assert!(read_after(StreamId::MAX).is_empty());
+
+    let read = GroupRead {
+        group: "group".to_string(),
+        consumer: "alice".to_string(),
+        noack: false,
+    };
+    let now = Instant::now();
+
+    db.edit_collection("stream", false, |stream: &mut Stream| {
+        assert_eq!(stream.read_group(&read, None, None, now), None);
+        assert!(stream.create_group("group", StreamId::new(5, 0)));
+        assert!(!stream.create_group("group", StreamId::default()));
+
+        // new entries are delivered once, and stay pending until acknowledged
+        let delivered = stream.read_group(&read, None, Some(2), now).unwrap();
+        assert_eq!(
+            ids(delivered),
+            vec![StreamId::new(5, 1), StreamId::new(5, 2)]
+        );
+        let delivered = stream.read_group(&read, None, None, now).unwrap();
+        assert_eq!(ids(delivered), vec![StreamId::new(7, 0)]);
+        assert!(stream
+            .read_group(&read, None, None, now)
+            .unwrap()
+            .is_empty());
+
+        let history = stream.read_group(&read, Some(StreamId::default()), None, now);
+        assert_eq!(history.map(ids).unwrap_or_default().len(), 3);
+
+        let group = stream.group_mut("group").unwrap();
+        assert_eq!(group.ack(&[StreamId::new(5, 1), StreamId::new(9, 0)]), 1);
+        assert_eq!(group.pending_len(), 2);
+    })
+    .unwrap();
+
+    // idle entries can be claimed by another consumer
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    db.edit_collection("stream", false, |stream: &mut Stream| {
+        let now = Instant::now();
+        let min_idle = |ms| ClaimOptions {
+            min_idle: Duration::from_millis(ms),
+            ..Default::default()
+        };
+
+        let claimed = stream.claim("group", "bob", &[StreamId::new(5, 2)], min_idle(200), now);
+        assert_eq!(claimed, Some(vec![]));
+
+        let (next, claimed, deleted) = stream
+            .auto_claim("group", "bob", StreamId::default(), 1, min_idle(50), now)
+            .unwrap();
+        assert_eq!(next, StreamId::new(7, 0));
+        assert_eq!(ids(claimed), vec![StreamId::new(5, 2)]);
+        assert!(deleted.is_empty());
+
+        let group = stream.group("group").unwrap();
+        let (_, pending) = group
+            .pending(Bound::Unbounded, Bound::Unbounded)
+            .next()
+            .unwrap();
+        assert_eq!((pending.consumer.as_str(), pending.deliveries), ("bob", 2));
+
+        // trimmed entries are dropped from the pending list when claimed
+        assert_eq!(stream.trim(Trim::MaxLen(0), None), 4);
+        let (_, claimed, deleted) = stream
+            .auto_claim("group", "bob", StreamId::default(), 10, min_idle(0), now)
+            .unwrap();
+        assert!(claimed.is_empty());
+        assert_eq!(deleted, vec![StreamId::new(5, 2), StreamId::new(7, 0)]);
+    })
+    .unwrap();
+
+    // streams are kept once emptied, and still only take greater IDs
+    assert_eq!(db.key_type("stream"), Some("stream"));
+    assert_eq!(add(NewId::Exact(StreamId::new(7, 0)), 0), None);
+
+    // blocked readers are served once an entry is added
+    let Ok(Err(mut blocked)) =
+        db.read_streams_or_block(vec!["stream".to_string()], vec![None], None, true)
+    else {
+        panic!("no entry was added after the last ID");
+    };
+    add(NewId::Auto, 10);
+    let (key, entries) = blocked.served().await.unwrap();
+    assert_eq!(
+        (key.as_str(), ids(entries)),
+        ("stream", vec![StreamId::new(10, 0)])
+    );
+
+    set(&db, "string".to_string(), Bytes::from("value"), None);
+    assert!(matches!(
+        db.read_streams_or_block(vec!["string".to_string()], vec![None], None, false),
+        Err(WrongType)
+    ));
+}
+
+#[tokio::test(start_paused = true)]
+async fn pattern_subscriptions() {
+    let db = Database::new(&Config::default());
+    let mut pattern = db.psubscribe("news.*".to_string());
+    let _channel = db.subscribe("news.tech".to_string());
+
+    // channel and pattern subscribers are counted alike
+    assert_eq!(db.publish("news.tech", Bytes::from("a")), 2);
+    assert_eq!(db.publish("news.sport", Bytes::from("b")), 1);
+    assert_eq!(db.publish("weather", Bytes::from("c")), 0);
+
+    assert_eq!(
+        pattern.recv().await,
+        Ok(("news.tech".to_string(), Bytes::from("a")))
+    );
+    assert_eq!(
+        pattern.recv().await,
+        Ok(("news.sport".to_string(), Bytes::from("b")))
+    );
+
+    drop(pattern);
+    assert_eq!(db.publish("news.tech", Bytes::from("d")), 1);
+}
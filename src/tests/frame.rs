@@ -1,73 +1,245 @@
-use crate::frame::Frame;
-use std::io::Cursor;
+use crate::{
+    codec::FrameCodec,
+    frame::{Frame, FrameError},
+};
+use bytes::{Bytes, BytesMut};
 
-macro_rules! into_cursor {
-    ($b:tt) => {
-        Cursor::new(&$b[..])
-    };
+/// Decodes a single frame, reporting a frame that has not fully arrived as an error.
+fn decode(buf: &[u8]) -> Result<Frame, FrameError> {
+    FrameCodec::default()
+        .decode_frame(&mut BytesMut::from(buf))?
+        .ok_or_else(|| FrameError::ParsingError("Incomplete frame.".to_string()))
+}
+
+fn validate(buf: &[u8]) -> Result<(), FrameError> {
+    decode(buf).map(|_| ())
+}
+
+fn is_incomplete(buf: &[u8]) -> bool {
+    matches!(
+        FrameCodec::default().decode_frame(&mut BytesMut::from(buf)),
+        Ok(None)
+    )
 }
 
 #[test]
 fn validation_simple_string() {
-    assert_eq!(Frame::validate(&mut into_cursor!(b"+Hello\r\n")), Ok(()));
-    assert_ne!(Frame::validate(&mut into_cursor!(b"+hello\r")), Ok(()));
-    assert_ne!(Frame::validate(&mut into_cursor!(b"+\r\n")), Ok(()));
-    assert_ne!(Frame::validate(&mut into_cursor!(b"+")), Ok(()));
+    assert_eq!(validate(b"+Hello\r\n"), Ok(()));
+    assert_ne!(validate(b"+hello\r"), Ok(()));
+    assert_ne!(validate(b"+\r\n"), Ok(()));
+    assert_ne!(validate(b"+"), Ok(()));
 }
 
 #[test]
 fn validation_error() {
-    assert_eq!(Frame::validate(&mut into_cursor!(b"-ERROR\r\n")), Ok(()));
-    assert_ne!(Frame::validate(&mut into_cursor!(b"-Error\n")), Ok(()));
-    assert_ne!(Frame::validate(&mut into_cursor!(b"-Error\r")), Ok(()));
-    assert_ne!(Frame::validate(&mut into_cursor!(b"-Error")), Ok(()));
-    assert_ne!(Frame::validate(&mut into_cursor!(b"-\r\n")), Ok(()));
+    assert_eq!(validate(b"-ERROR\r\n"), Ok(()));
+    assert_ne!(validate(b"-Error\n"), Ok(()));
+    assert_ne!(validate(b"-Error\r"), Ok(()));
+    assert_ne!(validate(b"-Error"), Ok(()));
+    assert_ne!(validate(b"-\r\n"), Ok(()));
 }
 
 #[test]
 fn validation_int() {
-    assert_eq!(Frame::validate(&mut into_cursor!(b":0\r\n")), Ok(()));
-    assert_eq!(Frame::validate(&mut into_cursor!(b":000001\r\n")), Ok(()));
-    assert_eq!(Frame::validate(&mut into_cursor!(b":992123\r\n")), Ok(()));
+    assert_eq!(validate(b":0\r\n"), Ok(()));
+    assert_eq!(validate(b":000001\r\n"), Ok(()));
+    assert_eq!(validate(b":992123\r\n"), Ok(()));
+
+    assert_eq!(validate(b":-1\r\n"), Ok(()));
+
+    let buff = format!(":{}\r\n", i64::MAX);
+    let buff = buff.as_bytes();
+    assert_eq!(validate(buff), Ok(()));
+
+    let buff = format!(":{}\r\n", i64::MIN);
+    let buff = buff.as_bytes();
+    assert_eq!(validate(buff), Ok(()));
+
+    assert_ne!(validate(b":0 0\r\n"), Ok(()));
+    assert_ne!(validate(b":0.0\r\n"), Ok(()));
 
-    let buff = format!(":{}\r\n", u64::MAX).as_bytes();
-    assert_eq!(Frame::validate(&mut into_cursor!(buff)), Ok(()));
+    assert_ne!(validate(b":-\r\n"), Ok(()));
+    assert_ne!(validate(b":--1\r\n"), Ok(()));
 
-    assert_ne!(Frame::validate(&mut into_cursor!(b":0 0\r\n")), Ok(()));
-    assert_ne!(Frame::validate(&mut into_cursor!(b":0.0\r\n")), Ok(()));
+    let buff = format!(":{}9\r\n", i64::MAX);
+    let buff = buff.as_bytes();
+    assert_ne!(validate(buff), Ok(()));
 
-    let buff = format!(":{}9\r\n", u64::MAX).as_bytes();
-    assert_ne!(Frame::validate(&mut into_cursor!(buff)), Ok(()));
+    let buff = format!(":{}\r\n", u64::MAX);
+    let buff = buff.as_bytes();
+    assert_ne!(validate(buff), Ok(()));
 }
 
 #[test]
 fn validation_null() {
-    assert_eq!(Frame::validate(&mut into_cursor!(b"_\r\n")), Ok(()));
-    assert_ne!(Frame::validate(&mut into_cursor!(b"_asd\r\n")), Ok(()));
+    assert_eq!(validate(b"_\r\n"), Ok(()));
+    assert_ne!(validate(b"_asd\r\n"), Ok(()));
+}
+
+#[test]
+fn parse_int() {
+    assert_eq!(decode(b":-42\r\n"), Ok(Frame::Integer(-42)));
+    assert_eq!(decode(b":+7\r\n"), Ok(Frame::Integer(7)));
 }
 
 #[test]
 fn validation_bulk_string() {
+    assert_eq!(validate(b"$0005\r\nHello\r\n"), Ok(()));
+    assert_eq!(validate(b"$0000\r\n\r\n"), Ok(()));
+    assert_ne!(validate(b"$0004\r\nHello\r\n"), Ok(()));
+    assert_ne!(validate(b"$\r\nHello\r\n"), Ok(()));
+    assert_ne!(validate(b"$03\r\nHell\r\n"), Ok(()));
+    assert_ne!(validate(b"$0003\r\n"), Ok(()));
+    assert_ne!(validate(b"$0000\r\n"), Ok(()));
+    assert_eq!(validate(b"$-1\r\n"), Ok(()));
+    assert_ne!(validate(b"$-2\r\n"), Ok(()));
+}
+
+#[test]
+fn parse_resp2_null() {
+    assert_eq!(decode(b"$-1\r\n"), Ok(Frame::Null));
+    assert_eq!(decode(b"*-1\r\n"), Ok(Frame::NullArray));
     assert_eq!(
-        Frame::validate(&mut into_cursor!(b"$0005\r\nHello\r\n")),
-        Ok(())
+        decode(b"*2\r\n$-1\r\n:-1\r\n"),
+        Ok(Frame::Array(vec![Frame::Null, Frame::Integer(-1)]))
     );
-    assert_eq!(Frame::validate(&mut into_cursor!(b"$0000\r\n\r\n")), Ok(()));
-    assert_ne!(
-        Frame::validate(&mut into_cursor!(b"$0004\r\nHello\r\n")),
+    assert_ne!(validate(b"~-1\r\n"), Ok(()));
+}
+
+#[test]
+fn validation_array() {
+    assert_eq!(validate(b"*0\r\n"), Ok(()));
+    assert_eq!(validate(b"*2\r\n$3\r\nGET\r\n$3\r\nkey\r\n"), Ok(()));
+    assert_eq!(validate(b"*2\r\n*1\r\n:1\r\n_\r\n"), Ok(()));
+    assert!(is_incomplete(b"*2\r\n$3\r\nGET\r\n"));
+    assert_ne!(validate(b"*a\r\n"), Ok(()));
+}
+
+#[test]
+fn validation_boolean() {
+    assert_eq!(validate(b"#t\r\n"), Ok(()));
+    assert_eq!(validate(b"#f\r\n"), Ok(()));
+    assert_ne!(validate(b"#x\r\n"), Ok(()));
+    assert_ne!(validate(b"#true\r\n"), Ok(()));
+}
+
+#[test]
+fn validation_double() {
+    assert_eq!(validate(b",1.23\r\n"), Ok(()));
+    assert_eq!(validate(b",-10\r\n"), Ok(()));
+    assert_eq!(validate(b",1.5e10\r\n"), Ok(()));
+    assert_eq!(validate(b",inf\r\n"), Ok(()));
+    assert_eq!(validate(b",-inf\r\n"), Ok(()));
+    assert_eq!(validate(b",nan\r\n"), Ok(()));
+    assert_ne!(validate(b",1.2.3\r\n"), Ok(()));
+    assert_ne!(validate(b",\r\n"), Ok(()));
+}
+
+#[test]
+fn validation_big_number() {
+    assert_eq!(
+        validate(b"(3492890328409238509324850943850943825024385\r\n"),
         Ok(())
     );
-    assert_ne!(
-        Frame::validate(&mut into_cursor!(b"$\r\nHello\r\n")),
-        Ok(())
+    assert_eq!(validate(b"(-12\r\n"), Ok(()));
+    assert_ne!(validate(b"(12a\r\n"), Ok(()));
+    assert_ne!(validate(b"(-\r\n"), Ok(()));
+}
+
+#[test]
+fn validation_bulk_error() {
+    assert_eq!(validate(b"!10\r\nERR failed\r\n"), Ok(()));
+    assert_ne!(validate(b"!3\r\nERR failed\r\n"), Ok(()));
+}
+
+#[test]
+fn validation_verbatim() {
+    assert_eq!(validate(b"=15\r\ntxt:Some string\r\n"), Ok(()));
+    assert_ne!(validate(b"=11\r\nSome string\r\n"), Ok(()));
+}
+
+#[test]
+fn validation_aggregates() {
+    assert_eq!(validate(b"%2\r\n+first\r\n:1\r\n+second\r\n:2\r\n"), Ok(()));
+    assert_eq!(validate(b"~2\r\n+orange\r\n+apple\r\n"), Ok(()));
+    assert_eq!(validate(b">2\r\n+message\r\n+hi\r\n"), Ok(()));
+    assert_eq!(validate(b"|1\r\n+ttl\r\n:3600\r\n+OK\r\n"), Ok(()));
+    assert!(is_incomplete(b"%1\r\n+first\r\n"));
+    assert!(is_incomplete(b"|1\r\n+ttl\r\n:3600\r\n"));
+}
+
+#[test]
+fn parse_resp3_types() {
+    assert_eq!(decode(b"#t\r\n"), Ok(Frame::Boolean(true)));
+    assert_eq!(decode(b",-1.5\r\n"), Ok(Frame::Double(-1.5)));
+    assert_eq!(decode(b",inf\r\n"), Ok(Frame::Double(f64::INFINITY)));
+    assert_eq!(
+        decode(b"(-123456789012345678901234567890\r\n"),
+        Ok(Frame::BigNumber(
+            "-123456789012345678901234567890".to_string()
+        ))
     );
-    assert_ne!(
-        Frame::validate(&mut into_cursor!(b"$03\r\nHel\r\n")),
-        Ok(())
+    assert_eq!(
+        decode(b"=8\r\nmkd:text\r\n"),
+        Ok(Frame::Verbatim(
+            "mkd".to_string(),
+            Bytes::from_static(b"text")
+        ))
+    );
+    assert_eq!(
+        decode(b"%1\r\n+key\r\n$3\r\nval\r\n"),
+        Ok(Frame::Map(vec![(
+            Frame::Simple("key".to_string()),
+            Frame::Bulk(Bytes::from_static(b"val"))
+        )]))
+    );
+    assert_eq!(
+        decode(b">2\r\n+message\r\n_\r\n"),
+        Ok(Frame::Push(vec![
+            Frame::Simple("message".to_string()),
+            Frame::Null
+        ]))
+    );
+    assert_eq!(
+        decode(b"|1\r\n+ttl\r\n:10\r\n~1\r\n:1\r\n"),
+        Ok(Frame::Attribute(
+            vec![(Frame::Simple("ttl".to_string()), Frame::Integer(10))],
+            Box::new(Frame::Set(vec![Frame::Integer(1)]))
+        ))
     );
-    assert_ne!(Frame::validate(&mut into_cursor!(b"$0003\r\n")), Ok(()));
-    assert_ne!(Frame::validate(&mut into_cursor!(b"$0000\r\n")), Ok(()));
 }
 
 #[test]
-fn validation_array() {}
+fn parse_inline() {
+    let bulks = |args: &[&'static str]| {
+        Frame::Array(
+            args.iter()
+                .map(|a| Frame::Bulk(Bytes::from_static(a.as_bytes())))
+                .collect(),
+        )
+    };
+
+    assert_eq!(decode(b"PING\r\n"), Ok(bulks(&["PING"])));
+    assert_eq!(
+        decode(b"set  key\tvalue\n"),
+        Ok(bulks(&["set", "key", "value"]))
+    );
+    assert_eq!(
+        decode(b"set key \"hello world\\n\\x41\"\r\n"),
+        Ok(bulks(&["set", "key", "hello world\nA"]))
+    );
+    assert_eq!(
+        decode(b"set key 'it\\'s \"quoted\"'\r\n"),
+        Ok(bulks(&["set", "key", "it's \"quoted\""]))
+    );
+    assert_eq!(decode(b"set key \"\"\r\n"), Ok(bulks(&["set", "key", ""])));
+    assert_eq!(decode(b"\r\n\nPING\r\n"), Ok(bulks(&["PING"])));
+    assert!(is_incomplete(b"\r\n"));
+
+    assert!(is_incomplete(b"PING"));
+    assert_ne!(
+        decode(b"set key \"value\r\n"),
+        Ok(bulks(&["set", "key", "value"]))
+    );
+    assert!(decode(b"get \"key\"x\r\n").is_err());
+}
@@ -0,0 +1,35 @@
+use crate::server::glob::matches;
+
+#[test]
+fn glob_patterns() {
+    for (pattern, text) in [
+        ("*", ""),
+        ("news.*", "news.tech"),
+        ("h?llo", "hello"),
+        ("h*llo", "heeeello"),
+        ("h[ae]llo", "hallo"),
+        ("h[^e]llo", "hallo"),
+        ("h[a-c]llo", "hbllo"),
+        ("a*b*c", "aXbYbZc"),
+        ("\\*", "*"),
+    ] {
+        assert!(
+            matches(pattern.as_bytes(), text.as_bytes()),
+            "{pattern} {text}"
+        );
+    }
+
+    for (pattern, text) in [
+        ("news.*", "sport.news"),
+        ("h?llo", "hllo"),
+        ("h[^e]llo", "hello"),
+        ("h[a-c]llo", "hello"),
+        ("a*b", "abc"),
+        ("\\*", "a"),
+    ] {
+        assert!(
+            !matches(pattern.as_bytes(), text.as_bytes()),
+            "{pattern} {text}"
+        );
+    }
+}
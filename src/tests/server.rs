@@ -0,0 +1,539 @@
+use std::time::Duration;
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::oneshot,
+    time::timeout,
+};
+
+use crate::server::{config::Config, serve};
+
+#[tokio::test]
+async fn shutdown_closes_subscribers() {
+    let tcp_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = tcp_listener.local_addr().unwrap();
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+    let server =
+        tokio::spawn(async move { serve(tcp_listener, &Config::default(), shutdown_rx).await });
+
+    let mut client = TcpStream::connect(addr).await.unwrap();
+    client
+        .write_all(b"*2\r\n$9\r\nsubscribe\r\n$4\r\nnews\r\n")
+        .await
+        .unwrap();
+
+    let mut buf = [0; 64];
+    let n = client.read(&mut buf).await.unwrap();
+    assert_eq!(&buf[..n], b"*3\r\n$9\r\nsubscribe\r\n$4\r\nnews\r\n:1\r\n");
+
+    shutdown_tx.send(()).unwrap();
+
+    let res = timeout(Duration::from_secs(5), server).await;
+    assert!(matches!(res, Ok(Ok(Ok(())))));
+
+    // the subscriber is disconnected rather than left hanging
+    assert_eq!(client.read(&mut buf).await.unwrap(), 0);
+    assert!(TcpStream::connect(addr).await.is_err());
+}
+
+#[tokio::test]
+async fn shutdown_does_not_wait_for_partial_requests() {
+    let tcp_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = tcp_listener.local_addr().unwrap();
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+    let server =
+        tokio::spawn(async move { serve(tcp_listener, &Config::default(), shutdown_rx).await });
+
+    // a client in the middle of sending a command
+    let mut client = TcpStream::connect(addr).await.unwrap();
+    client.write_all(b"*2\r\n$3\r\nget\r\n").await.unwrap();
+
+    shutdown_tx.send(()).unwrap();
+
+    let res = timeout(Duration::from_secs(5), server).await;
+    assert!(matches!(res, Ok(Ok(Ok(())))));
+}
+
+/// Sends `req` and returns whatever the server replies with first.
+async fn request(client: &mut TcpStream, req: &[u8]) -> Vec<u8> {
+    client.write_all(req).await.unwrap();
+
+    let mut buf = vec![0; 1024];
+    let n = client.read(&mut buf).await.unwrap();
+    buf.truncate(n);
+    buf
+}
+
+#[tokio::test]
+async fn connection_limit() {
+    let tcp_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = tcp_listener.local_addr().unwrap();
+    let (_shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+    let config = Config {
+        max_connections: 1,
+        ..Config::default()
+    };
+
+    tokio::spawn(async move { serve(tcp_listener, &config, shutdown_rx).await });
+
+    let mut first = TcpStream::connect(addr).await.unwrap();
+    assert_eq!(request(&mut first, b"PING\r\n").await, b"+PONG\r\n");
+
+    let info = request(&mut first, b"INFO clients\r\n").await;
+    let info = String::from_utf8(info).unwrap();
+    assert!(info.contains("connected_clients:1\r\n"));
+    assert!(info.contains("maxclients:1\r\n"));
+    assert!(!info.contains("# Server"));
+
+    let mut second = TcpStream::connect(addr).await.unwrap();
+    assert_eq!(
+        request(&mut second, b"PING\r\n").await,
+        b"-ERR max number of clients reached\r\n"
+    );
+
+    // the slot is given back once the first client disconnects
+    drop(first);
+
+    let pong = timeout(Duration::from_secs(5), async {
+        loop {
+            let mut client = TcpStream::connect(addr).await.unwrap();
+            let res = request(&mut client, b"PING\r\n").await;
+
+            if res == b"+PONG\r\n" {
+                return res;
+            }
+
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await;
+    assert!(pong.is_ok());
+}
+
+#[tokio::test]
+async fn blocking_pops() {
+    let tcp_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = tcp_listener.local_addr().unwrap();
+    let (_shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+    tokio::spawn(async move { serve(tcp_listener, &Config::default(), shutdown_rx).await });
+
+    let mut first = TcpStream::connect(addr).await.unwrap();
+    let mut second = TcpStream::connect(addr).await.unwrap();
+    let mut pusher = TcpStream::connect(addr).await.unwrap();
+
+    assert_eq!(
+        request(&mut first, b"BLPOP list 0.05\r\n").await,
+        b"*-1\r\n"
+    );
+
+    // waiters are served in the order they blocked
+    first.write_all(b"BLPOP other list 0\r\n").await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    second.write_all(b"BRPOP list 0\r\n").await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert_eq!(
+        request(&mut pusher, b"RPUSH list a b c\r\n").await,
+        b":3\r\n"
+    );
+
+    let mut buf = [0; 64];
+    let n = first.read(&mut buf).await.unwrap();
+    assert_eq!(&buf[..n], b"*2\r\n$4\r\nlist\r\n$1\r\na\r\n");
+    let n = second.read(&mut buf).await.unwrap();
+    assert_eq!(&buf[..n], b"*2\r\n$4\r\nlist\r\n$1\r\nc\r\n");
+
+    // a client that disconnects while blocked is not served
+    first
+        .write_all(b"BLMOVE src list LEFT LEFT 0\r\n")
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    drop(first);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert_eq!(request(&mut pusher, b"RPUSH src x\r\n").await, b":1\r\n");
+    assert_eq!(
+        request(&mut pusher, b"LRANGE list 0 -1\r\n").await,
+        b"*1\r\n$1\r\nb\r\n"
+    );
+}
+
+#[tokio::test]
+async fn stream_consumer_groups() {
+    let tcp_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = tcp_listener.local_addr().unwrap();
+    let (_shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+    tokio::spawn(async move { serve(tcp_listener, &Config::default(), shutdown_rx).await });
+
+    let mut alice = TcpStream::connect(addr).await.unwrap();
+    let mut bob = TcpStream::connect(addr).await.unwrap();
+    let mut producer = TcpStream::connect(addr).await.unwrap();
+
+    assert_eq!(
+        request(&mut producer, b"XADD s 1-1 f v\r\n").await,
+        b"$3\r\n1-1\r\n"
+    );
+    assert_eq!(
+        request(&mut producer, b"XGROUP CREATE s g 0\r\n").await,
+        b"+OK\r\n"
+    );
+    assert_eq!(
+        request(
+            &mut alice,
+            b"XREADGROUP GROUP g alice COUNT 5 STREAMS s >\r\n"
+        )
+        .await,
+        b"*1\r\n*2\r\n$1\r\ns\r\n*1\r\n*2\r\n$3\r\n1-1\r\n*2\r\n$1\r\nf\r\n$1\r\nv\r\n"
+    );
+
+    // a blocked consumer is delivered the next entry
+    bob.write_all(b"XREADGROUP GROUP g bob BLOCK 0 STREAMS s >\r\n")
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert_eq!(
+        request(&mut producer, b"XADD s 2-1 f w\r\n").await,
+        b"$3\r\n2-1\r\n"
+    );
+
+    let mut buf = [0; 128];
+    let n = bob.read(&mut buf).await.unwrap();
+    assert_eq!(
+        &buf[..n],
+        b"*1\r\n*2\r\n$1\r\ns\r\n*1\r\n*2\r\n$3\r\n2-1\r\n*2\r\n$1\r\nf\r\n$1\r\nw\r\n"
+    );
+
+    // entries stay pending until acknowledged
+    assert_eq!(
+        request(&mut producer, b"XPENDING s g\r\n").await,
+        b"*4\r\n:2\r\n$3\r\n1-1\r\n$3\r\n2-1\r\n*2\r\n*2\r\n$5\r\nalice\r\n$1\r\n1\r\n*2\r\n$3\r\nbob\r\n$1\r\n1\r\n"
+    );
+    assert_eq!(
+        request(&mut alice, b"XACK s g 1-1 1-1\r\n").await,
+        b":1\r\n"
+    );
+    assert_eq!(
+        request(&mut producer, b"XPENDING s g - + 10 alice\r\n").await,
+        b"*0\r\n"
+    );
+}
+
+#[tokio::test]
+async fn pattern_subscriptions() {
+    let tcp_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = tcp_listener.local_addr().unwrap();
+    let (_shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+    tokio::spawn(async move { serve(tcp_listener, &Config::default(), shutdown_rx).await });
+
+    let mut subscriber = TcpStream::connect(addr).await.unwrap();
+    let mut publisher = TcpStream::connect(addr).await.unwrap();
+
+    assert_eq!(
+        request(&mut subscriber, b"PSUBSCRIBE news.*\r\n").await,
+        b"*3\r\n$10\r\npsubscribe\r\n$6\r\nnews.*\r\n:1\r\n"
+    );
+    assert_eq!(
+        request(&mut subscriber, b"SUBSCRIBE news.tech\r\n").await,
+        b"*3\r\n$9\r\nsubscribe\r\n$9\r\nnews.tech\r\n:2\r\n"
+    );
+
+    // the channel and the pattern both receive the message
+    assert_eq!(
+        request(&mut publisher, b"PUBLISH news.tech hi\r\n").await,
+        b":2\r\n"
+    );
+
+    let message: &[u8] = b"*3\r\n$7\r\nmessage\r\n$9\r\nnews.tech\r\n$2\r\nhi\r\n";
+    let pmessage: &[u8] =
+        b"*4\r\n$8\r\npmessage\r\n$6\r\nnews.*\r\n$9\r\nnews.tech\r\n$2\r\nhi\r\n";
+    let mut received = vec![];
+
+    while received.len() < message.len() + pmessage.len() {
+        let mut buf = [0; 128];
+        let n = subscriber.read(&mut buf).await.unwrap();
+        received.extend_from_slice(&buf[..n]);
+    }
+
+    // the two subscriptions are served in no particular order
+    assert!(received == [message, pmessage].concat() || received == [pmessage, message].concat());
+
+    assert_eq!(
+        request(&mut subscriber, b"PUNSUBSCRIBE\r\n").await,
+        b"*3\r\n$12\r\npunsubscribe\r\n$6\r\nnews.*\r\n:1\r\n"
+    );
+    assert_eq!(
+        request(&mut publisher, b"PUBLISH news.sport hi\r\n").await,
+        b":0\r\n"
+    );
+    assert_eq!(
+        request(&mut subscriber, b"GET key\r\n").await,
+        b"-ERR only (P)SUBSCRIBE / (P)UNSUBSCRIBE / PING are allowed in this context\r\n"
+    );
+}
+
+#[tokio::test]
+async fn hello_negotiates_protocol() {
+    let tcp_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = tcp_listener.local_addr().unwrap();
+    let (_shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+    tokio::spawn(async move { serve(tcp_listener, &Config::default(), shutdown_rx).await });
+
+    let mut client = TcpStream::connect(addr).await.unwrap();
+
+    // connections start out speaking RESP2, which flattens the reply into an array
+    let res = request(&mut client, b"HELLO\r\n").await;
+    assert!(res.starts_with(b"*12\r\n$6\r\nserver\r\n"));
+    assert!(res.ends_with(b"$5\r\nproto\r\n:2\r\n$4\r\nmode\r\n$10\r\nstandalone\r\n$4\r\nrole\r\n$6\r\nmaster\r\n$7\r\nmodules\r\n*0\r\n"));
+    assert_eq!(request(&mut client, b"GET missing\r\n").await, b"$-1\r\n");
+
+    assert_eq!(
+        request(&mut client, b"HELLO 4\r\n").await,
+        b"-NOPROTO unsupported protocol version\r\n"
+    );
+
+    // no password is configured, so credentials are never accepted
+    assert!(request(&mut client, b"HELLO 3 AUTH default secret\r\n")
+        .await
+        .starts_with(b"-ERR AUTH <password> called without any password configured"));
+    assert!(request(&mut client, b"HELLO 3 AUTH alice secret\r\n")
+        .await
+        .starts_with(b"-WRONGPASS"));
+
+    let res = request(&mut client, b"HELLO 3\r\n").await;
+    assert!(res.starts_with(b"%6\r\n$6\r\nserver\r\n"));
+    assert!(res.ends_with(b"$7\r\nmodules\r\n*0\r\n"));
+    assert_eq!(request(&mut client, b"GET missing\r\n").await, b"_\r\n");
+}
+
+#[tokio::test]
+async fn subscriptions_are_pushed_under_resp3() {
+    let tcp_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = tcp_listener.local_addr().unwrap();
+    let (_shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+    tokio::spawn(async move { serve(tcp_listener, &Config::default(), shutdown_rx).await });
+
+    let mut subscriber = TcpStream::connect(addr).await.unwrap();
+    let mut publisher = TcpStream::connect(addr).await.unwrap();
+
+    assert!(request(&mut subscriber, b"HELLO 3\r\n")
+        .await
+        .starts_with(b"%"));
+    assert_eq!(
+        request(&mut subscriber, b"SUBSCRIBE news\r\n").await,
+        b">3\r\n$9\r\nsubscribe\r\n$4\r\nnews\r\n:1\r\n"
+    );
+    assert_eq!(
+        request(&mut publisher, b"PUBLISH news hi\r\n").await,
+        b":1\r\n"
+    );
+
+    let mut buf = [0; 64];
+    let n = subscriber.read(&mut buf).await.unwrap();
+    assert_eq!(
+        &buf[..n],
+        b">3\r\n$7\r\nmessage\r\n$4\r\nnews\r\n$2\r\nhi\r\n"
+    );
+
+    // RESP3 clients get their pongs as usual
+    assert_eq!(request(&mut subscriber, b"PING\r\n").await, b"+PONG\r\n");
+}
+
+#[tokio::test]
+async fn huge_set_counts() {
+    let tcp_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = tcp_listener.local_addr().unwrap();
+    let (_shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+    tokio::spawn(async move { serve(tcp_listener, &Config::default(), shutdown_rx).await });
+
+    let mut client = TcpStream::connect(addr).await.unwrap();
+    let mut other = TcpStream::connect(addr).await.unwrap();
+
+    assert_eq!(request(&mut client, b"SADD set a b\r\n").await, b":2\r\n");
+
+    // counts are capped at the size of the set rather than allocated for
+    let res = request(&mut client, b"SRANDMEMBER set 9223372036854775807\r\n").await;
+    assert!(res.starts_with(b"*2\r\n"));
+    assert_eq!(
+        request(&mut client, b"SRANDMEMBER set -9223372036854775807\r\n").await,
+        b"-ERR value is out of range\r\n"
+    );
+    let res = request(&mut client, b"SPOP set 9223372036854775807\r\n").await;
+    assert!(res.starts_with(b"*2\r\n"));
+
+    // the server is still serving every client
+    assert_eq!(request(&mut other, b"GET x\r\n").await, b"$-1\r\n");
+}
+
+#[tokio::test]
+async fn blocked_clients_buffer_a_bounded_backlog() {
+    let tcp_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = tcp_listener.local_addr().unwrap();
+    let (_shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+    let config = Config {
+        proto_max_bulk_len: 16,
+        proto_max_inline_len: 16,
+        ..Config::default()
+    };
+
+    tokio::spawn(async move { serve(tcp_listener, &config, shutdown_rx).await });
+
+    let mut client = TcpStream::connect(addr).await.unwrap();
+    client.write_all(b"BLPOP list 0\r\n").await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    // requests pipelined behind the blocked one may not outgrow a single request
+    let res = request(&mut client, "PING\r\n".repeat(10).as_bytes()).await;
+    assert!(res.starts_with(b"-ERR Limit Exceeded"));
+
+    let mut buf = [0; 64];
+    assert_eq!(client.read(&mut buf).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn leaving_subscribed_mode() {
+    let tcp_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = tcp_listener.local_addr().unwrap();
+    let (_shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+    tokio::spawn(async move { serve(tcp_listener, &Config::default(), shutdown_rx).await });
+
+    let mut client = TcpStream::connect(addr).await.unwrap();
+
+    let subscribed: &[u8] = b"*3\r\n$9\r\nsubscribe\r\n$1\r\na\r\n:1\r\n\
+        *3\r\n$9\r\nsubscribe\r\n$1\r\nb\r\n:2\r\n";
+    client.write_all(b"SUBSCRIBE a b\r\n").await.unwrap();
+    let mut buf = vec![0; subscribed.len()];
+    client.read_exact(&mut buf).await.unwrap();
+    assert_eq!(buf, subscribed);
+
+    // RESP2 clients are pushed their pongs while subscribed
+    assert_eq!(
+        request(&mut client, b"PING\r\n").await,
+        b"*2\r\n$4\r\npong\r\n$0\r\n\r\n"
+    );
+    assert_eq!(
+        request(&mut client, b"PING hi\r\n").await,
+        b"*2\r\n$4\r\npong\r\n$2\r\nhi\r\n"
+    );
+
+    assert_eq!(
+        request(&mut client, b"UNSUBSCRIBE a\r\n").await,
+        b"*3\r\n$11\r\nunsubscribe\r\n$1\r\na\r\n:1\r\n"
+    );
+    assert_eq!(
+        request(&mut client, b"UNSUBSCRIBE\r\n").await,
+        b"*3\r\n$11\r\nunsubscribe\r\n$1\r\nb\r\n:0\r\n"
+    );
+
+    // with no subscription left, the client is back to issuing any command
+    assert_eq!(request(&mut client, b"GET key\r\n").await, b"$-1\r\n");
+    assert_eq!(request(&mut client, b"PING\r\n").await, b"+PONG\r\n");
+}
+
+#[tokio::test]
+async fn malformed_requests_while_subscribed() {
+    let tcp_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = tcp_listener.local_addr().unwrap();
+    let (_shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+    tokio::spawn(async move { serve(tcp_listener, &Config::default(), shutdown_rx).await });
+
+    let mut client = TcpStream::connect(addr).await.unwrap();
+
+    assert_eq!(
+        request(&mut client, b"SUBSCRIBE a\r\n").await,
+        b"*3\r\n$9\r\nsubscribe\r\n$1\r\na\r\n:1\r\n"
+    );
+
+    // the client is told why before the connection is closed
+    let res = request(&mut client, b"*1\r\n$x\r\n").await;
+    assert!(res.starts_with(b"-ERR "));
+
+    let mut buf = [0; 64];
+    assert_eq!(client.read(&mut buf).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn strings_grow_up_to_the_configured_limit() {
+    let tcp_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = tcp_listener.local_addr().unwrap();
+    let (_shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+    let config = Config {
+        proto_max_bulk_len: 16,
+        ..Config::default()
+    };
+
+    tokio::spawn(async move { serve(tcp_listener, &config, shutdown_rx).await });
+
+    let mut client = TcpStream::connect(addr).await.unwrap();
+    let too_long: &[u8] = b"-ERR string exceeds maximum allowed size (proto-max-bulk-len)\r\n";
+
+    assert_eq!(
+        request(&mut client, b"APPEND k 0123456789\r\n").await,
+        b":10\r\n"
+    );
+    assert_eq!(
+        request(&mut client, b"APPEND k 0123456789\r\n").await,
+        too_long
+    );
+    assert_eq!(
+        request(&mut client, b"SETRANGE k 15 xy\r\n").await,
+        too_long
+    );
+    assert_eq!(request(&mut client, b"SETBIT k 127 1\r\n").await, b":0\r\n");
+    assert_eq!(
+        request(&mut client, b"SETBIT k 128 1\r\n").await,
+        b"-ERR bit offset is not an integer or out of range\r\n"
+    );
+    assert_eq!(
+        request(&mut client, b"BITFIELD k GET u8 128\r\n").await,
+        b"-ERR bit offset is not an integer or out of range\r\n"
+    );
+}
+
+#[tokio::test]
+async fn only_canonical_integers_are_incremented() {
+    let tcp_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = tcp_listener.local_addr().unwrap();
+    let (_shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+    tokio::spawn(async move { serve(tcp_listener, &Config::default(), shutdown_rx).await });
+
+    let mut client = TcpStream::connect(addr).await.unwrap();
+
+    for value in ["007", "-0", "+5", "-", "", " 1"] {
+        let set = format!(
+            "*3\r\n$3\r\nSET\r\n$1\r\nk\r\n${}\r\n{value}\r\n",
+            value.len()
+        );
+        assert_eq!(request(&mut client, set.as_bytes()).await, b"+OK\r\n");
+        assert_eq!(
+            request(&mut client, b"INCR k\r\n").await,
+            b"-ERR value is not an integer or out of range\r\n",
+            "{value:?}"
+        );
+    }
+
+    for (value, incremented) in [("0", ":1\r\n"), ("-10", ":-9\r\n"), ("10", ":11\r\n")] {
+        let set = format!("SET k {value}\r\n");
+        assert_eq!(request(&mut client, set.as_bytes()).await, b"+OK\r\n");
+        assert_eq!(
+            request(&mut client, b"INCR k\r\n").await,
+            incremented.as_bytes()
+        );
+    }
+}